@@ -1,3 +1,17 @@
+use std::process::Command;
+
 fn main() {
+    // 將目前的 git commit hash 寫入編譯環境，供診斷資訊顯示
+    if let Ok(output) = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+    {
+        if output.status.success() {
+            let hash = String::from_utf8_lossy(&output.stdout);
+            println!("cargo:rustc-env=BUILD_GIT_HASH={}", hash.trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+
     tauri_build::build()
 }
@@ -0,0 +1,120 @@
+//! 錄製與重播：`record` 將一次分析的所有回應與結果快照存成目錄，
+//! `replay` 不連網以同樣的回應重新執行完整流程，並比對結果是否與快照相同，
+//! 用來在網站改版或修改解析規則後檢查解析結果是否退化。
+//! `tests/bundles` 中提交的錄製目錄由 `cargo test` 重播。
+//!
+//! 目錄結構：
+//! - `payload.json`：分析參數
+//! - `responses/`：錄製的回應 (格式同 `FixtureFetcher`)
+//! - `snapshot.json`：結果快照；比對不符時另外寫出 `snapshot.actual.json`
+
+use crate::{analyze, CliError};
+use ptt_analyzer_core::analysis::AnalyzePayload;
+use ptt_analyzer_core::fetcher::{FixtureFetcher, HttpFetcher, RecordingFetcher};
+use ptt_analyzer_core::jobs::EventSink;
+use ptt_analyzer_core::report::AnalysisResult;
+use serde_json::Value;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const PAYLOAD_FILE: &str = "payload.json";
+const RESPONSES_DIR: &str = "responses";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const ACTUAL_SNAPSHOT_FILE: &str = "snapshot.actual.json";
+/// 每次執行都不同、不列入快照的欄位。
+const VOLATILE_FIELDS: [&str; 2] = ["jobId", "diagnostics"];
+
+/// 實際連網分析並錄製所有回應。不使用快取，確保每個請求都被錄下。
+pub async fn record(
+    mut payload: AnalyzePayload,
+    bundle: &Path,
+    sink: Arc<dyn EventSink>,
+) -> Result<ExitCode, CliError> {
+    // 重播時不會連線 term.ptt.cc；也避免帳號密碼與 token 被寫進錄製檔
+    payload.options.config.ptt_term = Default::default();
+    payload.options.config.http_api = Default::default();
+
+    std::fs::create_dir_all(bundle).map_err(CliError::file(bundle))?;
+    write_json(&bundle.join(PAYLOAD_FILE), &payload)?;
+
+    let fetcher = RecordingFetcher::new(Box::new(HttpFetcher), bundle.join(RESPONSES_DIR));
+    let result = analyze(sink, None, &fetcher, payload).await?;
+    write_json(&bundle.join(SNAPSHOT_FILE), &snapshot(&result))?;
+    eprintln!("已錄製到 {}", bundle.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 以錄製的回應重新分析並比對快照；不符時列出不同的欄位並以非零的結束碼結束。
+pub async fn replay(
+    bundle: &Path,
+    update: bool,
+    sink: Arc<dyn EventSink>,
+) -> Result<ExitCode, CliError> {
+    let payload: AnalyzePayload = read_json(&bundle.join(PAYLOAD_FILE))?;
+    let fetcher = FixtureFetcher::new(bundle.join(RESPONSES_DIR));
+    let actual = snapshot(&analyze(sink, None, &fetcher, payload).await?);
+
+    let snapshot_path = bundle.join(SNAPSHOT_FILE);
+    if update {
+        write_json(&snapshot_path, &actual)?;
+        eprintln!("已更新快照 {}", snapshot_path.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let expected: Value = read_json(&snapshot_path)?;
+    let differences = differing_fields(&expected, &actual);
+    if differences.is_empty() {
+        eprintln!("結果與快照相同");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let actual_path = bundle.join(ACTUAL_SNAPSHOT_FILE);
+    write_json(&actual_path, &actual)?;
+    eprintln!(
+        "結果與快照不同：{}\n這次的結果已寫入 {}",
+        differences.join(", "),
+        actual_path.display()
+    );
+    Ok(ExitCode::FAILURE)
+}
+
+/// 可比對的結果快照：移除每次執行都不同的欄位，
+/// 並將資料列依使用者排序 (排序鍵相同的資料列順序不固定)。
+fn snapshot(result: &AnalysisResult) -> Value {
+    let mut value = serde_json::to_value(result).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        for field in VOLATILE_FIELDS {
+            fields.remove(field);
+        }
+        for key in ["highlightedData", "normalData"] {
+            if let Some(Value::Array(rows)) = fields.get_mut(key) {
+                rows.sort_by(|a, b| a["user"].as_str().cmp(&b["user"].as_str()));
+            }
+        }
+    }
+    value
+}
+
+/// 兩份快照中值不同的頂層欄位。
+fn differing_fields(expected: &Value, actual: &Value) -> Vec<String> {
+    let (Some(expected), Some(actual)) = (expected.as_object(), actual.as_object()) else {
+        return vec!["<root>".to_string()];
+    };
+    let mut keys: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| expected.get(*key) != actual.get(*key))
+        .cloned()
+        .collect()
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let content = std::fs::read(path).map_err(CliError::file(path))?;
+    serde_json::from_slice(&content).map_err(|e| CliError::file(path)(e.into()))
+}
+
+fn write_json(path: &Path, value: &impl serde::Serialize) -> Result<(), CliError> {
+    let content = serde_json::to_vec_pretty(value).unwrap_or_default();
+    std::fs::write(path, content).map_err(CliError::file(path))
+}
@@ -0,0 +1,303 @@
+//! 命令列版的分析工具：不開啟視窗即可分析文章，供腳本與排程 (cron) 使用。
+//!
+//! 報告輸出到 stdout (或 `--output` 指定的檔案)，進度與錯誤訊息輸出到 stderr，
+//! 分析失敗時以非零的結束碼結束。`record` 與 `replay` 子命令用於解析結果的回歸檢查，見 [`bundle`]。
+
+mod bundle;
+mod output;
+mod progress;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ptt_analyzer_core::analysis::{self, AnalysisOptions, AnalysisState, AnalyzePayload};
+use ptt_analyzer_core::backoff::RateLimiters;
+use ptt_analyzer_core::config::{self, ConcurrentAnalysisPolicy};
+use ptt_analyzer_core::diagnostics::DiagnosticsState;
+use ptt_analyzer_core::disk_cache::DiskCache;
+use ptt_analyzer_core::domain::BoardName;
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::fetcher::{Fetcher, HttpFetcher};
+use ptt_analyzer_core::history::AppearanceHistory;
+use ptt_analyzer_core::jobs::{EventSink, JobEmitter, JobManager};
+use ptt_analyzer_core::notes::NoteStore;
+use ptt_analyzer_core::providers::UserStatsCache;
+use ptt_analyzer_core::report::AnalysisResult;
+use ptt_analyzer_core::trends::UserTrendStore;
+use ptt_analyzer_core::watchlists::WatchlistStore;
+use ptt_analyzer_core::{analyzers, scraper, selectors};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(
+    name = "ptt-analyzer",
+    version,
+    about = "分析 PTT 文章的留言者在各看板的留言數"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// selectors 覆寫檔 (與桌面 app 的 selectors.toml 格式相同)
+    #[arg(long, global = true)]
+    selectors: Option<PathBuf>,
+    /// 不在 stderr 顯示進度
+    #[arg(long, short, global = true)]
+    quiet: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 分析一篇文章並輸出報告
+    Analyze(AnalyzeArgs),
+    /// 分析一篇文章，並將所有回應與結果快照錄製到目錄中，供之後重播比對
+    Record(RecordArgs),
+    /// 以錄製的回應重新執行分析 (不連網)，比對結果是否與快照相同
+    Replay(ReplayArgs),
+}
+
+/// 決定分析內容的參數，`analyze` 與 `record` 共用。
+#[derive(Args)]
+struct AnalysisArgs {
+    /// 文章網址、`#AID (看板)` 形式的文章代碼，或本機 HTML 檔
+    url: String,
+    /// 要統計留言數的看板，以逗號分隔；未指定時使用設定檔中的看板
+    #[arg(long, value_delimiter = ',')]
+    boards: Vec<BoardName>,
+    /// 設定檔 (與桌面 app 的 config.json 格式相同)，未指定時使用預設設定
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// 只計算指定類型的推文 (push、hate、arrow)，以逗號分隔
+    #[arg(long = "types", value_delimiter = ',')]
+    filter_types: Vec<String>,
+    /// 只計算包含任一關鍵字的推文，以逗號分隔
+    #[arg(long, value_delimiter = ',')]
+    keywords: Vec<String>,
+    /// 推文內容的最少字數
+    #[arg(long)]
+    min_length: Option<usize>,
+    /// 推文內容的最多字數
+    #[arg(long)]
+    max_length: Option<usize>,
+    /// 將同一使用者連續的 → 推文合併為一則留言
+    #[arg(long)]
+    merge_arrows: bool,
+    /// 另外查詢每位留言者在目標看板的近期發文 (每位留言者多一次請求)
+    #[arg(long)]
+    recent_articles: bool,
+    /// 保留每位留言者所有看板的留言數，並列出留言者共同活躍的其他看板
+    #[arg(long)]
+    full_board_distribution: bool,
+    /// 高亮條件，格式為 `看板,運算子,數值`，例如 `HatePolitics,>=,50%`
+    #[arg(long)]
+    highlight: Option<String>,
+    /// 整體分析的時限 (秒)，超過後以現有資料輸出部分報告
+    #[arg(long)]
+    max_duration_secs: Option<u64>,
+}
+
+#[derive(Args)]
+struct AnalyzeArgs {
+    #[command(flatten)]
+    analysis: AnalysisArgs,
+    /// 報告格式
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// 報告的輸出檔案，未指定時輸出到 stdout
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// 快取目錄，可與先前的執行共用查詢結果；未指定時不讀寫快取
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    #[command(flatten)]
+    analysis: AnalysisArgs,
+    /// 錄製目錄，已存在的檔案會被覆寫
+    #[arg(long)]
+    bundle: PathBuf,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    /// 以 `record` 建立的錄製目錄
+    bundle: PathBuf,
+    /// 以這次的結果更新快照，而不是比對 (解析規則有意變更時使用)
+    #[arg(long)]
+    update: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// 每位留言者一列，高亮的留言者在前
+    Csv,
+    /// 完整的分析結果 (與桌面 app 的報告紀錄相同)
+    Json,
+}
+
+/// 命令列工具的錯誤：分析本身失敗，或讀寫檔案失敗。
+enum CliError {
+    Analysis(Error),
+    File { path: PathBuf, source: io::Error },
+}
+
+impl CliError {
+    fn file(path: &Path) -> impl FnOnce(io::Error) -> CliError + '_ {
+        move |source| CliError::File {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> Self {
+        CliError::Analysis(error)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Analysis(e) => write!(f, "錯誤 [{}]：{}", e.code(), e),
+            CliError::File { path, source } => write!(f, "無法讀寫 {}：{}", path.display(), source),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // 日誌與進度同樣輸出到 stderr，預設只顯示警告以免蓋過進度
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .init();
+
+    if let Some(path) = &cli.selectors {
+        if let Err(e) = selectors::reload(Some(path)) {
+            eprintln!("{}", CliError::from(e));
+            return ExitCode::FAILURE;
+        }
+    }
+    let sink: Arc<dyn EventSink> = if cli.quiet {
+        Arc::new(progress::Silent)
+    } else {
+        Arc::new(progress::StderrProgress::new())
+    };
+
+    let outcome = match cli.command {
+        Command::Analyze(args) => run_analyze(args, sink).await,
+        Command::Record(args) => {
+            bundle::record(build_payload(args.analysis), &args.bundle, sink).await
+        }
+        Command::Replay(args) => bundle::replay(&args.bundle, args.update, sink).await,
+    };
+    outcome.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        ExitCode::FAILURE
+    })
+}
+
+async fn run_analyze(args: AnalyzeArgs, sink: Arc<dyn EventSink>) -> Result<ExitCode, CliError> {
+    let payload = build_payload(args.analysis);
+    let boards = payload.options.config.boards.clone();
+    let result = analyze(sink, args.cache_dir.as_deref(), &HttpFetcher, payload).await?;
+
+    match &args.output {
+        Some(path) => std::fs::File::create(path)
+            .and_then(|file| output::write_report(file, &result, &boards, args.format))
+            .map_err(CliError::file(path))?,
+        None => output::write_report(std::io::stdout().lock(), &result, &boards, args.format)
+            .map_err(CliError::file(Path::new("<stdout>")))?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 由命令列參數與設定檔組出分析參數，命令列參數優先。
+fn build_payload(args: AnalysisArgs) -> AnalyzePayload {
+    let mut app_config = args
+        .config
+        .as_deref()
+        .map(config::load_from)
+        .unwrap_or_default();
+    if !args.boards.is_empty() {
+        app_config.boards = args.boards;
+    }
+
+    AnalyzePayload {
+        url: args.url,
+        options: AnalysisOptions {
+            filter_types: args.filter_types,
+            keywords: Some(args.keywords).filter(|k| !k.is_empty()),
+            min_content_length: args.min_length,
+            max_content_length: args.max_length,
+            merge_arrow_continuations: args.merge_arrows,
+            fetch_recent_articles: args.recent_articles,
+            full_board_distribution: args.full_board_distribution,
+            push_range_start: None,
+            push_range_end: None,
+            highlight_condition: args.highlight,
+            analyzers: analyzers::default_analyzers(),
+            max_duration_secs: args.max_duration_secs,
+            analyze_forwarded_origin: false,
+            config: app_config,
+        },
+    }
+}
+
+/// 以單次執行專用的狀態執行分析；收到 Ctrl-C 時取消工作。
+async fn analyze(
+    sink: Arc<dyn EventSink>,
+    cache_dir: Option<&Path>,
+    fetcher: &dyn Fetcher,
+    payload: AnalyzePayload,
+) -> Result<AnalysisResult, Error> {
+    let jobs = JobManager::default();
+    let diagnostics = DiagnosticsState::default();
+    let rate_limiters = RateLimiters::default();
+    let cache = UserStatsCache::load(cache_dir);
+    let lookups = scraper::UserPageLookups::default();
+    let disk_cache = DiskCache::new(cache_dir);
+    let watchlists = WatchlistStore::default();
+    let notes = NoteStore::default();
+    let history = AppearanceHistory::default();
+    let trends = UserTrendStore::default();
+    let state = AnalysisState {
+        jobs: &jobs,
+        diagnostics: &diagnostics,
+        rate_limiters: &rate_limiters,
+        cache: &cache,
+        lookups: &lookups,
+        disk_cache: &disk_cache,
+        fetcher,
+        watchlists: &watchlists,
+        notes: &notes,
+        history: &history,
+        trends: &trends,
+    };
+
+    let (job_id, control) = jobs.register(&payload.url);
+    let cancel = control.cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancel.cancel();
+        }
+    });
+
+    let emitter = JobEmitter::new(sink, job_id);
+    analysis::execute_job(
+        &state,
+        &emitter,
+        control,
+        payload,
+        ConcurrentAnalysisPolicy::Queue,
+    )
+    .await
+}
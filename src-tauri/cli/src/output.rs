@@ -0,0 +1,51 @@
+use crate::Format;
+use ptt_analyzer_core::domain::BoardName;
+use ptt_analyzer_core::report::AnalysisResult;
+use std::io::{self, Write};
+
+/// 將分析結果依指定格式寫出。
+pub fn write_report(
+    writer: impl Write,
+    result: &AnalysisResult,
+    boards: &[BoardName],
+    format: Format,
+) -> io::Result<()> {
+    match format {
+        Format::Csv => write_csv(writer, result, boards),
+        Format::Json => {
+            let mut writer = writer;
+            serde_json::to_writer_pretty(&mut writer, result)?;
+            writeln!(writer)
+        }
+    }
+}
+
+/// 每位留言者一列，欄位為使用者、本文留言數、各看板留言數、生涯總留言數與是否高亮。
+/// 順序與 app 中的表格相同：高亮的資料列在前，各自依設定排序。
+fn write_csv(writer: impl Write, result: &AnalysisResult, boards: &[BoardName]) -> io::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+
+    let mut header = vec!["使用者".to_string(), "本文留言數".to_string()];
+    header.extend(boards.iter().map(BoardName::to_string));
+    header.extend(["生涯總留言數".to_string(), "高亮".to_string()]);
+    csv.write_record(&header)?;
+
+    let rows = result
+        .highlighted_data
+        .iter()
+        .map(|row| (row, true))
+        .chain(result.normal_data.iter().map(|row| (row, false)));
+    for (row, highlighted) in rows {
+        let mut record = vec![row.user.to_string(), row.article_comments.to_string()];
+        record.extend(boards.iter().map(|board| {
+            row.board_comments
+                .get(board)
+                .copied()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        record.extend([row.total_comments.to_string(), highlighted.to_string()]);
+        csv.write_record(&record)?;
+    }
+    csv.flush()
+}
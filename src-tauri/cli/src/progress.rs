@@ -0,0 +1,106 @@
+use ptt_analyzer_core::events;
+use ptt_analyzer_core::jobs::EventSink;
+use ptt_analyzer_core::progress::ProgressPayload;
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 不是終端機 (例如 cron 導向到日誌檔) 時，每完成多少百分比輸出一行進度。
+const LOG_PROGRESS_STEP_PERCENT: usize = 10;
+
+/// 將分析事件轉為 stderr 上的進度訊息。
+/// 終端機上以同一行更新查詢進度；否則每完成一段比例才輸出一行，避免日誌被洗版。
+pub struct StderrProgress {
+    interactive: bool,
+    /// 上次輸出進度時的百分比
+    last_percent: AtomicUsize,
+}
+
+impl StderrProgress {
+    pub fn new() -> Self {
+        StderrProgress {
+            interactive: std::io::stderr().is_terminal(),
+            last_percent: AtomicUsize::new(0),
+        }
+    }
+
+    fn progress(&self, progress: ProgressPayload) {
+        let line = format!(
+            "查詢留言者 {}/{} (失敗 {}，快取 {}){}",
+            progress.current,
+            progress.total,
+            progress.failed,
+            progress.cached,
+            progress
+                .eta_secs
+                .map(|secs| format!("，約剩 {} 秒", secs))
+                .unwrap_or_default()
+        );
+        if self.interactive {
+            // 清除該行後重寫，避免較短的訊息留下前一次的殘字
+            eprint!("\r\x1b[K{}", line);
+            let _ = std::io::stderr().flush();
+            return;
+        }
+        let percent = progress.current * 100 / progress.total.max(1);
+        let step = percent / LOG_PROGRESS_STEP_PERCENT * LOG_PROGRESS_STEP_PERCENT;
+        if self.last_percent.fetch_max(step, Ordering::Relaxed) < step {
+            eprintln!("{}", line);
+        }
+    }
+
+    /// 輸出獨立一行的訊息；終端機上先結束正在更新的進度列。
+    fn line(&self, message: String) {
+        if self.interactive {
+            eprintln!("\r\x1b[K{}", message);
+        } else {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+impl EventSink for StderrProgress {
+    fn emit(&self, event: &str, payload: Value) {
+        let text = |key: &str| payload[key].as_str().unwrap_or_default().to_string();
+        let number = |key: &str| payload[key].as_u64().unwrap_or_default();
+        match event {
+            events::ARTICLE_SCRAPE_STARTED => self.line(format!("抓取文章：{}", text("url"))),
+            events::ARTICLE_SCRAPED => self.line(format!(
+                "{} [{}]：{} 位留言者",
+                text("title"),
+                text("board"),
+                number("commenterCount")
+            )),
+            events::SCRAPE_PROGRESS => {
+                if let Ok(progress) = serde_json::from_value(payload.clone()) {
+                    self.progress(progress);
+                }
+            }
+            events::USER_FAILED => self.line(format!(
+                "查詢 {} 失敗 [{}]：{}",
+                text("user_id"),
+                text("code"),
+                text("message")
+            )),
+            events::RATE_LIMIT_BACKOFF => self.line(format!(
+                "{} 限流，暫停 {:.1} 秒",
+                text("host"),
+                number("wait_ms") as f64 / 1000.0
+            )),
+            events::ANALYSIS_DONE => self.line(format!(
+                "分析完成：高亮 {} 位、一般 {} 位，耗時 {:.1} 秒",
+                number("highlightedCount"),
+                number("normalCount"),
+                number("totalMs") as f64 / 1000.0
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// `--quiet` 時使用，不輸出任何進度。
+pub struct Silent;
+
+impl EventSink for Silent {
+    fn emit(&self, _event: &str, _payload: Value) {}
+}
@@ -0,0 +1,37 @@
+//! 以 `ptt-analyzer replay` 重播 `tests/bundles` 中的每個錄製目錄，結果與快照不同時測試失敗。
+//!
+//! 解析規則有意變更時，以 `ptt-analyzer replay --update <目錄>` 更新快照後一併提交。
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn recorded_bundles_match_snapshots() {
+    let bundles_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/bundles");
+    let mut bundles: Vec<_> = std::fs::read_dir(&bundles_dir)
+        .expect("找不到錄製目錄")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    bundles.sort();
+    assert!(
+        !bundles.is_empty(),
+        "{} 中沒有錄製目錄",
+        bundles_dir.display()
+    );
+
+    for bundle in bundles {
+        let output = Command::new(env!("CARGO_BIN_EXE_ptt-analyzer"))
+            .arg("--quiet")
+            .arg("replay")
+            .arg(&bundle)
+            .output()
+            .expect("無法執行 ptt-analyzer");
+        assert!(
+            output.status.success(),
+            "{} 的重播結果與快照不同：\n{}",
+            bundle.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! 大型文章 (上千則推文) 的解析時間。產生一篇 ptt.cc 格式的文章存為暫存檔，
+//! 以 `parse_local_article` 重複解析，中位數超過上限時以非零狀態結束。
+
+use ptt_analyzer_core::scraper::{self, ScrapeOptions};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+const PUSH_COUNT: usize = 5_000;
+const USER_COUNT: usize = 1_500;
+const ITERATIONS: usize = 20;
+const LIMIT: Duration = Duration::from_millis(100);
+
+fn mega_thread_html() -> String {
+    let mut html = String::from(
+        r#"<html><head><title>[爆卦] 測試 - 看板 Gossiping - 批踢踢實業坊</title></head><body>
+<div id="main-content" class="bbs-screen bbs-content">
+<div class="article-metaline"><span class="article-meta-tag">作者</span><span class="article-meta-value">tester (測試)</span></div>
+<div class="article-metaline-right"><span class="article-meta-tag">看板</span><span class="article-meta-value">Gossiping</span></div>
+<div class="article-metaline"><span class="article-meta-tag">標題</span><span class="article-meta-value">[爆卦] 測試</span></div>
+<div class="article-metaline"><span class="article-meta-tag">時間</span><span class="article-meta-value">Sun Jan  2 12:34:56 2022</span></div>
+內文
+--
+<span class="f2">※ 發信站: 批踢踢實業坊(ptt.cc), 來自: 1.2.3.4 (臺灣)
+</span>"#,
+    );
+    for i in 0..PUSH_COUNT {
+        let tag = ["推 ", "噓 ", "→ "][i % 3];
+        let _ = write!(
+            html,
+            r#"<div class="push"><span class="hl push-tag">{tag}</span><span class="f3 hl push-userid">user{user}</span><span class="f3 push-content">: 第 {i} 則推文的內容，長度大約與一般推文相同</span><span class="push-ipdatetime"> 1.2.3.4 01/02 {hour:02}:{minute:02}
+</span></div>"#,
+            user = i % USER_COUNT,
+            hour = 12 + i / 600 % 12,
+            minute = i / 10 % 60,
+        );
+    }
+    html.push_str("</div></body></html>");
+    html
+}
+
+fn main() {
+    let path = std::env::temp_dir().join("ptt_analyzer_mega_thread.html");
+    std::fs::write(&path, mega_thread_html()).expect("無法寫入暫存檔");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let options = ScrapeOptions::default();
+
+    let mut timings: Vec<Duration> = (0..ITERATIONS)
+        .map(|_| {
+            let started = Instant::now();
+            let article = runtime
+                .block_on(scraper::parse_local_article(&path, &options))
+                .expect("解析失敗");
+            assert_eq!(article.total_floors, PUSH_COUNT);
+            started.elapsed()
+        })
+        .collect();
+    let _ = std::fs::remove_file(&path);
+    timings.sort();
+    let median = timings[ITERATIONS / 2];
+    println!(
+        "mega_thread: {} 則推文，中位數 {:?} (最快 {:?}，最慢 {:?})",
+        PUSH_COUNT,
+        median,
+        timings[0],
+        timings[ITERATIONS - 1]
+    );
+    if median > LIMIT {
+        eprintln!("解析時間超過 {:?}", LIMIT);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,202 @@
+//! 警示規則：每次分析或追蹤新推文後檢查結果，有留言者符合規則的所有條件時觸發警示並記錄。
+//! 條件與高亮條件格式相同 (`看板,運算子,數值`)，例如 `HatePolitics,>=,50%` 與 `生涯總留言數,<,300`。
+
+use crate::diagnostics::unix_now;
+use crate::domain::UserId;
+use crate::error::Error;
+use crate::report::{AnalysisResult, HighlightRule};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 警示規則與紀錄的檔案名稱，存放於 app data 目錄下。
+pub const ALERTS_FILE_NAME: &str = "alerts.json";
+/// 最多保留的警示紀錄數，超過時移除最舊的。
+const MAX_ALERT_HISTORY: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    pub id: u64,
+    pub name: String,
+    /// 同一位留言者需符合所有條件
+    pub conditions: Vec<String>,
+}
+
+/// 一次觸發的警示。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub rule_id: u64,
+    pub rule_name: String,
+    pub url: String,
+    pub title: String,
+    /// 這次新符合規則的留言者；同一篇文章中已警示過的留言者不再列入
+    pub users: Vec<UserId>,
+    /// 觸發時間 (Unix 秒)
+    pub triggered_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AlertData {
+    next_id: u64,
+    rules: BTreeMap<u64, AlertRule>,
+    /// 依時間由舊到新
+    history: Vec<AlertEvent>,
+}
+
+/// 所有警示規則與觸發紀錄，每次修改後立即寫回磁碟。
+#[derive(Default)]
+pub struct AlertStore {
+    path: Option<PathBuf>,
+    data: Mutex<AlertData>,
+}
+
+impl AlertStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從沒有規則開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(ALERTS_FILE_NAME));
+        let data = storage::load_json(path.as_deref());
+        AlertStore {
+            path,
+            data: Mutex::new(data),
+        }
+    }
+
+    pub fn rules(&self) -> Vec<AlertRule> {
+        self.data.lock().unwrap().rules.values().cloned().collect()
+    }
+
+    /// 新增規則；`id` 為已存在的規則時取代該規則。
+    pub fn save_rule(
+        &self,
+        id: Option<u64>,
+        name: String,
+        conditions: Vec<String>,
+    ) -> Result<AlertRule, Error> {
+        let conditions: Vec<String> = conditions
+            .iter()
+            .map(|condition| condition.trim().to_string())
+            .filter(|condition| !condition.is_empty())
+            .collect();
+        if conditions.is_empty() {
+            return Err(Error::InvalidAlertRule("no conditions".to_string()));
+        }
+        if let Some(invalid) = conditions
+            .iter()
+            .find(|condition| HighlightRule::parse(condition).is_none())
+        {
+            return Err(Error::InvalidAlertRule(invalid.clone()));
+        }
+        self.update(|data| {
+            let id = match id {
+                Some(id) if data.rules.contains_key(&id) => id,
+                Some(id) => return Err(Error::AlertRuleNotFound(id)),
+                None => {
+                    data.next_id += 1;
+                    data.next_id
+                }
+            };
+            let rule = AlertRule {
+                id,
+                name,
+                conditions,
+            };
+            data.rules.insert(id, rule.clone());
+            Ok(rule)
+        })
+    }
+
+    pub fn delete_rule(&self, id: u64) -> Result<(), Error> {
+        self.update(|data| {
+            data.rules
+                .remove(&id)
+                .map(|_| ())
+                .ok_or(Error::AlertRuleNotFound(id))
+        })
+    }
+
+    /// 觸發過的警示，最近的在前。
+    pub fn history(&self) -> Vec<AlertEvent> {
+        let data = self.data.lock().unwrap();
+        data.history.iter().rev().cloned().collect()
+    }
+
+    /// 以所有規則檢查分析結果，記錄並回傳觸發的警示。
+    /// 同一條規則在同一篇文章中只對新符合的留言者觸發，追蹤新推文時不會重複警示。
+    pub fn evaluate(&self, result: &AnalysisResult) -> Vec<AlertEvent> {
+        let mut data = self.data.lock().unwrap();
+        let url = &result.metadata.url;
+        let now = unix_now();
+        let mut fired = Vec::new();
+        for rule in data.rules.values() {
+            let conditions: Vec<HighlightRule> = rule
+                .conditions
+                .iter()
+                .filter_map(|condition| HighlightRule::parse(condition))
+                .collect();
+            if conditions.is_empty() {
+                continue;
+            }
+            let alerted: HashSet<&UserId> = data
+                .history
+                .iter()
+                .filter(|event| event.rule_id == rule.id && &event.url == url)
+                .flat_map(|event| &event.users)
+                .collect();
+            let mut users: Vec<UserId> = result
+                .highlighted_data
+                .iter()
+                .chain(&result.normal_data)
+                .filter(|row| !alerted.contains(&row.user))
+                .filter(|row| conditions.iter().all(|condition| condition.matches(row)))
+                .map(|row| row.user.clone())
+                .collect();
+            if users.is_empty() {
+                continue;
+            }
+            users.sort();
+            fired.push(AlertEvent {
+                rule_id: rule.id,
+                rule_name: rule.name.clone(),
+                url: url.clone(),
+                title: result.metadata.title.clone(),
+                users,
+                triggered_at: now,
+            });
+        }
+        if fired.is_empty() {
+            return fired;
+        }
+
+        data.history.extend(fired.iter().cloned());
+        let excess = data.history.len().saturating_sub(MAX_ALERT_HISTORY);
+        data.history.drain(..excess);
+        if let Some(path) = &self.path {
+            // 寫入失敗時警示仍然發送，只是重新啟動後可能再警示一次
+            if let Err(e) = storage::save_json(path, &*data) {
+                tracing::warn!(error = %e, "無法儲存警示紀錄");
+            }
+        }
+        fired
+    }
+
+    /// 修改規則並寫回磁碟；寫入失敗時還原修改並回傳錯誤。
+    fn update<T>(&self, f: impl FnOnce(&mut AlertData) -> Result<T, Error>) -> Result<T, Error> {
+        let mut data = self.data.lock().unwrap();
+        let previous_id = data.next_id;
+        let previous = data.rules.clone();
+        let result = f(&mut data)?;
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*data) {
+                data.next_id = previous_id;
+                data.rules = previous;
+                return Err(e);
+            }
+        }
+        Ok(result)
+    }
+}
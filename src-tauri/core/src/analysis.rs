@@ -0,0 +1,1202 @@
+//! 分析流程：抓取文章、查詢留言者統計、排序與高亮。
+
+use crate::analyzers::{self, AnalyzerKind, ReportDraft};
+use crate::article_url;
+use crate::backoff::{HostBackoff, RateLimiters};
+use crate::concurrency::{self, AdaptiveConcurrency};
+use crate::config::{self, AppConfig, ConcurrentAnalysisPolicy};
+use crate::diagnostics::{self, DiagnosticsState};
+use crate::disk_cache::DiskCache;
+use crate::domain::{BoardName, UserId};
+use crate::error::{panic_message, Error};
+use crate::events;
+use crate::fetcher::Fetcher;
+use crate::history::{self, AppearanceHistory};
+use crate::jobs::{JobControl, JobEmitter, JobId, JobManager, PauseGate};
+use crate::metrics;
+use crate::notes::NoteStore;
+use crate::profile::{self, ArticleEntry};
+use crate::progress::{LookupOutcome, ProgressTracker};
+use crate::providers::{self, UserStatsCache, UserStatsProvider};
+use crate::ptt_term;
+use crate::report::{
+    self, AnalysisDiagnostics, AnalysisResult, FailedUser, PhaseTimings, PttWebData,
+    ReportMetadata, SummaryStats, UserReportData, UserTiming,
+};
+use crate::scraper;
+use crate::timeline;
+use crate::trends::UserTrendStore;
+use crate::watchlists::WatchlistStore;
+use futures::stream::{self, StreamExt};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// pttweb.cc 查詢的初始併發數，之後依回應速度與錯誤自動調整。
+const INITIAL_CONCURRENCY: usize = 10;
+/// 診斷資訊中列出的最慢使用者數量。
+const SLOWEST_USERS_LIMIT: usize = 5;
+/// 單一使用者被限流時最多重試幾次。
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+/// `SCRAPE_METRICS` 事件的發送間隔。
+const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+/// `HEARTBEAT` 事件的發送間隔。
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(3);
+/// 同時查詢近期發文的使用者數。
+const RECENT_ARTICLES_CONCURRENCY: usize = 4;
+
+// (新增) 定義一個結構體來接收來自前端的完整 payload
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzePayload {
+    pub url: String,
+    #[serde(flatten)]
+    pub options: AnalysisOptions,
+}
+
+// 除了網址以外的分析選項，批次分析時每篇文章共用同一組
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisOptions {
+    pub filter_types: Vec<String>,
+    pub keywords: Option<Vec<String>>,
+    // 推文內容的字數範圍，用來排除過短 (或過長) 的留言
+    pub min_content_length: Option<usize>,
+    pub max_content_length: Option<usize>,
+    // 是否將同一使用者連續的 → 推文合併為一則留言
+    #[serde(default)]
+    pub merge_arrow_continuations: bool,
+    // 是否另外查詢每位使用者在目標看板的近期發文 (每位使用者多一次 pttweb.cc 請求)
+    #[serde(default)]
+    pub fetch_recent_articles: bool,
+    // 是否在資料列保留每位使用者所有看板的留言數，用於找出留言者共同活躍的其他看板
+    #[serde(default)]
+    pub full_board_distribution: bool,
+    // 只分析指定樓層範圍內的推文 (1 起算，含頭尾)
+    pub push_range_start: Option<usize>,
+    pub push_range_end: Option<usize>,
+    pub highlight_condition: Option<String>,
+    // 排序後依序執行的分析器，未指定時只做高亮
+    #[serde(default = "analyzers::default_analyzers")]
+    pub analyzers: Vec<AnalyzerKind>,
+    // 整體分析的時限 (秒)，超過後不再查詢新的使用者，以現有資料產生部分報告
+    pub max_duration_secs: Option<u64>,
+    // 轉錄文章是否也以相同選項將原文加入分析佇列
+    #[serde(default)]
+    pub analyze_forwarded_origin: bool,
+    pub config: AppConfig, // 包含前端傳來的設定
+}
+
+impl AnalysisOptions {
+    /// 不篩選推文、只做高亮的預設選項，用於不經過前端表單的分析 (例如系統匣)。
+    pub fn from_config(config: AppConfig) -> Self {
+        AnalysisOptions {
+            filter_types: Vec::new(),
+            keywords: None,
+            min_content_length: None,
+            max_content_length: None,
+            merge_arrow_continuations: false,
+            fetch_recent_articles: false,
+            full_board_distribution: false,
+            push_range_start: None,
+            push_range_end: None,
+            highlight_condition: None,
+            analyzers: analyzers::default_analyzers(),
+            max_duration_secs: None,
+            analyze_forwarded_origin: false,
+            config,
+        }
+    }
+}
+
+/// 分析流程共用的狀態；在 app 中皆為 Tauri managed state。
+pub struct AnalysisState<'a> {
+    pub jobs: &'a JobManager,
+    pub diagnostics: &'a DiagnosticsState,
+    pub rate_limiters: &'a RateLimiters,
+    pub cache: &'a UserStatsCache,
+    pub lookups: &'a scraper::UserPageLookups,
+    pub disk_cache: &'a DiskCache,
+    /// 所有 HTTP 請求經由此 fetcher；離線模式時改讀 fixture 檔
+    pub fetcher: &'a dyn Fetcher,
+    /// 出現在觀察名單上的留言者會在結果中標記
+    pub watchlists: &'a WatchlistStore,
+    /// 使用者對帳號的備註與標籤，附加在對應的資料列
+    pub notes: &'a NoteStore,
+    /// 留言者出現過的文章紀錄，每次分析後更新
+    pub history: &'a AppearanceHistory,
+    /// 每次查到使用者統計時附上時間記錄，用於觀察留言數的變化
+    pub trends: &'a UserTrendStore,
+}
+
+/// 執行一個已登記的分析工作：等待執行權、執行分析並更新工作狀態與發送結束事件。
+pub async fn execute_job(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    control: JobControl,
+    payload: AnalyzePayload,
+    policy: ConcurrentAnalysisPolicy,
+) -> Result<AnalysisResult, Error> {
+    let job_id = emitter.job_id();
+
+    // 攔截分析過程中的 panic，轉為錯誤回傳給前端，而不是讓命令永遠沒有回應
+    let pipeline = AssertUnwindSafe(async {
+        // 設定為不排隊時，先確認可以執行再抓文章
+        let early_slot = match policy {
+            ConcurrentAnalysisPolicy::Reject => Some(state.jobs.acquire_slot(policy).await?),
+            ConcurrentAnalysisPolicy::Queue => None,
+        };
+        // 兩段式流程：排隊中的工作先併發抓取文章頁面，輪到執行時再依序查詢 pttweb.cc
+        let article = fetch_article(state, emitter, payload).await?;
+        let _slot = match early_slot {
+            Some(slot) => slot,
+            None => state.jobs.acquire_slot(policy).await?,
+        };
+        state.jobs.mark_running(job_id);
+        let _job = state.diagnostics.start_job();
+        run_analysis(state, emitter, &control.pause, article).await
+    });
+
+    let result = tokio::select! {
+        result = pipeline.catch_unwind() => {
+            result.unwrap_or_else(|payload| Err(Error::Internal(panic_message(&*payload))))
+        }
+        _ = control.cancel.cancelled() => Err(Error::Cancelled),
+        _ = heartbeat_loop(emitter) => unreachable!(),
+    };
+
+    state.jobs.finish(job_id, &result);
+    match &result {
+        Ok(analysis) => {
+            if let Err(e) = state.history.record_snapshot(analysis) {
+                tracing::warn!(error = %e, "無法儲存文章快照");
+            }
+            // 報告紀錄與文章快照共用同一個壓縮快取與容量上限
+            if let Ok(report) = serde_json::to_vec(analysis) {
+                let key = format!("reports/{}-{}.json", diagnostics::unix_now(), job_id);
+                state.disk_cache.put(&key, &report);
+                if let Err(e) = state.history.set_report_key(&analysis.metadata.url, &key) {
+                    tracing::warn!(error = %e, "無法儲存報告紀錄的位置");
+                }
+            }
+            emitter.emit(
+                events::ANALYSIS_DONE,
+                events::AnalysisDonePayload {
+                    highlighted_count: analysis.highlighted_data.len(),
+                    normal_count: analysis.normal_data.len(),
+                    total_ms: analysis.diagnostics.total_ms,
+                },
+            );
+        }
+        Err(e) => {
+            if !matches!(e, Error::Cancelled) {
+                state.diagnostics.record_error(e);
+            }
+            emitter.emit(
+                events::ANALYSIS_FAILED,
+                events::AnalysisFailedPayload { error: e },
+            );
+        }
+    }
+    result
+}
+
+/// 工作執行期間定期發送心跳事件，直到被外層取消。
+async fn heartbeat_loop(emitter: &JobEmitter) {
+    let started = Instant::now();
+    let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        interval.tick().await;
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        emitter.emit(
+            events::HEARTBEAT,
+            events::HeartbeatPayload {
+                elapsed_ms: elapsed_ms(started),
+                timestamp_ms,
+            },
+        );
+    }
+}
+
+/// 已抓取並統計完留言者的文章，等待查詢 pttweb.cc。
+struct FetchedArticle {
+    url: String,
+    options: AnalysisOptions,
+    article_data: scraper::PttArticleData,
+    article_ms: u64,
+}
+
+/// 分析的第一階段：抓取文章頁面並統計留言者。
+/// 多個工作可同時進行此階段 (受 ptt.cc 的併發數與限流退避限制)。
+#[tracing::instrument(name = "fetch", skip_all, fields(job = emitter.job_id()))]
+async fn fetch_article(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    payload: AnalyzePayload,
+) -> Result<FetchedArticle, Error> {
+    let rate_limiters = state.rate_limiters;
+    let disk_cache = state.disk_cache;
+    // 各種官方網址形式 (web.ptt.cc、無 .html 等) 與文章代碼都正規化為 www.ptt.cc 的標準網址，
+    // 本機 HTML 檔則直接讀檔解析
+    let (article_input, url) = article_url::resolve_input(&payload.url)?;
+    let options = payload.options;
+    tracing::info!(url = %url, "開始分析文章");
+
+    // 步驟 1: 爬取 PTT 文章頁面
+    let scrape_options = scrape_options(&options);
+    let _permit = rate_limiters
+        .article_fetches
+        .acquire()
+        .await
+        .expect("article fetch semaphore 不會被關閉");
+    emitter.emit(
+        events::ARTICLE_SCRAPE_STARTED,
+        events::ArticleScrapeStartedPayload { url: url.clone() },
+    );
+    let article_started = Instant::now();
+    disk_cache.set_max_bytes(options.config.cache_max_mb.saturating_mul(1024 * 1024));
+
+    let mut retries = 0;
+    let article_data = loop {
+        rate_limiters.ptt.wait_ready().await;
+        match scraper::load_article(&article_input, &scrape_options, disk_cache, state.fetcher)
+            .await
+        {
+            Err(Error::RateLimited {
+                retry_after_secs, ..
+            }) if retries < MAX_RATE_LIMIT_RETRIES => {
+                let wait = rate_limiters
+                    .ptt
+                    .on_rate_limited(retry_after_secs.map(Duration::from_secs));
+                emitter.emit(
+                    events::RATE_LIMIT_BACKOFF,
+                    events::RateLimitBackoffPayload {
+                        host: rate_limiters.ptt.host(),
+                        wait_ms: wait.as_millis() as u64,
+                    },
+                );
+                retries += 1;
+            }
+            result => {
+                if result.is_ok() {
+                    rate_limiters.ptt.on_success();
+                }
+                break result?;
+            }
+        }
+    };
+    let article_ms = elapsed_ms(article_started);
+    emitter.emit(
+        events::ARTICLE_SCRAPED,
+        events::ArticleScrapedPayload {
+            title: article_data.title.clone(),
+            board: article_data.board.clone(),
+            commenter_count: article_data.user_comment_counts.len(),
+        },
+    );
+    tracing::info!(
+        board = %article_data.board,
+        commenters = article_data.user_comment_counts.len(),
+        "文章爬取完成"
+    );
+
+    Ok(FetchedArticle {
+        url,
+        options,
+        article_data,
+        article_ms,
+    })
+}
+
+fn scrape_options(options: &AnalysisOptions) -> scraper::ScrapeOptions {
+    scraper::ScrapeOptions {
+        filter_types: options.filter_types.clone(),
+        keywords: options.keywords.clone(),
+        min_content_length: options.min_content_length,
+        max_content_length: options.max_content_length,
+        merge_arrow_continuations: options.merge_arrow_continuations,
+        push_range_start: options.push_range_start,
+        push_range_end: options.push_range_end,
+    }
+}
+
+/// 分析的第二階段：查詢 pttweb.cc、排序與高亮。
+async fn run_analysis(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    pause: &PauseGate,
+    article: FetchedArticle,
+) -> Result<AnalysisResult, Error> {
+    let FetchedArticle {
+        url,
+        options,
+        article_data,
+        article_ms,
+    } = article;
+    let app_config = &options.config; // 直接使用從前端傳來的設定
+
+    // 時限與總耗時從輪到此工作執行時起算，排隊期間不計入
+    let analysis_started = Instant::now();
+
+    let mut target_boards = app_config.boards.clone(); // 使用傳入的看板列表
+    if !target_boards.contains(&article_data.board) {
+        target_boards.push(article_data.board.clone());
+    }
+
+    // 步驟 2: 併發查詢 pttweb.cc
+    // 看板列表在併發查詢間共用，避免每位使用者都複製一次
+    let users_to_scrape: Vec<UserId> = article_data.user_comment_counts.keys().cloned().collect();
+    let target_boards: Arc<[BoardName]> = target_boards.into();
+    emitter.emit(
+        events::ENRICH_STARTED,
+        events::EnrichStartedPayload {
+            total: users_to_scrape.len(),
+        },
+    );
+    let enrichment_started = Instant::now();
+    let deadline = options
+        .max_duration_secs
+        .map(|secs| analysis_started + Duration::from_secs(secs));
+    let EnrichmentOutcome {
+        mut ptt_web_data,
+        failed_users,
+        not_found_users,
+        skipped_users,
+        user_timings,
+        request_count,
+        retry_count,
+        cache_hits,
+    } = enrich_users(
+        &EnrichContext {
+            emitter,
+            pause,
+            diagnostics: state.diagnostics,
+            backoff: &state.rate_limiters.pttweb,
+            providers: &providers::build_chain(
+                &app_config.user_stats_sources,
+                state.cache,
+                state.lookups,
+                state.fetcher,
+            ),
+            cache: state.cache,
+            trends: state.trends,
+            deadline,
+        },
+        users_to_scrape,
+        target_boards,
+    )
+    .await;
+    // 啟用時另外登入 term.ptt.cc 查詢 pttweb.cc 沒有的帳號資料
+    // 離線模式不連網，略過 term.ptt.cc
+    let mut term_info = if app_config.ptt_term.enabled && !state.fetcher.is_offline() {
+        let users: Vec<_> = article_data.user_comment_counts.keys().cloned().collect();
+        query_term_info(
+            &app_config.ptt_term,
+            pause,
+            state.diagnostics,
+            deadline,
+            users,
+        )
+        .await
+    } else {
+        HashMap::new()
+    };
+    let mut recent_articles = if options.fetch_recent_articles {
+        let users: Vec<_> = article_data.user_comment_counts.keys().cloned().collect();
+        query_recent_articles(state, pause, deadline, users, &app_config.boards).await
+    } else {
+        HashMap::new()
+    };
+    let enrichment_ms = elapsed_ms(enrichment_started);
+    let post_processing_started = Instant::now();
+
+    if let Err(e) = state.history.record(&url, &article_data) {
+        tracing::warn!(error = %e, "無法儲存留言者出現紀錄");
+    }
+    let fast_responders: HashSet<UserId> = article_data
+        .author
+        .as_ref()
+        .map(|author| {
+            state
+                .history
+                .fast_responders(author, history::DEFAULT_FAST_RESPONSE_MINUTES)
+                .into_iter()
+                .map(|responder| responder.user)
+                .collect()
+        })
+        .unwrap_or_default();
+    let new_account = &app_config.new_account;
+    let now = diagnostics::unix_now();
+
+    let mut report_data: Vec<UserReportData> = article_data
+        .user_comment_counts
+        .iter()
+        .map(|(user, &article_comments)| {
+            let data = ptt_web_data.remove(user);
+            let user_term_info = term_info.remove(user);
+            // 查無資料或查詢失敗的使用者總留言數為 0，不能據此判斷為新帳號
+            let is_new_account = data
+                .as_ref()
+                .is_some_and(|data| data.total_comments < new_account.max_total_comments)
+                || user_term_info
+                    .as_ref()
+                    .and_then(|info| info.account_age_days(now))
+                    .is_some_and(|days| days < new_account.max_account_age_days);
+            let data = data.unwrap_or_else(|| PttWebData {
+                board_comments: HashMap::new(),
+                total_comments: 0,
+                all_board_comments: HashMap::new(),
+            });
+            let articles_seen_in = state.history.seen_in(user, &url);
+
+            UserReportData {
+                user: user.clone(),
+                article_comments,
+                board_comments: data.board_comments,
+                total_comments: data.total_comments,
+                all_board_comments: if options.full_board_distribution {
+                    data.all_board_comments
+                } else {
+                    HashMap::new()
+                },
+                push_types: article_data
+                    .user_push_types
+                    .get(user)
+                    .copied()
+                    .unwrap_or_default(),
+                term_info: user_term_info,
+                fields: Default::default(),
+                watchlists: state.watchlists.lists_containing(user),
+                note: state.notes.get(user),
+                members: Vec::new(),
+                times_seen: articles_seen_in.len() as u32,
+                articles_seen_in,
+                recent_articles: recent_articles.remove(user).unwrap_or_default(),
+                first_push_latency_minutes: article_data.first_push_latency.get(user).copied(),
+                floors: article_data
+                    .user_floors
+                    .get(user)
+                    .cloned()
+                    .unwrap_or_default(),
+                fast_responder: fast_responders.contains(user),
+                is_new_account,
+            }
+        })
+        .collect();
+
+    // 步驟 3: 排序資料；步驟 4: 依序執行分析器 (高亮、評分等)
+    let pipeline = analyzers::build_pipeline(
+        &options.analyzers,
+        &app_config.boards,
+        options.highlight_condition.as_deref(),
+    );
+    let sort_span = tracing::info_span!("sort", users = report_data.len());
+    let ReportDraft {
+        highlighted: highlighted_data,
+        normal: normal_data,
+        sections,
+    } = sort_span.in_scope(|| {
+        report::sort_rows(&mut report_data, &app_config.sorting);
+        let mut draft = ReportDraft::new(report_data);
+        analyzers::run_pipeline(&pipeline, &article_data, &mut draft);
+        draft
+    });
+
+    let metadata = ReportMetadata {
+        title: article_data.title,
+        url,
+        board: article_data.board,
+        author: article_data.author,
+        body: article_data.body,
+        posted_at: article_data.posted_at,
+        edits: article_data.edits,
+        total_floors: article_data.total_floors,
+        forwarded_from: article_data.forwarded_from,
+        filter_types: options.filter_types,
+        keywords: options.keywords,
+        min_content_length: options.min_content_length,
+        max_content_length: options.max_content_length,
+        push_range_start: options.push_range_start,
+        push_range_end: options.push_range_end,
+        highlight_condition: options.highlight_condition,
+        source: article_data.source,
+    };
+
+    let analysis_diagnostics = AnalysisDiagnostics {
+        total_ms: article_ms + elapsed_ms(analysis_started),
+        phases: PhaseTimings {
+            article_ms,
+            enrichment_ms,
+            post_processing_ms: elapsed_ms(post_processing_started),
+        },
+        // 文章頁面本身 + 實際發出的 pttweb.cc 查詢 (含限流後的重試，不含快取命中)
+        request_count: 1 + request_count,
+        retry_count,
+        cache_hits,
+        slowest_users: slowest_users(user_timings),
+    };
+
+    let mut result = AnalysisResult {
+        schema_version: report::SCHEMA_VERSION,
+        job_id: emitter.job_id(),
+        metadata,
+        highlighted_data,
+        normal_data,
+        failed_users,
+        not_found_users,
+        diagnostics: analysis_diagnostics,
+        warnings: article_data.warnings,
+        partial: !skipped_users.is_empty(),
+        skipped_users,
+        sections,
+        summary: SummaryStats::default(),
+        timeline: timeline::build(&article_data.timed_pushes, article_data.time_anchor()),
+    };
+    result.summary = SummaryStats::compute(&result);
+    Ok(result)
+}
+
+/// 使用者查詢 pttweb.cc 後的彙整結果。
+struct EnrichmentOutcome {
+    ptt_web_data: HashMap<UserId, PttWebData>,
+    failed_users: Vec<FailedUser>,
+    not_found_users: Vec<UserId>,
+    // 因超過分析時限而未查詢的使用者
+    skipped_users: Vec<UserId>,
+    user_timings: Vec<UserTiming>,
+    // 實際發出的使用者統計請求數，快取命中不計入
+    request_count: u32,
+    retry_count: u32,
+    cache_hits: u32,
+}
+
+/// 查詢 pttweb.cc 時需要的執行環境。
+struct EnrichContext<'a> {
+    emitter: &'a JobEmitter,
+    pause: &'a PauseGate,
+    diagnostics: &'a DiagnosticsState,
+    backoff: &'a HostBackoff,
+    /// 依序查詢的資料來源
+    providers: &'a [Box<dyn UserStatsProvider + 'a>],
+    /// 非快取來源查到的資料寫回此快取
+    cache: &'a UserStatsCache,
+    /// 非快取來源查到的資料同時記錄到統計紀錄
+    trends: &'a UserTrendStore,
+    /// 超過此時間後不再發出新的查詢
+    deadline: Option<Instant>,
+}
+
+/// 併發查詢多位使用者在 pttweb.cc 上的留言統計，並回報進度。
+#[tracing::instrument(name = "enrich", skip_all, fields(users = users_to_scrape.len()))]
+async fn enrich_users(
+    ctx: &EnrichContext<'_>,
+    users_to_scrape: Vec<UserId>,
+    target_boards: Arc<[BoardName]>,
+) -> EnrichmentOutcome {
+    let emitter = ctx.emitter;
+    let tracker = ProgressTracker::new(users_to_scrape.len());
+    let metrics = metrics::ScrapeMetrics::default();
+    let concurrency = AdaptiveConcurrency::new(INITIAL_CONCURRENCY);
+
+    let report_futures = stream::iter(users_to_scrape)
+        .map(|user| {
+            let target_boards = Arc::clone(&target_boards);
+            let tracker = &tracker;
+            let metrics = &metrics;
+            let concurrency = &concurrency;
+            async move {
+                ctx.pause.wait_if_paused().await;
+                // 超過時限後不再發出新的查詢
+                if ctx
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline)
+                {
+                    return (user, None, 0);
+                }
+                let started = Instant::now();
+                let result = lookup_user(ctx, metrics, concurrency, &user, &target_boards).await;
+
+                let outcome = match &result {
+                    Ok((_, outcome)) => *outcome,
+                    Err(Error::PttWebUserNotFound(_)) => LookupOutcome::Succeeded,
+                    Err(e) => {
+                        emitter.emit(
+                            events::USER_FAILED,
+                            events::UserFailedPayload {
+                                user_id: user.clone(),
+                                code: e.code(),
+                                message: e.to_string(),
+                                retryable: e.is_retryable(),
+                            },
+                        );
+                        LookupOutcome::Failed
+                    }
+                };
+                emitter.emit(events::SCRAPE_PROGRESS, tracker.record(&user, outcome));
+
+                (
+                    user,
+                    Some(result.map(|(data, _)| data)),
+                    elapsed_ms(started),
+                )
+            }
+        })
+        // 實際的併發數由 `concurrency` 控制，這裡只設定上限
+        .buffer_unordered(concurrency::MAX_CONCURRENCY);
+
+    // 查詢期間定期發送吞吐量統計
+    let metrics_loop = async {
+        let mut interval = tokio::time::interval(METRICS_INTERVAL);
+        let mut last_completed = 0;
+        loop {
+            interval.tick().await;
+            let completed = metrics.completed();
+            let requests_per_sec =
+                (completed - last_completed) as f64 / METRICS_INTERVAL.as_secs_f64();
+            last_completed = completed;
+            emitter.emit(
+                events::SCRAPE_METRICS,
+                metrics.snapshot(requests_per_sec, concurrency.current()),
+            );
+        }
+    };
+
+    let ptt_web_results: Vec<_> = tokio::select! {
+        results = report_futures.collect() => results,
+        _ = metrics_loop => unreachable!(),
+    };
+
+    let mut outcome = EnrichmentOutcome {
+        ptt_web_data: HashMap::new(),
+        failed_users: Vec::new(),
+        not_found_users: Vec::new(),
+        skipped_users: Vec::new(),
+        user_timings: Vec::new(),
+        request_count: metrics.completed() as u32,
+        retry_count: metrics.retries(),
+        cache_hits: metrics.cache_hits(),
+    };
+    for (user, ptt_web_result, duration_ms) in ptt_web_results {
+        let Some(ptt_web_result) = ptt_web_result else {
+            outcome.skipped_users.push(user);
+            continue;
+        };
+        outcome.user_timings.push(UserTiming {
+            user: user.clone(),
+            duration_ms,
+        });
+        match ptt_web_result {
+            Ok(data) => {
+                outcome.ptt_web_data.insert(user, data);
+            }
+            Err(Error::PttWebUserNotFound(_)) => outcome.not_found_users.push(user),
+            Err(e) => {
+                tracing::warn!(user = %user, error = ?e, "查詢 pttweb.cc 時發生非預期錯誤");
+                ctx.diagnostics.record_error(&e);
+                outcome.failed_users.push(FailedUser {
+                    user,
+                    error_code: e.code().to_string(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+    outcome.failed_users.sort_by(|a, b| a.user.cmp(&b.user));
+    outcome.not_found_users.sort();
+    outcome.skipped_users.sort();
+    ctx.cache.save();
+    if let Err(e) = ctx.trends.save() {
+        tracing::warn!(error = %e, "無法儲存使用者統計紀錄");
+    }
+    outcome
+}
+
+/// 依序向各資料來源查詢使用者，第一個有資料的來源為準；非快取來源的結果會寫回快取。
+/// 一併回傳進度統計的結果類型，資料來自本機快取時為 [`LookupOutcome::Cached`]。
+async fn lookup_user(
+    ctx: &EnrichContext<'_>,
+    metrics: &metrics::ScrapeMetrics,
+    concurrency: &AdaptiveConcurrency,
+    user: &UserId,
+    target_boards: &[BoardName],
+) -> Result<(PttWebData, LookupOutcome), Error> {
+    for provider in ctx.providers {
+        match scrape_with_backoff(
+            ctx,
+            metrics,
+            concurrency,
+            provider.as_ref(),
+            user,
+            target_boards,
+        )
+        .await?
+        {
+            Some(data) => {
+                if provider.is_cache() {
+                    metrics.record_cache_hit();
+                    return Ok((data, LookupOutcome::Cached));
+                }
+                ctx.cache.insert(user, target_boards, &data);
+                ctx.trends.record(user, &data);
+                return Ok((data, LookupOutcome::Succeeded));
+            }
+            None => {
+                tracing::debug!(user = %user, provider = provider.name(), "資料來源沒有此使用者")
+            }
+        }
+    }
+    Err(Error::UserStatsUnavailable(user.to_string()))
+}
+
+/// 以單一 term.ptt.cc 連線依序查詢使用者資料；連線或登入失敗時略過，不影響分析結果。
+async fn query_term_info(
+    config: &config::PttTermConfig,
+    pause: &PauseGate,
+    diagnostics: &DiagnosticsState,
+    deadline: Option<Instant>,
+    users: Vec<UserId>,
+) -> HashMap<UserId, ptt_term::PttUserInfo> {
+    let mut infos = HashMap::new();
+    let mut client = match ptt_term::PttTermClient::connect(config).await {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = ?e, "無法登入 term.ptt.cc，略過使用者資料查詢");
+            diagnostics.record_error(&e);
+            return infos;
+        }
+    };
+
+    for user in users {
+        pause.wait_if_paused().await;
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+        match client.query_user(user.as_str()).await {
+            Ok(info) => {
+                infos.insert(user, info);
+            }
+            Err(e) => {
+                // 畫面狀態可能已經錯亂，不再繼續查詢
+                tracing::warn!(user = %user, error = ?e, "查詢 term.ptt.cc 使用者資料失敗");
+                diagnostics.record_error(&e);
+                return infos;
+            }
+        }
+    }
+    client.close().await;
+    infos
+}
+
+/// 查詢每位使用者在目標看板的近期發文；查詢失敗的使用者略過，不影響分析結果。
+async fn query_recent_articles(
+    state: &AnalysisState<'_>,
+    pause: &PauseGate,
+    deadline: Option<Instant>,
+    users: Vec<UserId>,
+    boards: &[BoardName],
+) -> HashMap<UserId, Vec<ArticleEntry>> {
+    stream::iter(users)
+        .map(|user| async move {
+            pause.wait_if_paused().await;
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return None;
+            }
+            match profile::fetch_recent_articles(
+                &user,
+                boards,
+                state.fetcher,
+                &state.rate_limiters.pttweb,
+            )
+            .await
+            {
+                Ok(articles) => Some((user, articles)),
+                Err(e) => {
+                    tracing::warn!(user = %user, error = ?e, "查詢近期發文失敗");
+                    state.diagnostics.record_error(&e);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(RECENT_ARTICLES_CONCURRENCY)
+        .filter_map(|entry| async move { entry })
+        .collect()
+        .await
+}
+
+/// 向單一資料來源查詢使用者；被限流時讓整個站台退避一段時間後重試，並通知前端。
+async fn scrape_with_backoff(
+    ctx: &EnrichContext<'_>,
+    metrics: &metrics::ScrapeMetrics,
+    concurrency: &AdaptiveConcurrency,
+    provider: &dyn UserStatsProvider,
+    user: &UserId,
+    target_boards: &[BoardName],
+) -> Result<Option<PttWebData>, Error> {
+    // 快取不受站台限流影響
+    if provider.is_cache() {
+        return provider.lookup(user, target_boards).await;
+    }
+    let mut retries = 0;
+    loop {
+        ctx.backoff.wait_ready().await;
+        let result = {
+            let _permit = concurrency.acquire().await;
+            let _in_flight = metrics.start_request();
+            let started = Instant::now();
+            let result = provider.lookup(user, target_boards).await;
+            concurrency.record(started.elapsed(), &result);
+            result
+        };
+
+        match &result {
+            Err(Error::RateLimited {
+                retry_after_secs, ..
+            }) => {
+                metrics.record_rate_limit();
+                if retries >= MAX_RATE_LIMIT_RETRIES {
+                    return result;
+                }
+                let wait = ctx
+                    .backoff
+                    .on_rate_limited(retry_after_secs.map(Duration::from_secs));
+                ctx.emitter.emit(
+                    events::RATE_LIMIT_BACKOFF,
+                    events::RateLimitBackoffPayload {
+                        host: ctx.backoff.host(),
+                        wait_ms: wait.as_millis() as u64,
+                    },
+                );
+                tracing::warn!(
+                    host = ctx.backoff.host(),
+                    wait_ms = wait.as_millis() as u64,
+                    "被限流，暫停請求"
+                );
+                retries += 1;
+                metrics.record_retry();
+            }
+            Ok(_) => {
+                ctx.backoff.on_success();
+                return result;
+            }
+            Err(_) => return result,
+        }
+    }
+}
+
+/// 依耗時由大到小取出最慢的幾位使用者。
+fn slowest_users(mut user_timings: Vec<UserTiming>) -> Vec<UserTiming> {
+    user_timings.sort_by_key(|timing| std::cmp::Reverse(timing.duration_ms));
+    user_timings.truncate(SLOWEST_USERS_LIMIT);
+    user_timings
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+// (新增) 重新查詢先前失敗使用者時，前端傳入的 payload
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPayload {
+    pub users: Vec<UserId>,
+    pub boards: Vec<BoardName>,
+    // 原本的報告，工作列表中以其網址顯示這次重新查詢
+    #[serde(default)]
+    pub report_id: Option<JobId>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryResult {
+    pub job_id: JobId,
+    // 成功查詢的使用者，前端以此更新對應的資料列
+    pub ptt_web_data: HashMap<UserId, PttWebData>,
+    pub failed_users: Vec<FailedUser>,
+    pub not_found_users: Vec<UserId>,
+}
+
+/// 只重新查詢指定使用者 (通常是上次分析中失敗的使用者)，不需重新分析整篇文章。
+/// 與分析相同登記為工作 (見 [`retry_job_label`])，可經由 `control` 取消或暫停。
+pub async fn retry_users(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    control: JobControl,
+    payload: RetryPayload,
+) -> Result<RetryResult, Error> {
+    let job_id = emitter.job_id();
+    state.jobs.mark_running(job_id);
+    let result = tokio::select! {
+        result = enrich_retry(state, emitter, &control.pause, payload) => result,
+        _ = control.cancel.cancelled() => Err(Error::Cancelled),
+    };
+    state.jobs.finish_without_result(job_id, &result);
+    result
+}
+
+/// 重新查詢在工作列表中顯示的名稱：原本報告的網址，沒有指定報告時為空字串。
+pub fn retry_job_label(jobs: &JobManager, payload: &RetryPayload) -> String {
+    payload
+        .report_id
+        .and_then(|id| {
+            jobs.with_result(id, |result| result.metadata.url.clone())
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+async fn enrich_retry(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    pause: &PauseGate,
+    payload: RetryPayload,
+) -> Result<RetryResult, Error> {
+    let _job = state.diagnostics.start_job();
+    let EnrichmentOutcome {
+        ptt_web_data,
+        failed_users,
+        not_found_users,
+        ..
+    } = AssertUnwindSafe(enrich_users(
+        &EnrichContext {
+            emitter,
+            pause,
+            diagnostics: state.diagnostics,
+            backoff: &state.rate_limiters.pttweb,
+            // 重新查詢時略過快取，一律取得最新資料
+            providers: &providers::build_chain(
+                &[config::UserStatsSource::PttWeb],
+                state.cache,
+                state.lookups,
+                state.fetcher,
+            ),
+            cache: state.cache,
+            trends: state.trends,
+            deadline: None,
+        },
+        payload.users,
+        payload.boards.into(),
+    ))
+    .catch_unwind()
+    .await
+    .map_err(|payload| Error::Internal(panic_message(&*payload)))?;
+
+    Ok(RetryResult {
+        job_id: emitter.job_id(),
+        ptt_web_data,
+        failed_users,
+        not_found_users,
+    })
+}
+
+/// 重新抓取已分析過的文章，與上次的留言數快照比對：只查詢第一次留言的使用者，
+/// 其餘資料列只更新本文留言數，再重新排序並執行分析器，不重跑整份分析。
+/// `snapshot` 會更新為這次的留言數；沒有新推文時回傳 `None`。
+/// 追蹤期間不查詢 term.ptt.cc 與近期發文，新加入的資料列沒有這兩項資料。
+pub async fn poll_new_pushes(
+    state: &AnalysisState<'_>,
+    emitter: &JobEmitter,
+    options: &AnalysisOptions,
+    snapshot: &mut HashMap<UserId, u32>,
+) -> Result<Option<events::NewPushesPayload>, Error> {
+    let report_id = emitter.job_id();
+    let url = state
+        .jobs
+        .with_result(report_id, |result| result.metadata.url.clone())?;
+    let (article_input, _) = article_url::resolve_input(&url)?;
+    state.rate_limiters.ptt.wait_ready().await;
+    let article_data = scraper::load_article(
+        &article_input,
+        &scrape_options(options),
+        state.disk_cache,
+        state.fetcher,
+    )
+    .await?;
+    state.rate_limiters.ptt.on_success();
+
+    let mut new_pushes = events::NewPushesPayload::default();
+    for (user, &count) in &article_data.user_comment_counts {
+        let previous = snapshot.get(user).copied();
+        if previous.is_none() {
+            new_pushes.users.push(user.clone());
+        }
+        let added = count.saturating_sub(previous.unwrap_or(0));
+        if added > 0 {
+            new_pushes.counts.insert(user.clone(), added);
+        }
+    }
+    *snapshot = article_data.user_comment_counts.clone();
+    if new_pushes.counts.is_empty() {
+        return Ok(None);
+    }
+    new_pushes.users.sort();
+
+    let app_config = &options.config;
+    let mut target_boards = app_config.boards.clone();
+    if !target_boards.contains(&article_data.board) {
+        target_boards.push(article_data.board.clone());
+    }
+    let EnrichmentOutcome {
+        mut ptt_web_data,
+        failed_users,
+        not_found_users,
+        ..
+    } = enrich_users(
+        &EnrichContext {
+            emitter,
+            pause: &PauseGate::default(),
+            diagnostics: state.diagnostics,
+            backoff: &state.rate_limiters.pttweb,
+            providers: &providers::build_chain(
+                &app_config.user_stats_sources,
+                state.cache,
+                state.lookups,
+                state.fetcher,
+            ),
+            cache: state.cache,
+            trends: state.trends,
+            deadline: None,
+        },
+        new_pushes.users.clone(),
+        target_boards.into(),
+    )
+    .await;
+
+    // 重新記錄出現紀錄會清除報告位置，記錄後再寫回
+    let report_key = state.history.get(&url).and_then(|record| record.report_key);
+    if let Err(e) = state.history.record(&url, &article_data) {
+        tracing::warn!(error = %e, "無法儲存留言者出現紀錄");
+    }
+    // 之前查詢失敗或查無此人的使用者可能已在報告中，這次查到資料時以新的資料列取代
+    let resolved: HashSet<UserId> = new_pushes
+        .users
+        .iter()
+        .filter(|user| ptt_web_data.contains_key(*user))
+        .cloned()
+        .collect();
+    let new_account = &app_config.new_account;
+    let new_rows: Vec<UserReportData> = new_pushes
+        .users
+        .iter()
+        .map(|user| {
+            let data = ptt_web_data.remove(user);
+            // 查無資料或查詢失敗的使用者沒有資料，不能據此判斷為新帳號
+            let is_new_account = data
+                .as_ref()
+                .is_some_and(|data| data.total_comments < new_account.max_total_comments);
+            let data = data.unwrap_or_else(|| PttWebData {
+                board_comments: HashMap::new(),
+                total_comments: 0,
+                all_board_comments: HashMap::new(),
+            });
+            let articles_seen_in = state.history.seen_in(user, &url);
+            UserReportData {
+                user: user.clone(),
+                // 本文留言數與推文類型在下面與既有資料列一起更新
+                article_comments: 0,
+                board_comments: data.board_comments,
+                total_comments: data.total_comments,
+                all_board_comments: if options.full_board_distribution {
+                    data.all_board_comments
+                } else {
+                    HashMap::new()
+                },
+                push_types: Default::default(),
+                term_info: None,
+                fields: Default::default(),
+                watchlists: state.watchlists.lists_containing(user),
+                note: state.notes.get(user),
+                members: Vec::new(),
+                times_seen: articles_seen_in.len() as u32,
+                articles_seen_in,
+                recent_articles: Vec::new(),
+                first_push_latency_minutes: None,
+                floors: Vec::new(),
+                fast_responder: false,
+                is_new_account,
+            }
+        })
+        .collect();
+
+    let pipeline = analyzers::build_pipeline(
+        &options.analyzers,
+        &app_config.boards,
+        options.highlight_condition.as_deref(),
+    );
+    let report = state.jobs.update_result(report_id, |result| {
+        let mut rows: Vec<UserReportData> = result
+            .highlighted_data
+            .drain(..)
+            .chain(result.normal_data.drain(..))
+            .filter(|row| !resolved.contains(&row.user))
+            .collect();
+        let existing: HashSet<UserId> = rows.iter().map(|row| row.user.clone()).collect();
+        rows.extend(
+            new_rows
+                .into_iter()
+                .filter(|row| !existing.contains(&row.user)),
+        );
+        for row in &mut rows {
+            if let Some(&count) = article_data.user_comment_counts.get(&row.user) {
+                row.article_comments = count;
+            }
+            if let Some(&push_types) = article_data.user_push_types.get(&row.user) {
+                row.push_types = push_types;
+            }
+            if let Some(&latency) = article_data.first_push_latency.get(&row.user) {
+                row.first_push_latency_minutes = Some(latency);
+            }
+            if let Some(floors) = article_data.user_floors.get(&row.user) {
+                row.floors.clone_from(floors);
+            }
+        }
+        report::sort_rows(&mut rows, &app_config.sorting);
+        let mut draft = ReportDraft::new(rows);
+        analyzers::run_pipeline(&pipeline, &article_data, &mut draft);
+        result.highlighted_data = draft.highlighted;
+        result.normal_data = draft.normal;
+        result.sections = draft.sections;
+        // 每位使用者只保留最近一次的查詢結果
+        result.failed_users.retain(|failed| {
+            !resolved.contains(&failed.user)
+                && !not_found_users.contains(&failed.user)
+                && !failed_users.iter().any(|new| new.user == failed.user)
+        });
+        result.failed_users.extend(failed_users);
+        result.failed_users.sort_by(|a, b| a.user.cmp(&b.user));
+        result.not_found_users.retain(|user| {
+            !resolved.contains(user) && !result.failed_users.iter().any(|f| &f.user == user)
+        });
+        for user in not_found_users {
+            if !result.not_found_users.contains(&user) {
+                result.not_found_users.push(user);
+            }
+        }
+        result.not_found_users.sort();
+        result.warnings = article_data.warnings.clone();
+        // 作者可能在推文期間修改內文
+        result.metadata.body = article_data.body.clone();
+        result.metadata.edits = article_data.edits.clone();
+        result.metadata.total_floors = article_data.total_floors;
+        result.timeline = timeline::build(&article_data.timed_pushes, article_data.time_anchor());
+        result.summary = SummaryStats::compute(result);
+        if let Err(e) = state.history.record_snapshot(result) {
+            tracing::warn!(error = %e, "無法儲存文章快照");
+        }
+        serde_json::to_vec(&*result)
+    })?;
+
+    // 更新磁碟上的報告紀錄，之後從「最近的分析」開啟時包含新推文
+    if let (Some(key), Ok(report)) = (report_key, report) {
+        state.disk_cache.put(&key, &report);
+        if let Err(e) = state.history.set_report_key(&url, &key) {
+            tracing::warn!(error = %e, "無法儲存報告紀錄的位置");
+        }
+    }
+    Ok(Some(new_pushes))
+}
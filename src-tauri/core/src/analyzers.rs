@@ -0,0 +1,384 @@
+//! 分析結果的後處理：每次分析依設定組成分析器管線，依序在排序後的資料列上
+//! 計算附加欄位或整份報告的附加區塊。
+
+use crate::domain::{BoardName, UserId};
+use crate::report::{self, UserReportData};
+use crate::scraper::{self, PttArticleData};
+use jieba_rs::Jieba;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// 可在分析選項中啟用的內建分析器。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AnalyzerKind {
+    /// 依高亮條件將資料列分為高亮與一般兩組
+    Highlight,
+    /// 為每位使用者計算 `score` 欄位：在追蹤看板的留言佔生涯總留言的百分比
+    Scoring,
+    /// 將上次上站 IP 相同的使用者分組，產生 `ipGroups` 區塊 (需啟用 term.ptt.cc 查詢)
+    IpGroups,
+    /// 將 ID 只差在結尾數字或鍵盤序列的使用者分組 (例如 `abcd01`、`abcd02`)，產生 `idPatterns` 區塊
+    IdPatterns,
+    /// 找出短時間內大量新留言者湧入的時段 (可能為動員)，產生 `bursts` 區塊
+    Bursts,
+    /// 將推文內容斷詞並統計出現最多的詞，產生 `terms` 區塊
+    Terms,
+}
+
+/// 未指定時只做高亮，與加入管線前的行為相同。
+pub fn default_analyzers() -> Vec<AnalyzerKind> {
+    vec![AnalyzerKind::Highlight]
+}
+
+/// 管線中各分析器共同修改的報告內容；資料列已依設定排序。
+pub struct ReportDraft {
+    pub highlighted: Vec<UserReportData>,
+    pub normal: Vec<UserReportData>,
+    /// 整份報告的附加區塊，以分析器定義的名稱為鍵
+    pub sections: BTreeMap<String, Value>,
+}
+
+impl ReportDraft {
+    pub fn new(rows: Vec<UserReportData>) -> Self {
+        ReportDraft {
+            highlighted: vec![],
+            normal: rows,
+            sections: BTreeMap::new(),
+        }
+    }
+
+    /// 依序走訪所有資料列 (高亮資料列在前)。
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut UserReportData> {
+        self.highlighted.iter_mut().chain(self.normal.iter_mut())
+    }
+}
+
+/// 分析器：讀取解析後的文章與查詢完統計的資料列，
+/// 可在資料列的 `fields` 加入計算欄位，或在 `sections` 加入附加區塊。
+pub trait Analyzer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn run(&self, article: &PttArticleData, report: &mut ReportDraft);
+}
+
+pub struct HighlightAnalyzer {
+    condition: Option<String>,
+}
+
+impl Analyzer for HighlightAnalyzer {
+    fn name(&self) -> &'static str {
+        "highlight"
+    }
+
+    fn run(&self, _article: &PttArticleData, report: &mut ReportDraft) {
+        // 重新分組前先依原本的順序合併，管線中重複高亮時結果不變
+        let mut rows = std::mem::take(&mut report.highlighted);
+        rows.append(&mut report.normal);
+        (report.highlighted, report.normal) =
+            report::split_highlighted(rows, self.condition.as_deref());
+    }
+}
+
+pub struct ScoringAnalyzer {
+    boards: Vec<BoardName>,
+}
+
+impl Analyzer for ScoringAnalyzer {
+    fn name(&self) -> &'static str {
+        "scoring"
+    }
+
+    fn run(&self, article: &PttArticleData, report: &mut ReportDraft) {
+        let mut boards: Vec<&BoardName> = self.boards.iter().collect();
+        if !boards.contains(&&article.board) {
+            boards.push(&article.board);
+        }
+        for row in report.rows_mut() {
+            let tracked: u32 = boards
+                .iter()
+                .filter_map(|board| row.board_comments.get(*board))
+                .sum();
+            // 查無生涯留言 (查詢失敗或新帳號) 時沒有分數
+            let score = (row.total_comments > 0)
+                .then(|| (tracked as f64 / row.total_comments as f64 * 1000.0).round() / 10.0);
+            row.fields.insert("score".to_string(), json!(score));
+        }
+    }
+}
+
+pub struct IpGroupsAnalyzer;
+
+impl Analyzer for IpGroupsAnalyzer {
+    fn name(&self) -> &'static str {
+        "ipGroups"
+    }
+
+    fn run(&self, _article: &PttArticleData, report: &mut ReportDraft) {
+        let mut groups: HashMap<&str, Vec<&UserId>> = HashMap::new();
+        for row in report.highlighted.iter().chain(&report.normal) {
+            if let Some(ip) = row
+                .term_info
+                .as_ref()
+                .and_then(|info| info.last_login_ip.as_deref())
+            {
+                groups.entry(ip).or_default().push(&row.user);
+            }
+        }
+        // 只列出多位使用者共用的 IP，人數多的在前
+        let mut shared: Vec<(&str, Vec<&UserId>)> = groups
+            .into_iter()
+            .filter(|(_, users)| users.len() > 1)
+            .collect();
+        shared.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        let section = shared
+            .into_iter()
+            .map(|(ip, users)| json!({ "ip": ip, "users": users }))
+            .collect();
+        report
+            .sections
+            .insert(self.name().to_string(), Value::Array(section));
+    }
+}
+
+/// 常見的鍵盤序列，帳號農場常以此接在相同的字首後。
+const KEYBOARD_SUFFIXES: &[&str] = &[
+    "qwerty", "qwer", "qwe", "asdf", "asd", "zxcv", "zxc", "1qaz", "2wsx", "abc", "xyz",
+];
+/// 去掉結尾後至少要剩下的字元數，太短的字首容易誤判。
+const MIN_ID_STEM_LEN: usize = 3;
+
+/// 去掉結尾的數字與鍵盤序列後的 ID 字首 (小寫)；字首太短時為 `None`。
+pub fn id_pattern_stem(user: &UserId) -> Option<String> {
+    let lower = user.normalized();
+    let mut stem = lower.trim_end_matches(|c: char| c.is_ascii_digit());
+    if let Some(rest) = KEYBOARD_SUFFIXES
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+    {
+        stem = rest.trim_end_matches(|c: char| c.is_ascii_digit());
+    }
+    (stem.len() >= MIN_ID_STEM_LEN).then(|| stem.to_string())
+}
+
+pub struct IdPatternsAnalyzer;
+
+impl Analyzer for IdPatternsAnalyzer {
+    fn name(&self) -> &'static str {
+        "idPatterns"
+    }
+
+    fn run(&self, _article: &PttArticleData, report: &mut ReportDraft) {
+        let mut groups: HashMap<String, Vec<&UserId>> = HashMap::new();
+        for row in report.highlighted.iter().chain(&report.normal) {
+            if let Some(stem) = id_pattern_stem(&row.user) {
+                groups.entry(stem).or_default().push(&row.user);
+            }
+        }
+        // 只列出多位使用者共用的字首，人數多的在前
+        let mut shared: Vec<(String, Vec<&UserId>)> = groups
+            .into_iter()
+            .filter(|(_, users)| users.len() > 1)
+            .collect();
+        shared.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+        let section = shared
+            .into_iter()
+            .map(|(pattern, mut users)| {
+                users.sort();
+                json!({ "pattern": pattern, "users": users })
+            })
+            .collect();
+        report
+            .sections
+            .insert(self.name().to_string(), Value::Array(section));
+    }
+}
+
+/// 計算新留言者數量的滑動視窗長度 (分鐘)。
+const BURST_WINDOW_MINUTES: u32 = 5;
+/// 視窗內的新留言者數超過平均值幾個標準差才算異常。
+const BURST_Z_THRESHOLD: f64 = 3.0;
+/// 視窗內至少要有幾位新留言者才列出，避免冷門文章的少量留言被誤判。
+const MIN_BURST_USERS: usize = 5;
+
+pub struct BurstsAnalyzer;
+
+impl Analyzer for BurstsAnalyzer {
+    fn name(&self) -> &'static str {
+        "bursts"
+    }
+
+    fn run(&self, article: &PttArticleData, report: &mut ReportDraft) {
+        report.sections.insert(
+            self.name().to_string(),
+            Value::Array(detect_bursts(article)),
+        );
+    }
+}
+
+/// 以每位留言者第一則推文的時間為準，統計每個滑動視窗內的新留言者數，
+/// 找出明顯高於全文平均的視窗，重疊的視窗合併為同一個時段。
+/// 推文時間以發文時間為起點換算 (見 [`scraper::minute_after`])，跨年的文章不會把年初的推文排到最前面。
+fn detect_bursts(article: &PttArticleData) -> Vec<Value> {
+    let Some(anchor) = article.time_anchor() else {
+        return Vec::new();
+    };
+    let mut first_seen: HashMap<&UserId, u32> = HashMap::new();
+    for push in &article.timed_pushes {
+        let pushed = scraper::minute_after(anchor, push.minute);
+        let minute = first_seen.entry(&push.user).or_insert(pushed);
+        *minute = (*minute).min(pushed);
+    }
+    let (Some(&first), Some(&last)) = (first_seen.values().min(), first_seen.values().max()) else {
+        return Vec::new();
+    };
+
+    let mut arrivals = vec![0usize; (last - first + 1) as usize];
+    for &minute in first_seen.values() {
+        arrivals[(minute - first) as usize] += 1;
+    }
+    let window = (BURST_WINDOW_MINUTES as usize).min(arrivals.len());
+    let counts: Vec<usize> = arrivals
+        .windows(window)
+        .map(|minutes| minutes.iter().sum())
+        .collect();
+    let mean = counts.iter().sum::<usize>() as f64 / counts.len() as f64;
+    let std_dev = (counts
+        .iter()
+        .map(|&count| (count as f64 - mean).powi(2))
+        .sum::<f64>()
+        / counts.len() as f64)
+        .sqrt();
+    if std_dev == 0.0 {
+        return Vec::new();
+    }
+
+    // (起始分鐘, 結束分鐘 (含), 最高 z 分數)
+    let mut ranges: Vec<(u32, u32, f64)> = Vec::new();
+    for (offset, &count) in counts.iter().enumerate() {
+        let z = (count as f64 - mean) / std_dev;
+        if z < BURST_Z_THRESHOLD || count < MIN_BURST_USERS {
+            continue;
+        }
+        let start = first + offset as u32;
+        let end = start + window as u32 - 1;
+        match ranges.last_mut() {
+            Some(range) if start <= range.1 + 1 => {
+                range.1 = end;
+                range.2 = range.2.max(z);
+            }
+            _ => ranges.push((start, end, z)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end, z)| {
+            let mut users: Vec<&UserId> = first_seen
+                .iter()
+                .filter(|(_, &minute)| (start..=end).contains(&minute))
+                .map(|(user, _)| *user)
+                .collect();
+            users.sort();
+            json!({
+                "start": scraper::format_minute_of_year(start),
+                "end": scraper::format_minute_of_year(end),
+                "newCommenters": users.len(),
+                "zScore": (z * 100.0).round() / 100.0,
+                "users": users,
+            })
+        })
+        .collect()
+}
+
+/// `terms` 區塊最多列出的詞數。
+const TOP_TERMS_LIMIT: usize = 50;
+/// 不列入統計的常見虛詞與推文用語。
+const STOP_WORDS: &[&str] = &[
+    "的", "了", "是", "在", "我", "你", "他", "就", "都", "也", "不", "有", "這", "那", "啊", "吧",
+    "嗎", "呢", "喔", "啦", "什麼", "一個", "沒有", "自己", "就是", "還是", "可以", "不是", "這樣",
+    "真的", "還有", "http", "https",
+];
+
+/// 斷詞用的字典載入較慢，第一次使用時才建立並由所有分析共用。
+static JIEBA: Lazy<Jieba> = Lazy::new(Jieba::new);
+
+pub struct TermsAnalyzer;
+
+impl Analyzer for TermsAnalyzer {
+    fn name(&self) -> &'static str {
+        "terms"
+    }
+
+    fn run(&self, article: &PttArticleData, report: &mut ReportDraft) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for content in &article.push_contents {
+            for word in JIEBA.cut(content, false) {
+                let word = word.trim().to_lowercase();
+                // 單一字元多為虛詞或標點，不列入
+                if word.chars().count() < 2
+                    || word.chars().all(|c| !c.is_alphanumeric())
+                    || STOP_WORDS.contains(&word.as_str())
+                {
+                    continue;
+                }
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+        let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+        terms.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let section = terms
+            .into_iter()
+            .take(TOP_TERMS_LIMIT)
+            .map(|(term, count)| json!({ "term": term, "count": count }))
+            .collect();
+        report
+            .sections
+            .insert(self.name().to_string(), Value::Array(section));
+    }
+}
+
+/// 依設定的順序建立分析器管線，重複的分析器只保留第一個。
+pub fn build_pipeline(
+    kinds: &[AnalyzerKind],
+    boards: &[BoardName],
+    highlight_condition: Option<&str>,
+) -> Vec<Box<dyn Analyzer>> {
+    let mut seen = Vec::new();
+    kinds
+        .iter()
+        .filter(|kind| {
+            let first = !seen.contains(*kind);
+            seen.push(**kind);
+            first
+        })
+        .map(|kind| -> Box<dyn Analyzer> {
+            match kind {
+                AnalyzerKind::Highlight => Box::new(HighlightAnalyzer {
+                    condition: highlight_condition.map(str::to_string),
+                }),
+                AnalyzerKind::Scoring => Box::new(ScoringAnalyzer {
+                    boards: boards.to_vec(),
+                }),
+                AnalyzerKind::IpGroups => Box::new(IpGroupsAnalyzer),
+                AnalyzerKind::IdPatterns => Box::new(IdPatternsAnalyzer),
+                AnalyzerKind::Bursts => Box::new(BurstsAnalyzer),
+                AnalyzerKind::Terms => Box::new(TermsAnalyzer),
+            }
+        })
+        .collect()
+}
+
+/// 依序執行管線中的分析器。
+pub fn run_pipeline(
+    pipeline: &[Box<dyn Analyzer>],
+    article: &PttArticleData,
+    report: &mut ReportDraft,
+) {
+    for analyzer in pipeline {
+        let _span = tracing::debug_span!("analyzer", name = analyzer.name()).entered();
+        analyzer.run(article, report);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::error::Error;
+use crate::sources::{self, MirrorPage, MirrorResolution};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+
+/// 文章路徑：`/bbs/<看板>/<文章 ID>`，文章 ID 形如 `M.1700000000.A.1B2`，`.html` 可有可無。
+static ARTICLE_PATH_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^/bbs/([A-Za-z0-9_\-]+)/(M\.\d+\.A\.[0-9A-Fa-f]{1,4})(?:\.html?)?/?$").unwrap()
+});
+
+/// 指向一篇 PTT 文章的看板與文章 ID。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleRef {
+    pub board: String,
+    pub article_id: String,
+}
+
+impl ArticleRef {
+    /// 官方網頁版 (www.ptt.cc) 的標準文章網址。
+    pub fn canonical_url(&self) -> String {
+        format!(
+            "https://www.ptt.cc/bbs/{}/{}.html",
+            self.board, self.article_id
+        )
+    }
+
+    /// pttweb.cc 上對應的文章鏡像網址。
+    pub fn pttweb_mirror_url(&self) -> String {
+        format!(
+            "https://www.pttweb.cc/bbs/{}/{}",
+            self.board, self.article_id
+        )
+    }
+}
+
+/// 判斷是否為 PTT 官方網站 (ptt.cc 與其子網域，例如 www / web / term)。
+fn is_official_host(host: &str) -> bool {
+    host == "ptt.cc" || host.ends_with(".ptt.cc")
+}
+
+/// 判斷是否為 pttweb.cc 鏡像站，其文章網址 `/bbs/<看板>/<文章 ID>` 與官方網站相同。
+fn is_pttweb_host(host: &str) -> bool {
+    host == "pttweb.cc" || host.ends_with(".pttweb.cc")
+}
+
+/// 解析任何形式的官方 PTT 文章網址 (不同子網域、http/https、有無 `.html`、
+/// 帶查詢字串或錨點)，取出看板與文章 ID。pttweb.cc 的文章連結也會對應回原文。
+pub fn parse_article_url(input: &str) -> Result<ArticleRef, Error> {
+    let trimmed = input.trim();
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+
+    let invalid = || Error::InvalidArticleUrl(trimmed.to_string());
+    let url = Url::parse(&with_scheme).map_err(|_| invalid())?;
+    let host = url.host_str().ok_or_else(invalid)?.to_ascii_lowercase();
+    if !is_official_host(&host) && !is_pttweb_host(&host) {
+        return Err(invalid());
+    }
+
+    let caps = ARTICLE_PATH_RE.captures(url.path()).ok_or_else(invalid)?;
+    Ok(ArticleRef {
+        board: caps[1].to_string(),
+        article_id: caps[2].to_string(),
+    })
+}
+
+/// 將使用者輸入的文章網址或文章代碼正規化為 www.ptt.cc 的標準網址。
+pub fn normalize_article_url(input: &str) -> Result<(ArticleRef, String), Error> {
+    let article = resolve_article_input(input)?;
+    let url = article.canonical_url();
+    Ok((article, url))
+}
+
+/// PTT 文章代碼 (AID) 使用的 64 進位字元表。
+const AID_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+
+/// 文章代碼加看板，例如 `#1bXyZ123 (Gossiping)`、`Gossiping #1bXyZ123`、`1bXyZ123 Gossiping`。
+static AID_INPUT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:(?P<board_before>[A-Za-z0-9_\-]+)\s+)?#?(?P<aid>[0-9A-Za-z\-_]{8})(?:\s*\(?\s*(?P<board_after>[A-Za-z0-9_\-]+)\s*\)?)?$",
+    )
+    .unwrap()
+});
+
+/// 將 8 碼的文章代碼 (AID) 轉換為文章 ID (檔名)，例如 `1bXyZ123` → `M.1700000000.A.123`。
+pub fn aid_to_article_id(aid: &str) -> Option<String> {
+    if aid.len() != 8 {
+        return None;
+    }
+    let mut value: u64 = 0;
+    for byte in aid.bytes() {
+        let digit = AID_ALPHABET.iter().position(|&c| c == byte)? as u64;
+        value = (value << 6) | digit;
+    }
+
+    // 48 bits：最高 4 bits 為檔案類型 (0 = M, 1 = G)，接著 32 bits 時間戳記，最後 12 bits 為亂數
+    let prefix = match value >> 44 {
+        0 => "M",
+        1 => "G",
+        _ => return None,
+    };
+    let timestamp = (value >> 12) & 0xFFFF_FFFF;
+    let random = value & 0xFFF;
+    Some(format!("{}.{}.A.{:03X}", prefix, timestamp, random))
+}
+
+/// 解析使用者在網址欄輸入的內容：可以是任何官方文章網址、網址帶有文章 ID 的鏡像網址，
+/// 或是文章代碼加上看板。
+pub fn resolve_article_input(input: &str) -> Result<ArticleRef, Error> {
+    let trimmed = input.trim();
+    if let Ok(article) = parse_article_url(trimmed) {
+        return Ok(article);
+    }
+    if let Some(MirrorResolution::Canonical(article)) = sources::resolve_mirror_url(trimmed) {
+        return Ok(article);
+    }
+
+    let invalid = || Error::InvalidArticleUrl(trimmed.to_string());
+    let caps = AID_INPUT_RE.captures(trimmed).ok_or_else(invalid)?;
+    let board = caps
+        .name("board_before")
+        .or_else(|| caps.name("board_after"))
+        .ok_or_else(invalid)?
+        .as_str()
+        .to_string();
+    let article_id = aid_to_article_id(&caps["aid"]).ok_or_else(invalid)?;
+
+    Ok(ArticleRef { board, article_id })
+}
+
+/// 分析的輸入來源：線上文章、無法對應回原文的鏡像頁面，或本機儲存的 HTML 檔。
+#[derive(Debug, Clone)]
+pub enum ArticleInput {
+    Remote(ArticleRef),
+    Mirror(MirrorPage),
+    LocalFile(PathBuf),
+}
+
+/// 判斷輸入是否為本機 HTML 檔 (`file://` 網址，或存在的 .html / .htm 路徑)。
+pub fn local_file_path(input: &str) -> Option<PathBuf> {
+    let trimmed = input.trim();
+    if trimmed.starts_with("file://") {
+        return Url::parse(trimmed).ok()?.to_file_path().ok();
+    }
+
+    let path = Path::new(trimmed);
+    let is_html = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+    (is_html && path.is_file()).then(|| path.to_path_buf())
+}
+
+/// 解析網址欄的輸入，回傳輸入來源與要顯示在報告中的網址 (或檔案路徑)。
+pub fn resolve_input(input: &str) -> Result<(ArticleInput, String), Error> {
+    if let Some(path) = local_file_path(input) {
+        let display = path.display().to_string();
+        return Ok((ArticleInput::LocalFile(path), display));
+    }
+    resolve_remote_input(input)
+}
+
+/// 同 [`resolve_input`]，但只接受 ptt.cc 網址、文章代碼與鏡像站網址，不接受本機檔案。
+/// 用於來自外部的輸入 (例如網頁觸發的 `ptt-analyzer://` 連結)：檢查路徑是否存在就可能連線到
+/// 對方指定的網路磁碟 (Windows 的 UNC 路徑會送出登入憑證)。
+pub fn resolve_remote_input(input: &str) -> Result<(ArticleInput, String), Error> {
+    match normalize_article_url(input) {
+        Ok((article, url)) => Ok((ArticleInput::Remote(article), url)),
+        Err(e) => match sources::resolve_mirror_url(input) {
+            Some(MirrorResolution::Page(page)) => {
+                let url = page.url.clone();
+                Ok((ArticleInput::Mirror(page), url))
+            }
+            _ => Err(e),
+        },
+    }
+}
@@ -0,0 +1,120 @@
+use crate::config::{QuietHoursConfig, QuietHoursMode};
+use crate::diagnostics::unix_now;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Semaphore;
+use tokio::time::{sleep, sleep_until, Duration, Instant};
+
+/// 第一次被限流時的等待時間，之後每次連續被限流加倍。
+const BASE_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// 排隊中的工作最多同時抓取幾篇文章頁面。
+pub const ARTICLE_FETCH_LIMIT: usize = 3;
+/// 暫停時段中重新檢查時段是否結束的間隔。
+const QUIET_HOURS_RECHECK: Duration = Duration::from_secs(60);
+
+/// 單一站台的限流退避狀態：被回應 429 後，所有對該站台的請求都暫停到指定時間。
+pub struct HostBackoff {
+    host: &'static str,
+    blocked_until: Mutex<Option<Instant>>,
+    consecutive_hits: AtomicU32,
+}
+
+impl HostBackoff {
+    pub const fn new(host: &'static str) -> Self {
+        HostBackoff {
+            host,
+            blocked_until: Mutex::new(None),
+            consecutive_hits: AtomicU32::new(0),
+        }
+    }
+
+    pub fn host(&self) -> &'static str {
+        self.host
+    }
+
+    /// 若站台目前處於退避期間，等待到退避結束。
+    pub async fn wait_ready(&self) {
+        let blocked_until = *self.blocked_until.lock().unwrap();
+        if let Some(until) = blocked_until {
+            sleep_until(until).await;
+        }
+    }
+
+    /// 記錄一次限流並回傳需要等待的時間；伺服器有提供 Retry-After 時優先採用。
+    pub fn on_rate_limited(&self, retry_after: Option<Duration>) -> Duration {
+        let hits = self.consecutive_hits.fetch_add(1, Ordering::SeqCst);
+        let wait = retry_after
+            .unwrap_or_else(|| BASE_BACKOFF.saturating_mul(1 << hits.min(5)))
+            .min(MAX_BACKOFF);
+
+        let until = Instant::now() + wait;
+        let mut blocked_until = self.blocked_until.lock().unwrap();
+        if blocked_until.is_none_or(|current| current < until) {
+            *blocked_until = Some(until);
+        }
+        wait
+    }
+
+    pub fn on_success(&self) {
+        self.consecutive_hits.store(0, Ordering::SeqCst);
+    }
+}
+
+/// 背景工作的安靜時段：背景工作發出請求前經由此等待，或依此延長檢查間隔。
+#[derive(Default)]
+pub struct QuietHours {
+    config: Mutex<QuietHoursConfig>,
+}
+
+impl QuietHours {
+    pub fn set_config(&self, config: QuietHoursConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    pub fn current_mode(&self) -> Option<QuietHoursMode> {
+        self.config.lock().unwrap().mode_at(unix_now())
+    }
+
+    /// 暫停時段中等到時段結束；其他時候立即返回。
+    pub async fn wait_background(&self) {
+        while self.current_mode() == Some(QuietHoursMode::Pause) {
+            sleep(QUIET_HOURS_RECHECK).await;
+        }
+    }
+
+    /// 背景工作在目前時段的檢查間隔：放慢時段中為 `base` 的 `slow_factor` 倍。
+    pub fn background_interval(&self, base: Duration) -> Duration {
+        base * self.interval_factor()
+    }
+
+    /// 目前時段的檢查間隔倍數，不在放慢時段時為 1。
+    pub fn interval_factor(&self) -> u32 {
+        let config = self.config.lock().unwrap();
+        match config.mode_at(unix_now()) {
+            Some(QuietHoursMode::Slow) => config.slow_factor.max(1),
+            _ => 1,
+        }
+    }
+}
+
+/// 放在 Tauri managed state 中，各站台共用的限流退避狀態。
+pub struct RateLimiters {
+    pub pttweb: HostBackoff,
+    pub ptt: HostBackoff,
+    /// 同時抓取文章頁面的數量上限 (批次分析時多個工作會同時抓取)
+    pub article_fetches: Semaphore,
+    /// 背景工作的安靜時段，手動執行的分析不受影響
+    pub quiet_hours: QuietHours,
+}
+
+impl Default for RateLimiters {
+    fn default() -> Self {
+        RateLimiters {
+            pttweb: HostBackoff::new("www.pttweb.cc"),
+            ptt: HostBackoff::new("www.ptt.cc"),
+            article_fetches: Semaphore::new(ARTICLE_FETCH_LIMIT),
+            quiet_hours: QuietHours::default(),
+        }
+    }
+}
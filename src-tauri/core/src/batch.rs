@@ -0,0 +1,103 @@
+use crate::article_url;
+use crate::error::Error;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 網址清單中一行可分析的文章。
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// 檔案中的行號 (1 起算)
+    pub line: usize,
+    /// 正規化後的文章網址，作為分析的輸入
+    pub url: String,
+}
+
+/// 無法解析的一行，連同錯誤碼回報給前端。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineError {
+    pub line: usize,
+    pub content: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// 與前面某一行指向同一篇文章而被略過的行。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateLine {
+    pub line: usize,
+    pub url: String,
+    /// 第一次出現的行號
+    pub first_line: usize,
+}
+
+/// 解析網址清單檔的結果。
+#[derive(Debug, Default)]
+pub struct ParsedUrlList {
+    pub entries: Vec<BatchEntry>,
+    pub duplicates: Vec<DuplicateLine>,
+    pub errors: Vec<LineError>,
+}
+
+/// 讀取網址清單檔 (純文字一行一個網址，或 CSV 取第一欄)。
+pub fn read_url_list(path: &Path) -> Result<ParsedUrlList, Error> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::LocalFile {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(parse_url_list(&content))
+}
+
+/// 逐行驗證並去除重複的文章，空行、`#` 開頭的註解與 CSV 標題列會被略過。
+pub fn parse_url_list(content: &str) -> ParsedUrlList {
+    let mut parsed = ParsedUrlList::default();
+    // 正規化後的網址 → 第一次出現的行號
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line = index + 1;
+        let field = first_field(raw_line);
+        if field.is_empty() || field.starts_with('#') || is_header(field) {
+            continue;
+        }
+
+        match article_url::resolve_input(field) {
+            Ok((_, url)) => match seen.get(&url) {
+                Some(&first_line) => parsed.duplicates.push(DuplicateLine {
+                    line,
+                    url,
+                    first_line,
+                }),
+                None => {
+                    seen.insert(url.clone(), line);
+                    parsed.entries.push(BatchEntry { line, url });
+                }
+            },
+            Err(e) => parsed.errors.push(LineError {
+                line,
+                content: raw_line.trim().to_string(),
+                code: e.code(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    parsed
+}
+
+/// 取出 CSV 的第一欄並去除前後空白與引號；純文字清單則是整行。
+fn first_field(line: &str) -> &str {
+    let line = line.trim().trim_start_matches('\u{feff}');
+    let field = line.split([',', '\t']).next().unwrap_or("");
+    field.trim().trim_matches('"').trim()
+}
+
+/// CSV 第一列常見的欄位名稱。
+fn is_header(field: &str) -> bool {
+    matches!(
+        field.to_ascii_lowercase().as_str(),
+        "url" | "urls" | "link" | "article" | "網址" | "文章"
+    )
+}
@@ -0,0 +1,84 @@
+//! 留言者在各看板活動的彙整，需在分析選項中啟用 `full_board_distribution` 取得完整的看板分布。
+
+use crate::domain::BoardName;
+use crate::report::UserReportData;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// 看板留言佔生涯總留言至少此比例，才算「經常在此看板留言」。
+const HEAVY_BOARD_SHARE: f64 = 0.1;
+/// 最多列出的看板數。
+const TOP_BOARDS_LIMIT: usize = 20;
+/// 每位留言者留言最多的前幾個看板視為其常駐看板。
+const HOME_BOARDS_PER_USER: usize = 5;
+
+/// 留言者中有多少人經常在某個看板留言 (或以此為常駐看板)。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardOverlap {
+    pub board: BoardName,
+    pub commenters: usize,
+    /// 佔有完整看板分布的留言者的比例 (0 ~ 1)
+    pub share: f64,
+}
+
+/// 追蹤看板以外，最多留言者經常留言的看板，人數多的在前。
+pub fn board_overlap(rows: &[&UserReportData]) -> Vec<BoardOverlap> {
+    let tracked: HashSet<&BoardName> = rows
+        .iter()
+        .flat_map(|row| row.board_comments.keys())
+        .collect();
+    let with_distribution: Vec<&&UserReportData> = rows
+        .iter()
+        .filter(|row| !row.all_board_comments.is_empty() && row.total_comments > 0)
+        .collect();
+
+    let mut commenters: HashMap<&BoardName, usize> = HashMap::new();
+    for row in &with_distribution {
+        for (board, &count) in &row.all_board_comments {
+            let share = count as f64 / row.total_comments as f64;
+            if share >= HEAVY_BOARD_SHARE && !tracked.contains(board) {
+                *commenters.entry(board).or_insert(0) += 1;
+            }
+        }
+    }
+    rank(commenters, with_distribution.len())
+}
+
+/// 依有多少留言者將看板列在留言數前 5 名排序 (包含追蹤看板)，
+/// 不必事先猜測看板，即可看出一波留言來自哪個社群。
+pub fn home_boards(rows: &[&UserReportData]) -> Vec<BoardOverlap> {
+    let mut with_distribution = 0;
+    let mut commenters: HashMap<&BoardName, usize> = HashMap::new();
+    for row in rows {
+        if row.all_board_comments.is_empty() {
+            continue;
+        }
+        with_distribution += 1;
+        let mut boards: Vec<(&BoardName, u32)> = row
+            .all_board_comments
+            .iter()
+            .map(|(board, &count)| (board, count))
+            .collect();
+        boards.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        for (board, _) in boards.into_iter().take(HOME_BOARDS_PER_USER) {
+            *commenters.entry(board).or_insert(0) += 1;
+        }
+    }
+    rank(commenters, with_distribution)
+}
+
+/// 依人數 (其次依看板名稱) 排序並取前幾名。
+fn rank(commenters: HashMap<&BoardName, usize>, total: usize) -> Vec<BoardOverlap> {
+    let mut ranked: Vec<(&BoardName, usize)> = commenters.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    ranked
+        .into_iter()
+        .take(TOP_BOARDS_LIMIT)
+        .map(|(board, commenters)| BoardOverlap {
+            board: board.clone(),
+            commenters,
+            share: commenters as f64 / total.max(1) as f64,
+        })
+        .collect()
+}
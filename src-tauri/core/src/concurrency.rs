@@ -0,0 +1,86 @@
+use crate::error::Error;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+pub const MIN_CONCURRENCY: usize = 1;
+pub const MAX_CONCURRENCY: usize = 32;
+/// 回應時間低於此值才視為「快速」，可以繼續提高併發數。
+const FAST_RESPONSE: Duration = Duration::from_millis(1500);
+
+/// AIMD (加法增加、乘法減少) 併發控制：回應快速且無錯誤時逐步提高併發數，
+/// 遇到逾時或限流時減半。
+pub struct AdaptiveConcurrency {
+    state: Mutex<State>,
+    released: Notify,
+}
+
+struct State {
+    /// 以浮點數累加，每次成功增加 1/limit，約等於每輪請求增加 1
+    limit: f64,
+    in_flight: usize,
+}
+
+/// 請求進行期間持有，離開作用域時釋放併發名額。
+pub struct ConcurrencyPermit<'a> {
+    controller: &'a AdaptiveConcurrency,
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        self.controller.state.lock().unwrap().in_flight -= 1;
+        self.controller.released.notify_waiters();
+    }
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(initial: usize) -> Self {
+        AdaptiveConcurrency {
+            state: Mutex::new(State {
+                limit: initial.clamp(MIN_CONCURRENCY, MAX_CONCURRENCY) as f64,
+                in_flight: 0,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// 目前允許的併發數。
+    pub fn current(&self) -> usize {
+        self.state.lock().unwrap().limit as usize
+    }
+
+    /// 等待直到進行中的請求數低於目前的併發上限。
+    pub async fn acquire(&self) -> ConcurrencyPermit<'_> {
+        loop {
+            // 先建立 future 再檢查，避免錯過檢查與等待之間的釋放通知
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.in_flight < state.limit as usize {
+                    state.in_flight += 1;
+                    return ConcurrencyPermit { controller: self };
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// 依請求結果調整併發上限。
+    pub fn record<T>(&self, latency: Duration, result: &Result<T, Error>) {
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Ok(_) | Err(Error::PttWebUserNotFound(_)) => {
+                if latency <= FAST_RESPONSE {
+                    state.limit = (state.limit + 1.0 / state.limit).min(MAX_CONCURRENCY as f64);
+                }
+            }
+            Err(e) if e.is_retryable() => {
+                state.limit = (state.limit / 2.0).max(MIN_CONCURRENCY as f64);
+            }
+            Err(_) => {}
+        }
+        drop(state);
+        // 上限提高時讓等待中的查詢有機會開始
+        self.released.notify_waiters();
+    }
+}
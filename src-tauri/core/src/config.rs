@@ -0,0 +1,431 @@
+// src-tauri/core/src/config.rs
+
+use crate::domain::BoardName;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 設定檔名稱，存放於 app config 目錄下。
+pub const CONFIG_FILE_NAME: &str = "config.json";
+
+// (新增) 也加上 Serialize，讓這個結構體可以在 Rust 端與前端之間雙向傳遞
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SortingConfig {
+    pub sort_by: String,
+    pub order: String, // "asc" or "desc"
+}
+
+/// 日誌相關設定。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggingConfig {
+    /// tracing 的過濾等級，例如 "info"、"debug" 或 "ptt_analyzer_core=trace"。
+    pub level: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: "info".to_string(),
+        }
+    }
+}
+
+/// 已有分析在執行時，新的分析要排隊等待還是直接拒絕。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConcurrentAnalysisPolicy {
+    #[default]
+    Queue,
+    Reject,
+}
+
+/// 使用者留言統計的資料來源。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UserStatsSource {
+    /// 先前查詢結果的本機快取
+    Cache,
+    /// 即時查詢 pttweb.cc
+    PttWeb,
+}
+
+fn default_cache_max_mb() -> u64 {
+    200
+}
+
+/// 預設先查快取，沒有資料時再查 pttweb.cc。
+fn default_user_stats_sources() -> Vec<UserStatsSource> {
+    vec![UserStatsSource::Cache, UserStatsSource::PttWeb]
+}
+
+/// 登入 term.ptt.cc 查詢使用者註冊日期、登入次數與上次上站 IP 的設定，預設關閉。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PttTermConfig {
+    pub enabled: bool,
+    pub username: String,
+    pub password: String,
+}
+
+// 避免密碼出現在日誌中
+impl std::fmt::Debug for PttTermConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PttTermConfig")
+            .field("enabled", &self.enabled)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+fn default_http_api_port() -> u16 {
+    17878
+}
+
+/// 本機 HTTP API 的設定 (需以 `http-api` feature 建置)，預設關閉。
+/// 只監聽 127.0.0.1，且每個請求都必須以 `Authorization: Bearer <token>` 附上 `token`。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiConfig {
+    pub enabled: bool,
+    #[serde(default = "default_http_api_port")]
+    pub port: u16,
+    /// 未設定 (空字串) 時不啟動 API
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        HttpApiConfig {
+            enabled: false,
+            port: default_http_api_port(),
+            token: String::new(),
+        }
+    }
+}
+
+// 避免 token 出現在日誌中
+impl std::fmt::Debug for HttpApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpApiConfig")
+            .field("enabled", &self.enabled)
+            .field("port", &self.port)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+fn default_global_shortcut() -> String {
+    "CmdOrCtrl+Shift+P".to_string()
+}
+
+/// 分析剪貼簿網址的全域快捷鍵，預設關閉；格式如 `CmdOrCtrl+Shift+P`。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalShortcutConfig {
+    pub enabled: bool,
+    #[serde(default = "default_global_shortcut")]
+    pub shortcut: String,
+}
+
+impl Default for GlobalShortcutConfig {
+    fn default() -> Self {
+        GlobalShortcutConfig {
+            enabled: false,
+            shortcut: default_global_shortcut(),
+        }
+    }
+}
+
+/// 背景執行的設定：關閉視窗時縮到系統匣，讓佇列中的分析繼續執行。
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundConfig {
+    /// 登入系統時自動啟動，啟動時不顯示視窗
+    pub autostart: bool,
+    /// 關閉視窗時隱藏到系統匣而不結束 app
+    pub close_to_tray: bool,
+}
+
+/// 系統通知的設定。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfig {
+    /// 分析結果中出現觀察名單上的使用者時發送系統通知
+    pub watchlist_matches: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            watchlist_matches: true,
+        }
+    }
+}
+
+/// 判斷「新帳號」的條件，符合任一項即標記。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAccountConfig {
+    /// 生涯總留言數低於此值
+    pub max_total_comments: u32,
+    /// 帳號建立未滿此天數，需啟用 term.ptt.cc 查詢才有註冊日期
+    pub max_account_age_days: u64,
+}
+
+impl Default for NewAccountConfig {
+    fn default() -> Self {
+        NewAccountConfig {
+            max_total_comments: 50,
+            max_account_age_days: 180,
+        }
+    }
+}
+
+/// 熱門文章監看：定期檢查看板文章列表，推文數達到門檻的文章發送通知。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotArticleConfig {
+    /// 監看的看板，空白時不監看
+    pub boards: Vec<BoardName>,
+    /// 推文數門檻，預設為「爆」(100)
+    pub min_push_count: i32,
+    /// 檢查間隔 (分鐘)
+    pub interval_minutes: u32,
+    /// 達到門檻的文章自動加入分析佇列
+    pub auto_analyze: bool,
+}
+
+impl Default for HotArticleConfig {
+    fn default() -> Self {
+        HotArticleConfig {
+            boards: Vec::new(),
+            min_push_count: 100,
+            interval_minutes: 5,
+            auto_analyze: false,
+        }
+    }
+}
+
+/// 台灣時間 (PTT 使用的時區) 與 UTC 的時差 (秒)；設定中的整點都以台灣時間表示。
+pub(crate) const TAIWAN_UTC_OFFSET_SECS: u64 = 8 * 60 * 60;
+
+/// app 關閉期間錯過的排程在啟動時的處理方式。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MissedSchedulePolicy {
+    /// 立即補執行一次 (錯過多次也只執行一次)
+    #[default]
+    RunImmediately,
+    /// 略過，從啟動時起重新計算間隔
+    Skip,
+}
+
+/// 安靜時段內背景工作 (排程、追蹤新推文、熱門文章監看) 的處理方式。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuietHoursMode {
+    /// 暫停，時段結束後再執行
+    Pause,
+    /// 檢查間隔延長為 `slow_factor` 倍
+    Slow,
+}
+
+/// 一個安靜時段，`start_hour` 到 `end_hour` (台灣時間的整點，不含結束的整點)；
+/// `end_hour` 小於 `start_hour` 時跨越午夜，例如 22 到 7。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursWindow {
+    pub start_hour: u8,
+    pub end_hour: u8,
+    pub mode: QuietHoursMode,
+}
+
+impl QuietHoursWindow {
+    fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// 暫停或放慢背景工作的時段，例如上班時間使用計量連線時。手動執行的分析不受影響。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietHoursConfig {
+    pub windows: Vec<QuietHoursWindow>,
+    /// `Slow` 時段中檢查間隔的倍數
+    pub slow_factor: u32,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        QuietHoursConfig {
+            windows: Vec::new(),
+            slow_factor: 4,
+        }
+    }
+}
+
+impl QuietHoursConfig {
+    /// `unix_secs` 所在的安靜時段；多個時段重疊時暫停優先。
+    pub fn mode_at(&self, unix_secs: u64) -> Option<QuietHoursMode> {
+        let hour = ((unix_secs + TAIWAN_UTC_OFFSET_SECS) / 3600 % 24) as u8;
+        let mut modes = self
+            .windows
+            .iter()
+            .filter(|window| window.contains(hour))
+            .map(|window| window.mode);
+        let first = modes.next()?;
+        Some(
+            if first == QuietHoursMode::Pause || modes.any(|mode| mode == QuietHoursMode::Pause) {
+                QuietHoursMode::Pause
+            } else {
+                QuietHoursMode::Slow
+            },
+        )
+    }
+}
+
+/// 每日摘要：每天固定時間彙整過去 24 小時的分析結果並發送通知。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// 產生摘要的時間 (台灣時間的整點，0 ~ 23)
+    pub hour: u8,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        DigestConfig {
+            enabled: false,
+            hour: 8,
+        }
+    }
+}
+
+/// webhook 送出的訊息格式。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookFormat {
+    /// 事件本身的 JSON，見 [`crate::webhooks::WebhookEvent`]
+    #[default]
+    Json,
+    /// Discord 的 incoming webhook
+    Discord,
+    /// Telegram Bot API 的 `sendMessage` 網址，需設定 `telegram_chat_id`
+    Telegram,
+}
+
+/// 分析完成或警示觸發時發送通知的外部網址。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default)]
+    pub telegram_chat_id: Option<String>,
+    /// 每次分析完成時發送
+    #[serde(default)]
+    pub on_analysis: bool,
+    /// 警示規則觸發時發送
+    #[serde(default)]
+    pub on_alert: bool,
+}
+
+// webhook 網址通常含有 token，避免出現在日誌中
+impl std::fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("url", &"<redacted>")
+            .field("format", &self.format)
+            .field("on_analysis", &self.on_analysis)
+            .field("on_alert", &self.on_alert)
+            .finish()
+    }
+}
+
+// (新增) 也加上 Serialize
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfig {
+    pub boards: Vec<BoardName>,
+    pub sorting: SortingConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub concurrent_analysis: ConcurrentAnalysisPolicy,
+    #[serde(default)]
+    pub ptt_term: PttTermConfig,
+    /// 依序嘗試的資料來源，全部都沒有資料時略過該使用者
+    #[serde(default = "default_user_stats_sources")]
+    pub user_stats_sources: Vec<UserStatsSource>,
+    /// 文章快照與報告紀錄 (壓縮後) 的磁碟用量上限 (MB)，超過時移除最久未使用的檔案
+    #[serde(default = "default_cache_max_mb")]
+    pub cache_max_mb: u64,
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub new_account: NewAccountConfig,
+    #[serde(default)]
+    pub global_shortcut: GlobalShortcutConfig,
+    #[serde(default)]
+    pub background: BackgroundConfig,
+    #[serde(default)]
+    pub hot_articles: HotArticleConfig,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub digest: DigestConfig,
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    #[serde(default)]
+    pub missed_schedules: MissedSchedulePolicy,
+}
+
+// Default 實作依然有用，可以作為前端初始狀態的參考
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            boards: vec![
+                BoardName::from("Gossiping"),
+                BoardName::from("HatePolitics"),
+            ],
+            sorting: SortingConfig {
+                sort_by: "本文留言數".to_string(),
+                order: "desc".to_string(),
+            },
+            logging: LoggingConfig::default(),
+            concurrent_analysis: ConcurrentAnalysisPolicy::default(),
+            ptt_term: PttTermConfig::default(),
+            user_stats_sources: default_user_stats_sources(),
+            cache_max_mb: default_cache_max_mb(),
+            http_api: HttpApiConfig::default(),
+            notifications: NotificationConfig::default(),
+            new_account: NewAccountConfig::default(),
+            global_shortcut: GlobalShortcutConfig::default(),
+            background: BackgroundConfig::default(),
+            hot_articles: HotArticleConfig::default(),
+            webhooks: Vec::new(),
+            digest: DigestConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            missed_schedules: MissedSchedulePolicy::default(),
+        }
+    }
+}
+
+/// 載入設定檔，檔案不存在或格式錯誤時使用預設值。
+pub fn load_from(path: &Path) -> AppConfig {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,88 @@
+use crate::error::Error;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 保留最近幾筆錯誤紀錄。
+const RECENT_ERROR_LIMIT: usize = 20;
+
+/// 放在 Tauri managed state 中，記錄執行期的診斷資訊。
+#[derive(Default)]
+pub struct DiagnosticsState {
+    active_jobs: AtomicUsize,
+    error_counts: Mutex<HashMap<String, u32>>,
+    recent_errors: Mutex<VecDeque<RecentError>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentError {
+    code: String,
+    message: String,
+    /// Unix 時間 (秒)
+    timestamp: u64,
+}
+
+/// 分析進行期間持有，離開作用域時自動將進行中的工作數減一。
+pub struct ActiveJobGuard<'a> {
+    state: &'a DiagnosticsState,
+}
+
+impl Drop for ActiveJobGuard<'_> {
+    fn drop(&mut self) {
+        self.state.active_jobs.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl DiagnosticsState {
+    /// 標記一個分析工作開始，回傳的 guard 被釋放時視為結束。
+    pub fn start_job(&self) -> ActiveJobGuard<'_> {
+        self.active_jobs.fetch_add(1, Ordering::SeqCst);
+        ActiveJobGuard { state: self }
+    }
+
+    /// 記錄一筆錯誤，供 `get_diagnostics` 統計。
+    pub fn record_error(&self, error: &Error) {
+        let code = error.code().to_string();
+        *self
+            .error_counts
+            .lock()
+            .unwrap()
+            .entry(code.clone())
+            .or_insert(0) += 1;
+
+        let mut recent_errors = self.recent_errors.lock().unwrap();
+        if recent_errors.len() == RECENT_ERROR_LIMIT {
+            recent_errors.pop_front();
+        }
+        recent_errors.push_back(RecentError {
+            code,
+            message: error.to_string(),
+            timestamp: unix_now(),
+        });
+    }
+
+    /// 進行中的分析工作數。
+    pub fn active_jobs(&self) -> usize {
+        self.active_jobs.load(Ordering::SeqCst)
+    }
+
+    /// 各錯誤碼累計發生的次數。
+    pub fn error_counts(&self) -> HashMap<String, u32> {
+        self.error_counts.lock().unwrap().clone()
+    }
+
+    /// 最近的錯誤紀錄，由舊到新。
+    pub fn recent_errors(&self) -> Vec<RecentError> {
+        self.recent_errors.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,205 @@
+//! 每日摘要：由文章快照與警示紀錄彙整一段期間內分析過的文章、新出現的高亮帳號，
+//! 以及在最多篇文章中被高亮的帳號。摘要存放於 app data 目錄，保留最近幾份。
+
+use crate::alerts::{AlertEvent, AlertStore};
+use crate::config::TAIWAN_UTC_OFFSET_SECS;
+use crate::diagnostics::unix_now;
+use crate::domain::{BoardName, UserId};
+use crate::history::AppearanceHistory;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 摘要檔名稱，存放於 app data 目錄下。
+pub const DIGESTS_FILE_NAME: &str = "digests.json";
+/// 每日摘要涵蓋的期間 (秒)。
+pub const DIGEST_PERIOD_SECS: u64 = 24 * 60 * 60;
+/// 最多保留的摘要數。
+const MAX_DIGESTS: usize = 30;
+/// 摘要中列出的最活躍帳號數。
+const MOST_ACTIVE_LIMIT: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Digest {
+    /// 涵蓋的期間 (Unix 秒)
+    pub since: u64,
+    pub until: u64,
+    /// 期間內分析過 (或追蹤中有新推文) 的文章，高亮人數多的在前
+    pub articles: Vec<DigestArticle>,
+    /// 期間內被高亮、但在此之前從未在任何文章被高亮的帳號
+    pub new_highlighted_users: Vec<UserId>,
+    /// 期間內在最多篇文章被高亮的帳號
+    pub most_active: Vec<ActiveAccount>,
+    pub alerts: Vec<AlertEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestArticle {
+    pub url: String,
+    pub title: String,
+    pub board: BoardName,
+    /// 期間內的分析與追蹤次數
+    pub analyses: usize,
+    /// 期間內最後一次的留言者數與高亮人數
+    pub commenter_count: usize,
+    pub highlighted_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAccount {
+    pub user: UserId,
+    /// 被高亮的文章數
+    pub articles: usize,
+}
+
+/// 彙整 `since` 到 `until` (Unix 秒) 之間的分析結果。
+pub fn build(history: &AppearanceHistory, alerts: &AlertStore, since: u64, until: u64) -> Digest {
+    let in_period = |time: u64| since <= time && time < until;
+    let mut articles = Vec::new();
+    let mut highlighted_before: HashSet<UserId> = HashSet::new();
+    let mut highlighted_in: HashMap<UserId, usize> = HashMap::new();
+    for (url, snapshots) in history.snapshots() {
+        let mut article_users: HashSet<&UserId> = HashSet::new();
+        for snapshot in snapshots
+            .iter()
+            .filter(|snapshot| snapshot.taken_at < since)
+        {
+            highlighted_before.extend(snapshot.highlighted_users.iter().cloned());
+        }
+        let period: Vec<_> = snapshots
+            .iter()
+            .filter(|snapshot| in_period(snapshot.taken_at))
+            .collect();
+        let Some(latest) = period.last() else {
+            continue;
+        };
+        for snapshot in &period {
+            article_users.extend(&snapshot.highlighted_users);
+        }
+        for user in article_users {
+            *highlighted_in.entry(user.clone()).or_default() += 1;
+        }
+        let record = history.get(&url);
+        articles.push(DigestArticle {
+            title: record
+                .as_ref()
+                .map(|record| record.title.clone())
+                .unwrap_or_default(),
+            board: record
+                .map(|record| record.board)
+                .unwrap_or_else(|| BoardName::from("")),
+            analyses: period.len(),
+            commenter_count: latest.commenter_count,
+            highlighted_count: latest.highlighted_users.len(),
+            url,
+        });
+    }
+    articles.sort_by(|a, b| {
+        b.highlighted_count
+            .cmp(&a.highlighted_count)
+            .then_with(|| a.url.cmp(&b.url))
+    });
+
+    let mut new_highlighted_users: Vec<UserId> = highlighted_in
+        .keys()
+        .filter(|user| !highlighted_before.contains(*user))
+        .cloned()
+        .collect();
+    new_highlighted_users.sort();
+    let mut most_active: Vec<ActiveAccount> = highlighted_in
+        .into_iter()
+        .map(|(user, articles)| ActiveAccount { user, articles })
+        .collect();
+    most_active.sort_by(|a, b| {
+        b.articles
+            .cmp(&a.articles)
+            .then_with(|| a.user.cmp(&b.user))
+    });
+    most_active.truncate(MOST_ACTIVE_LIMIT);
+
+    let mut alerts: Vec<AlertEvent> = alerts
+        .history()
+        .into_iter()
+        .filter(|alert| in_period(alert.triggered_at))
+        .collect();
+    alerts.reverse();
+
+    Digest {
+        since,
+        until,
+        articles,
+        new_highlighted_users,
+        most_active,
+        alerts,
+    }
+}
+
+/// `now` 之後下一次產生摘要的時間 (Unix 秒)，`hour` 為台灣時間的整點。
+pub fn next_run_at(now: u64, hour: u8) -> u64 {
+    let local_now = now + TAIWAN_UTC_OFFSET_SECS;
+    let today_start = local_now - local_now % DIGEST_PERIOD_SECS;
+    let mut next = today_start + u64::from(hour.min(23)) * 60 * 60;
+    if next <= local_now {
+        next += DIGEST_PERIOD_SECS;
+    }
+    next - TAIWAN_UTC_OFFSET_SECS
+}
+
+/// 最近產生的摘要，每次新增後立即寫回磁碟。
+#[derive(Default)]
+pub struct DigestStore {
+    path: Option<PathBuf>,
+    digests: Mutex<Vec<Digest>>,
+}
+
+impl DigestStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從沒有摘要開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(DIGESTS_FILE_NAME));
+        let digests = storage::load_json(path.as_deref());
+        DigestStore {
+            path,
+            digests: Mutex::new(digests),
+        }
+    }
+
+    /// 最近的摘要，最新的在前。
+    pub fn list(&self) -> Vec<Digest> {
+        self.digests.lock().unwrap().iter().rev().cloned().collect()
+    }
+
+    /// 最近一份摘要涵蓋到的時間 (Unix 秒)。
+    pub fn last_generated_at(&self) -> Option<u64> {
+        self.digests
+            .lock()
+            .unwrap()
+            .last()
+            .map(|digest| digest.until)
+    }
+
+    /// 產生過去 24 小時的摘要並保存；寫入失敗時仍回傳摘要，只是重新啟動後不會保留。
+    pub fn generate(&self, history: &AppearanceHistory, alerts: &AlertStore) -> Digest {
+        let until = unix_now();
+        let digest = build(
+            history,
+            alerts,
+            until.saturating_sub(DIGEST_PERIOD_SECS),
+            until,
+        );
+        let mut digests = self.digests.lock().unwrap();
+        digests.push(digest.clone());
+        let excess = digests.len().saturating_sub(MAX_DIGESTS);
+        digests.drain(..excess);
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*digests) {
+                tracing::warn!(error = %e, "無法儲存每日摘要");
+            }
+        }
+        digest
+    }
+}
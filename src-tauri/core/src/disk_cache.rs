@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// 存放文章快照與報告紀錄的子目錄 (位於 app cache 目錄下)。
+const BLOB_DIR_NAME: &str = "blobs";
+const BLOB_EXTENSION: &str = "zst";
+/// zstd 壓縮等級；HTML 與 JSON 在預設等級下已有很好的壓縮率。
+const COMPRESSION_LEVEL: i32 = 3;
+pub const DEFAULT_MAX_BYTES: u64 = 200 * 1024 * 1024;
+
+/// 以 zstd 壓縮資料。
+pub fn compress(data: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::encode_all(data, COMPRESSION_LEVEL)
+}
+
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::decode_all(data)
+}
+
+/// `get_cache_stats` 命令回傳的磁碟快取使用量。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub dir: Option<PathBuf>,
+    pub entries: usize,
+    /// 壓縮後實際佔用的大小
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+/// 放在 Tauri managed state 中的壓縮檔案快取 (文章 HTML 快照、分析報告紀錄)。
+/// 總大小超過上限時，依最後存取時間 (LRU) 移除最舊的檔案。
+pub struct DiskCache {
+    dir: Option<PathBuf>,
+    max_bytes: AtomicU64,
+    /// 寫入與清除時持有，避免同時清除造成重複計算大小
+    write_lock: Mutex<()>,
+}
+
+impl DiskCache {
+    /// 在 `cache_dir` (app cache 目錄) 下建立快取；無法取得目錄時快取不作用。
+    pub fn new(cache_dir: Option<&Path>) -> Self {
+        DiskCache {
+            dir: cache_dir.map(|dir| dir.join(BLOB_DIR_NAME)),
+            max_bytes: AtomicU64::new(DEFAULT_MAX_BYTES),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn set_max_bytes(&self, max_bytes: u64) {
+        self.max_bytes.store(max_bytes, Ordering::SeqCst);
+    }
+
+    fn path_for(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}", key, BLOB_EXTENSION)))
+    }
+
+    /// 壓縮後寫入，失敗時只記錄日誌；寫入後若超過上限則清除最舊的檔案。
+    pub fn put(&self, key: &str, data: &[u8]) {
+        let Some(path) = self.path_for(key) else {
+            return;
+        };
+        let _guard = self.write_lock.lock().unwrap();
+        let result = compress(data).and_then(|compressed| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, compressed)
+        });
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), error = %e, "無法寫入磁碟快取");
+            return;
+        }
+        self.evict();
+    }
+
+    /// 讀取並解壓縮，同時更新最後存取時間。
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path_for(key)?;
+        let compressed = std::fs::read(&path).ok()?;
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        decompress(&compressed).ok()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let files = self.dir.as_deref().map(list_files).unwrap_or_default();
+        CacheStats {
+            dir: self.dir.clone(),
+            entries: files.len(),
+            total_bytes: files.iter().map(|file| file.size).sum(),
+            max_bytes: self.max_bytes.load(Ordering::SeqCst),
+        }
+    }
+
+    /// 依最後存取時間由舊到新移除檔案，直到總大小低於上限。
+    fn evict(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let max_bytes = self.max_bytes.load(Ordering::SeqCst);
+        let mut files = list_files(dir);
+        let mut total: u64 = files.iter().map(|file| file.size).sum();
+        if total <= max_bytes {
+            return;
+        }
+
+        files.sort_by_key(|file| file.modified);
+        for file in files {
+            if total <= max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&file.path).is_ok() {
+                total = total.saturating_sub(file.size);
+                tracing::debug!(path = %file.path.display(), "磁碟快取超過上限，移除最舊的檔案");
+            }
+        }
+    }
+}
+
+struct CachedFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// 遞迴列出目錄下所有快取檔案，目錄不存在時為空。
+fn list_files(dir: &Path) -> Vec<CachedFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .flat_map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => list_files(&entry.path()),
+            Ok(meta) => vec![CachedFile {
+                path: entry.path(),
+                size: meta.len(),
+                modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            }],
+            Err(_) => Vec::new(),
+        })
+        .collect()
+}
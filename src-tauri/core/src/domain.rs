@@ -0,0 +1,123 @@
+//! PTT 帳號與看板名稱的型別。兩者在 PTT 上都不分大小寫，
+//! 比較與雜湊時忽略 ASCII 大小寫，顯示與序列化時保留原本的寫法。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+macro_rules! case_insensitive_name {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(name: impl Into<String>) -> Self {
+                let mut name = name.into();
+                // 前後有空白時才重新配置
+                let trimmed = name.trim();
+                if trimmed.len() != name.len() {
+                    name = trimmed.to_string();
+                }
+                $name(name)
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// 轉為小寫的正規化寫法，用於以字串為鍵的快取與查詢合併。
+            pub fn normalized(&self) -> String {
+                self.0.to_ascii_lowercase()
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.eq_ignore_ascii_case(&other.0)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0.eq_ignore_ascii_case(other)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                for byte in self.0.bytes() {
+                    state.write_u8(byte.to_ascii_lowercase());
+                }
+                // 與 str 相同，加上結尾避免前綴碰撞
+                state.write_u8(0xff);
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                let lower = |b: u8| b.to_ascii_lowercase();
+                self.0.bytes().map(lower).cmp(other.0.bytes().map(lower))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(name: &str) -> Self {
+                $name::new(name)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(name: String) -> Self {
+                $name::new(name)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map($name::new)
+            }
+        }
+    };
+}
+
+case_insensitive_name!(
+    /// PTT 帳號 (例如 `SomeUser`)。
+    UserId
+);
+
+case_insensitive_name!(
+    /// PTT 看板名稱 (例如 `HatePolitics`)。
+    BoardName
+);
+
+impl UserId {
+    /// PTT 帳號由英文字母開頭，共 2 到 12 個英數字。
+    pub fn is_valid(&self) -> bool {
+        let id = self.as_str();
+        (2..=12).contains(&id.len())
+            && id.starts_with(|c: char| c.is_ascii_alphabetic())
+            && id.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+}
@@ -0,0 +1,285 @@
+use serde::ser::SerializeStruct;
+use serde::Serialize;
+use serde_json::json;
+use std::any::Any;
+use thiserror::Error;
+
+/// 定義應用程式的統一錯誤類型。
+///
+/// 後端不負責在地化：每個 variant 都有穩定的 `code()` 與 `context()` 參數，
+/// 前端依代碼挑選對應語系的訊息範本；`Display` 的英文訊息僅作為日誌與後備顯示。
+#[derive(Debug, Error)]
+pub enum Error {
+    /// 代表 reqwest 網路請求過程中發生的任何錯誤。
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// 輸入的網址不是可辨識的 PTT 文章網址。
+    #[error("not a valid PTT article URL: {0}")]
+    InvalidArticleUrl(String),
+
+    /// 文章在 ptt.cc 與 pttweb.cc 鏡像上都找不到。
+    #[error("article not found: {0}")]
+    ArticleNotFound(String),
+
+    /// 頁面只剩刪除公告，且鏡像與快照中也找不到原文。
+    #[error("article has been deleted: {0}")]
+    ArticleDeleted(String),
+
+    /// 讀取本機 HTML 檔失敗。
+    #[error("failed to read local file {path}: {reason}")]
+    LocalFile { path: String, reason: String },
+
+    /// 不是有效的 PTT 帳號。
+    #[error("invalid PTT user ID: {0:?}")]
+    InvalidUserId(String),
+
+    /// 網址不在允許以瀏覽器開啟的網站清單中。
+    #[error("URL is not allowed to be opened: {0}")]
+    UrlNotAllowed(String),
+
+    /// 當在 pttweb.cc 找不到指定使用者時回傳。
+    #[error("user not found on pttweb.cc: {0}")]
+    PttWebUserNotFound(String),
+
+    /// 當解析 pttweb.cc 的 HTML 結構失敗或格式不符預期時回傳。
+    /// `field` 為無法解析的欄位代號 (例如 `totalComments`)。
+    #[error("failed to parse {field} from pttweb.cc page of {user_id}")]
+    PttWebParse {
+        user_id: String,
+        field: &'static str,
+    },
+
+    /// 設定的資料來源都沒有此使用者的留言統計。
+    #[error("no configured source has stats for {0}")]
+    UserStatsUnavailable(String),
+
+    /// 對方伺服器回應 429 Too Many Requests，`retry_after_secs` 取自 Retry-After 標頭。
+    #[error("rate limited by {host}")]
+    RateLimited {
+        host: String,
+        retry_after_secs: Option<u64>,
+    },
+
+    /// 與 term.ptt.cc 的連線或畫面互動失敗。
+    #[error("PTT terminal session failed: {0}")]
+    PttTerm(String),
+
+    /// term.ptt.cc 登入失敗 (帳號或密碼錯誤)。
+    #[error("failed to log in to PTT as {0}")]
+    PttTermLogin(String),
+
+    /// selectors 定義檔格式錯誤，或其中的 CSS selector 無法解析。
+    #[error("invalid selector definitions: {0}")]
+    InvalidSelectors(String),
+
+    /// 報告檔不是有效的分析結果。
+    #[error("invalid report: {0}")]
+    InvalidReport(String),
+
+    /// 報告檔由較新版本的程式產生，無法轉換為目前的格式。
+    #[error("report schema version {version} is newer than supported version {supported}")]
+    UnsupportedReportVersion { version: u64, supported: u64 },
+
+    /// 找不到指定名稱的觀察名單。
+    #[error("watchlist not found: {0}")]
+    WatchlistNotFound(String),
+
+    /// 已有同名的觀察名單。
+    #[error("watchlist already exists: {0}")]
+    WatchlistExists(String),
+
+    /// 觀察名單名稱為空白。
+    #[error("invalid watchlist name: {0:?}")]
+    InvalidWatchlistName(String),
+
+    /// 找不到指定名稱的帳號群組。
+    #[error("user group not found: {0}")]
+    UserGroupNotFound(String),
+
+    /// 帳號群組名稱為空白。
+    #[error("invalid user group name: {0:?}")]
+    InvalidUserGroupName(String),
+
+    /// 找不到指定 ID 的排程。
+    #[error("schedule not found: {0}")]
+    ScheduleNotFound(u64),
+
+    /// 排程的設定無效 (例如間隔過短)。
+    #[error("invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    /// 找不到指定 ID 的警示規則。
+    #[error("alert rule not found: {0}")]
+    AlertRuleNotFound(u64),
+
+    /// 警示規則的條件格式錯誤或沒有條件。
+    #[error("invalid alert rule: {0}")]
+    InvalidAlertRule(String),
+
+    /// 寫入 app data 目錄中的使用者資料 (例如觀察名單) 失敗。
+    #[error("failed to write {path}: {reason}")]
+    Storage { path: String, reason: String },
+
+    /// 已有分析正在執行，且設定為不排隊。
+    #[error("another analysis is already running")]
+    AnalysisBusy,
+
+    /// 分析工作被使用者取消。
+    #[error("analysis was cancelled")]
+    Cancelled,
+
+    /// 找不到指定 ID 的分析工作。
+    #[error("job not found: {0}")]
+    JobNotFound(u64),
+
+    /// 工作尚未完成 (或已失敗)，沒有可讀取的分析結果。
+    #[error("job {0} has no result yet")]
+    ResultNotReady(u64),
+
+    /// webhook 發送失敗 (重試後仍失敗，或對方回應 4xx)。只記錄主機名稱，網址中可能含有 token。
+    #[error("webhook to {host} failed: {reason}")]
+    Webhook { host: String, reason: String },
+
+    /// 檢查、下載或安裝更新失敗。
+    #[error("update failed: {0}")]
+    Update(String),
+
+    /// 命令執行期間發生 panic，已被攔截並轉為錯誤回傳。
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl Error {
+    /// 提供給前端辨識錯誤種類的穩定代碼。
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Request(_) => "REQUEST_FAILED",
+            Error::InvalidArticleUrl(_) => "INVALID_ARTICLE_URL",
+            Error::ArticleNotFound(_) => "ARTICLE_NOT_FOUND",
+            Error::ArticleDeleted(_) => "ARTICLE_DELETED",
+            Error::LocalFile { .. } => "LOCAL_FILE_READ_FAILED",
+            Error::InvalidUserId(_) => "INVALID_USER_ID",
+            Error::UrlNotAllowed(_) => "URL_NOT_ALLOWED",
+            Error::PttWebUserNotFound(_) => "PTTWEB_USER_NOT_FOUND",
+            Error::PttWebParse { .. } => "PTTWEB_PARSE_FAILED",
+            Error::UserStatsUnavailable(_) => "USER_STATS_UNAVAILABLE",
+            Error::RateLimited { .. } => "RATE_LIMITED",
+            Error::PttTerm(_) => "PTT_TERM_FAILED",
+            Error::PttTermLogin(_) => "PTT_TERM_LOGIN_FAILED",
+            Error::InvalidSelectors(_) => "INVALID_SELECTORS",
+            Error::InvalidReport(_) => "INVALID_REPORT",
+            Error::UnsupportedReportVersion { .. } => "UNSUPPORTED_REPORT_VERSION",
+            Error::WatchlistNotFound(_) => "WATCHLIST_NOT_FOUND",
+            Error::WatchlistExists(_) => "WATCHLIST_EXISTS",
+            Error::InvalidWatchlistName(_) => "INVALID_WATCHLIST_NAME",
+            Error::UserGroupNotFound(_) => "USER_GROUP_NOT_FOUND",
+            Error::InvalidUserGroupName(_) => "INVALID_USER_GROUP_NAME",
+            Error::ScheduleNotFound(_) => "SCHEDULE_NOT_FOUND",
+            Error::InvalidSchedule(_) => "INVALID_SCHEDULE",
+            Error::AlertRuleNotFound(_) => "ALERT_RULE_NOT_FOUND",
+            Error::InvalidAlertRule(_) => "INVALID_ALERT_RULE",
+            Error::Storage { .. } => "STORAGE_FAILED",
+            Error::AnalysisBusy => "ANALYSIS_BUSY",
+            Error::Cancelled => "CANCELLED",
+            Error::JobNotFound(_) => "JOB_NOT_FOUND",
+            Error::ResultNotReady(_) => "RESULT_NOT_READY",
+            Error::Webhook { .. } => "WEBHOOK_FAILED",
+            Error::Update(_) => "UPDATE_FAILED",
+            Error::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// 重試是否可能成功 (網路逾時、被限流等暫時性錯誤)。
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Request(e) => {
+                e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| s.is_server_error())
+            }
+            Error::RateLimited { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 錯誤的參數 (例如找不到的使用者 ID)，供前端套入訊息範本。
+    pub fn context(&self) -> serde_json::Value {
+        match self {
+            Error::Request(e) => json!({
+                "url": e.url().map(|u| u.to_string()),
+                "status": e.status().map(|s| s.as_u16()),
+                "isTimeout": e.is_timeout(),
+                "isConnect": e.is_connect(),
+            }),
+            Error::InvalidArticleUrl(url)
+            | Error::ArticleNotFound(url)
+            | Error::ArticleDeleted(url)
+            | Error::UrlNotAllowed(url) => json!({ "url": url }),
+            Error::LocalFile { path, reason } | Error::Storage { path, reason } => {
+                json!({ "path": path, "reason": reason })
+            }
+            Error::InvalidUserId(user_id)
+            | Error::PttWebUserNotFound(user_id)
+            | Error::UserStatsUnavailable(user_id) => {
+                json!({ "userId": user_id })
+            }
+            Error::PttWebParse { user_id, field } => json!({
+                "userId": user_id,
+                "field": field,
+            }),
+            Error::RateLimited {
+                host,
+                retry_after_secs,
+            } => json!({ "host": host, "retryAfterSecs": retry_after_secs }),
+            Error::PttTerm(detail)
+            | Error::InvalidSelectors(detail)
+            | Error::InvalidReport(detail)
+            | Error::InvalidSchedule(detail)
+            | Error::InvalidAlertRule(detail) => {
+                json!({ "detail": detail })
+            }
+            Error::UnsupportedReportVersion { version, supported } => {
+                json!({ "version": version, "supported": supported })
+            }
+            Error::PttTermLogin(username) => json!({ "username": username }),
+            Error::WatchlistNotFound(name)
+            | Error::WatchlistExists(name)
+            | Error::InvalidWatchlistName(name)
+            | Error::UserGroupNotFound(name)
+            | Error::InvalidUserGroupName(name) => json!({ "name": name }),
+            Error::AnalysisBusy | Error::Cancelled => serde_json::Value::Null,
+            Error::JobNotFound(job_id) | Error::ResultNotReady(job_id) => {
+                json!({ "jobId": job_id })
+            }
+            Error::ScheduleNotFound(schedule_id) => json!({ "scheduleId": schedule_id }),
+            Error::AlertRuleNotFound(rule_id) => json!({ "ruleId": rule_id }),
+            Error::Webhook { host, reason } => json!({ "host": host, "reason": reason }),
+            Error::Update(detail) | Error::Internal(detail) => json!({ "detail": detail }),
+        }
+    }
+}
+
+// 為了讓錯誤可以被序列化並傳遞到前端，我們需要手動為 Error 實現 Serialize trait。
+// 錯誤會被序列化為 `{ code, message, context }`，讓前端能依錯誤種類做不同處理。
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+/// 從 panic payload 取出可讀的訊息。
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "未知的錯誤".to_string()
+    }
+}
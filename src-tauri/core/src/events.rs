@@ -0,0 +1,88 @@
+//! 後端發送給前端的事件名稱與 payload。
+
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const SCRAPE_PROGRESS: &str = "SCRAPE_PROGRESS";
+pub const SCRAPE_METRICS: &str = "SCRAPE_METRICS";
+pub const FATAL_ERROR: &str = "FATAL_ERROR";
+pub const ARTICLE_SCRAPE_STARTED: &str = "ARTICLE_SCRAPE_STARTED";
+pub const ARTICLE_SCRAPED: &str = "ARTICLE_SCRAPED";
+pub const ENRICH_STARTED: &str = "ENRICH_STARTED";
+pub const ANALYSIS_DONE: &str = "ANALYSIS_DONE";
+pub const ANALYSIS_FAILED: &str = "ANALYSIS_FAILED";
+pub const HEARTBEAT: &str = "HEARTBEAT";
+pub const USER_FAILED: &str = "USER_FAILED";
+pub const RATE_LIMIT_BACKOFF: &str = "RATE_LIMIT_BACKOFF";
+pub const NEW_PUSHES: &str = "NEW_PUSHES";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleScrapeStartedPayload {
+    pub url: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleScrapedPayload {
+    pub title: String,
+    pub board: BoardName,
+    pub commenter_count: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichStartedPayload {
+    pub total: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisDonePayload {
+    pub highlighted_count: usize,
+    pub normal_count: usize,
+    pub total_ms: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisFailedPayload<'a> {
+    pub error: &'a Error,
+}
+
+/// 長時間工作期間定期發送，前端若一段時間沒收到即可判定後端卡住。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatPayload {
+    pub elapsed_ms: u64,
+    /// Unix 時間 (毫秒)
+    pub timestamp_ms: u64,
+}
+
+/// 單一使用者的 pttweb.cc 查詢失敗時發送，讓前端標記該列並提供重試。
+#[derive(Serialize, Debug, Clone)]
+pub struct UserFailedPayload {
+    pub user_id: UserId,
+    pub code: &'static str,
+    pub message: String,
+    pub retryable: bool,
+}
+
+/// 因被限流而暫停對某站台的請求時發送，讓使用者知道進度停滯的原因。
+#[derive(Serialize, Debug, Clone)]
+pub struct RateLimitBackoffPayload {
+    pub host: &'static str,
+    pub wait_ms: u64,
+}
+
+/// 追蹤中的文章有新推文時發送，只包含與上次檢查的差異。
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPushesPayload {
+    /// 第一次在本文留言的使用者，已加入報告
+    pub users: Vec<UserId>,
+    /// 各使用者自上次檢查後新增的留言數
+    pub counts: HashMap<UserId, u32>,
+}
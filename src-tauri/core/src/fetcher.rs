@@ -0,0 +1,200 @@
+use crate::error::Error;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+use tokio::time::Duration;
+
+/// 一次 GET 請求的結果；狀態碼交由呼叫端判斷 (404 代表頁面不存在、429 代表被限流)。
+#[derive(Debug, Clone)]
+pub struct FetchResponse {
+    pub status: u16,
+    /// `Retry-After` 標頭 (秒)
+    pub retry_after_secs: Option<u64>,
+    pub body: String,
+}
+
+impl FetchResponse {
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+
+    /// 被限流 (429) 時轉為 `RateLimited` 錯誤。
+    pub fn check_rate_limited(&self, host: &str) -> Result<(), Error> {
+        if self.status == 429 {
+            return Err(Error::RateLimited {
+                host: host.to_string(),
+                retry_after_secs: self.retry_after_secs,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 抓取網頁的方式，`scraper` 的所有 HTTP 請求都經由此 trait。
+pub trait Fetcher: Send + Sync {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse, Error>>;
+
+    /// 是否為不連網的離線模式；離線時也不會登入 term.ptt.cc。
+    fn is_offline(&self) -> bool {
+        false
+    }
+}
+
+/// 共用的 HTTP client：啟用 HTTP/2 並保持連線，
+/// 數百次 pttweb.cc 查詢只需少數幾條連線，不必每位使用者都重新建立 TLS 連線。
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        // 連線池大小與併發上限相同，併發查詢不會因為沒有閒置連線而另開新連線
+        .pool_max_idle_per_host(crate::concurrency::MAX_CONCURRENCY)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .http2_keep_alive_timeout(Duration::from_secs(10))
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .unwrap_or_default()
+});
+
+/// 實際連網的 fetcher，附上 PTT 的滿 18 歲確認 cookie。
+#[derive(Default)]
+pub struct HttpFetcher;
+
+impl Fetcher for HttpFetcher {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse, Error>> {
+        async move {
+            let response = HTTP_CLIENT
+                .get(url)
+                .header(reqwest::header::COOKIE, "over18=1")
+                .send()
+                .await?;
+            let status = response.status().as_u16();
+            let retry_after_secs = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            let body = response.text().await?;
+            Ok(FetchResponse {
+                status,
+                retry_after_secs,
+                body,
+            })
+        }
+        .boxed()
+    }
+}
+
+/// 離線模式：從預先存下的 HTML 檔回應請求，讓整個分析流程不連網也能重現相同結果。
+///
+/// 檔案位置為 `<dir>/<host><path>`，忽略 query string；路徑以 `/` 結尾時讀取 `index.html`。
+/// 例如 `https://www.pttweb.cc/user/abc?t=message` 對應 `<dir>/www.pttweb.cc/user/abc`。
+/// 找不到檔案時回應 404。
+pub struct FixtureFetcher {
+    dir: PathBuf,
+}
+
+impl FixtureFetcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FixtureFetcher { dir: dir.into() }
+    }
+}
+
+/// 網址在 fixture 目錄中對應的檔案位置，規則見 [`FixtureFetcher`]。
+fn fixture_path(dir: &Path, url: &str) -> Option<PathBuf> {
+    let url = Url::parse(url).ok()?;
+    let mut path = dir.join(url.host_str()?);
+    for segment in url.path_segments()? {
+        // 不允許以 `..` 跳出 fixture 目錄
+        if segment == ".." {
+            return None;
+        }
+        if !segment.is_empty() {
+            path.push(segment);
+        }
+    }
+    if url.path().ends_with('/') {
+        path.push("index.html");
+    }
+    Some(path)
+}
+
+impl Fetcher for FixtureFetcher {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse, Error>> {
+        async move {
+            let body = match fixture_path(&self.dir, url) {
+                Some(path) => read_fixture(&path).await,
+                None => None,
+            };
+            tracing::debug!(url, found = body.is_some(), "離線模式讀取 fixture");
+            Ok(match body {
+                Some(body) => FetchResponse {
+                    status: 200,
+                    retry_after_secs: None,
+                    body,
+                },
+                None => FetchResponse {
+                    status: 404,
+                    retry_after_secs: None,
+                    body: String::new(),
+                },
+            })
+        }
+        .boxed()
+    }
+
+    fn is_offline(&self) -> bool {
+        true
+    }
+}
+
+async fn read_fixture(path: &Path) -> Option<String> {
+    tokio::fs::read_to_string(path).await.ok()
+}
+
+/// 錄製模式：照常經由 `inner` 發出請求，並將成功 (200) 的回應以 [`FixtureFetcher`] 的格式存到 `dir`，
+/// 之後即可用 `FixtureFetcher` 不連網重現同一次分析。
+pub struct RecordingFetcher {
+    inner: Box<dyn Fetcher>,
+    dir: PathBuf,
+}
+
+impl RecordingFetcher {
+    pub fn new(inner: Box<dyn Fetcher>, dir: impl Into<PathBuf>) -> Self {
+        RecordingFetcher {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+impl Fetcher for RecordingFetcher {
+    fn get<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse, Error>> {
+        async move {
+            let response = self.inner.get(url).await?;
+            // 404 不必錄製：重播時找不到檔案同樣回應 404
+            if response.status == 200 {
+                if let Some(path) = fixture_path(&self.dir, url) {
+                    if let Err(e) = write_fixture(&path, &response.body).await {
+                        tracing::warn!(url, error = %e, "無法寫入錄製的回應");
+                    }
+                }
+            }
+            Ok(response)
+        }
+        .boxed()
+    }
+
+    fn is_offline(&self) -> bool {
+        self.inner.is_offline()
+    }
+}
+
+async fn write_fixture(path: &Path, body: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, body).await
+}
@@ -0,0 +1,98 @@
+//! 使用者認定為同一人或同一集團的帳號群組；報告可切換為群組檢視，將同群組帳號的留言數合併為一列。
+
+use crate::domain::UserId;
+use crate::error::Error;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 帳號群組檔名稱，存放於 app data 目錄下。
+pub const USER_GROUPS_FILE_NAME: &str = "user_groups.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserGroup {
+    pub name: String,
+    pub members: Vec<UserId>,
+}
+
+/// 所有帳號群組，每次修改後立即寫回磁碟。
+#[derive(Default)]
+pub struct UserGroupStore {
+    path: Option<PathBuf>,
+    /// 群組名稱 → 成員帳號
+    groups: Mutex<BTreeMap<String, Vec<UserId>>>,
+}
+
+impl UserGroupStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從沒有群組開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(USER_GROUPS_FILE_NAME));
+        let groups = storage::load_json(path.as_deref());
+        UserGroupStore {
+            path,
+            groups: Mutex::new(groups),
+        }
+    }
+
+    pub fn list(&self) -> Vec<UserGroup> {
+        self.groups
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, members)| UserGroup {
+                name: name.clone(),
+                members: members.clone(),
+            })
+            .collect()
+    }
+
+    /// 建立群組，或以 `members` 取代同名群組的成員；重複的帳號只保留一次。
+    pub fn save(&self, name: &str, members: Vec<UserId>) -> Result<UserGroup, Error> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::InvalidUserGroupName(name.to_string()));
+        }
+        let mut unique: Vec<UserId> = Vec::with_capacity(members.len());
+        for member in members {
+            if !unique.contains(&member) {
+                unique.push(member);
+            }
+        }
+        self.update(|groups| {
+            groups.insert(name.to_string(), unique.clone());
+            Ok(UserGroup {
+                name: name.to_string(),
+                members: unique,
+            })
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), Error> {
+        self.update(|groups| {
+            groups
+                .remove(name)
+                .map(|_| ())
+                .ok_or_else(|| Error::UserGroupNotFound(name.to_string()))
+        })
+    }
+
+    /// 修改群組並寫回磁碟；寫入失敗時還原修改並回傳錯誤。
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut BTreeMap<String, Vec<UserId>>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut groups = self.groups.lock().unwrap();
+        let previous = groups.clone();
+        let result = f(&mut groups)?;
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*groups) {
+                *groups = previous;
+                return Err(e);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use std::time::Instant;
+use tokio::time::Duration;
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 單一站台的連線狀態。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HostStatus {
+    pub host: String,
+    pub reachable: bool,
+    pub status_code: Option<u16>,
+    pub latency_ms: Option<u64>,
+    /// 回應看起來是反爬蟲驗證頁 (例如 Cloudflare challenge)，代表目前被擋。
+    pub blocked: bool,
+    pub error: Option<String>,
+}
+
+/// `health_check` 命令回傳的整體連線狀態。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub ptt: HostStatus,
+    pub pttweb: HostStatus,
+}
+
+/// 同時檢查 ptt.cc 與 pttweb.cc 的連線狀況。
+pub async fn check_all() -> HealthReport {
+    let client = reqwest::Client::builder()
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let (ptt, pttweb) = tokio::join!(
+        check_host(&client, "www.ptt.cc", "https://www.ptt.cc/bbs/index.html"),
+        check_host(&client, "www.pttweb.cc", "https://www.pttweb.cc/"),
+    );
+
+    HealthReport { ptt, pttweb }
+}
+
+async fn check_host(client: &reqwest::Client, host: &str, url: &str) -> HostStatus {
+    let started = Instant::now();
+    let response = client
+        .get(url)
+        .header(reqwest::header::COOKIE, "over18=1")
+        .send()
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            let challenge_header = response.headers().contains_key("cf-mitigated");
+            let body = response.text().await.unwrap_or_default();
+            let blocked = challenge_header || is_bot_challenge(status.as_u16(), &body);
+
+            HostStatus {
+                host: host.to_string(),
+                reachable: true,
+                status_code: Some(status.as_u16()),
+                latency_ms: Some(latency_ms),
+                blocked,
+                error: None,
+            }
+        }
+        Err(e) => HostStatus {
+            host: host.to_string(),
+            reachable: false,
+            status_code: None,
+            latency_ms: None,
+            blocked: false,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// 判斷回應是否為常見的反爬蟲驗證頁。
+fn is_bot_challenge(status: u16, body: &str) -> bool {
+    matches!(status, 403 | 429 | 503)
+        && (body.contains("cf-chl")
+            || body.contains("Just a moment")
+            || body.contains("challenge-platform")
+            || body.contains("captcha"))
+}
@@ -0,0 +1,446 @@
+//! 留言者出現紀錄：記錄每位使用者出現在哪些分析過的文章中，用於找出反覆出現的帳號，
+//! 以及經常在同一篇文章的相近時間留言的帳號組合。
+//! 同一篇文章每次分析 (或追蹤新推文) 的結果另外記錄為快照，用於觀察文章隨時間的變化。
+
+use crate::diagnostics::unix_now;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::report::AnalysisResult;
+use crate::scraper::PttArticleData;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 出現紀錄檔名稱，存放於 app data 目錄下。
+pub const HISTORY_FILE_NAME: &str = "user_history.json";
+/// 兩位使用者的留言間隔在此範圍內 (分鐘) 才算「同時出現」。
+pub const DEFAULT_CO_OCCURRENCE_WINDOW_MINUTES: u32 = 10;
+/// 至少在幾篇文章中同時出現才列為相似帳號。
+const MIN_CO_OCCURRENCES: usize = 2;
+/// `find_similar` 最多回傳的帳號數。
+const SIMILAR_ACCOUNTS_LIMIT: usize = 20;
+/// 第一則推文距發文在此範圍內 (分鐘) 才算「快速回應」。
+pub const DEFAULT_FAST_RESPONSE_MINUTES: u32 = 2;
+/// 至少在作者的幾篇文章中快速回應才列入。
+const MIN_FAST_RESPONSES: usize = 2;
+/// 快速回應的文章數至少佔作者 (有發文時間的) 文章的比例，即「幾乎每篇都搶先回應」。
+const MIN_FAST_RESPONSE_SHARE: f64 = 0.8;
+/// 每篇文章最多保留的快照數，超過時移除最舊的。
+const MAX_SNAPSHOTS_PER_ARTICLE: usize = 200;
+
+/// 分析過的一篇文章；同一篇文章重新分析時更新紀錄而不重複計算。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleRecord {
+    pub url: String,
+    pub title: String,
+    pub board: BoardName,
+    #[serde(default)]
+    pub author: Option<UserId>,
+    /// 最後一次分析的時間 (Unix 秒)
+    pub analyzed_at: u64,
+    /// 最後一次分析的報告在磁碟快取中的位置；報告可能已因容量上限被移除
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_key: Option<String>,
+}
+
+/// `query_user_history` 的回傳值。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserHistory {
+    pub user: UserId,
+    /// 使用者出現過的文章數
+    pub times_seen: usize,
+    /// 所有分析過的文章數
+    pub total_articles: usize,
+    /// 使用者出現過的文章，最近分析的在前
+    pub articles: Vec<ArticleRecord>,
+}
+
+/// 與查詢對象經常在同一篇文章相近時間留言的帳號。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarAccount {
+    pub user: UserId,
+    /// 兩人在時間窗內都有留言的文章數
+    pub co_occurrences: usize,
+    /// 同時出現的文章數佔兩人出現過的所有文章的比例 (0 ~ 1)
+    pub score: f64,
+    pub evidence: Vec<CoOccurrence>,
+}
+
+/// 反覆在同一位作者發文後很快留言的帳號。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FastResponder {
+    pub user: UserId,
+    /// 在時限內留言的作者文章數
+    pub fast_responses: usize,
+    /// 紀錄中作者有發文時間的文章數
+    pub author_articles: usize,
+    pub evidence: Vec<FastResponse>,
+}
+
+/// 快速回應的一篇文章。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FastResponse {
+    pub url: String,
+    pub title: String,
+    /// 第一則推文距發文的分鐘數
+    pub latency_minutes: u32,
+}
+
+/// 兩人同時出現的一篇文章。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoOccurrence {
+    pub url: String,
+    pub title: String,
+    /// 兩人留言時間最接近的間隔 (分鐘)
+    pub gap_minutes: u32,
+}
+
+/// 一次分析或追蹤檢查後的文章狀態。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleSnapshot {
+    /// 記錄時間 (Unix 秒)
+    pub taken_at: u64,
+    pub commenter_count: usize,
+    /// 所有留言者在本文的留言數總和
+    pub comment_count: u32,
+    pub highlighted_users: Vec<UserId>,
+}
+
+/// `get_article_timeline` 的回傳值，快照依時間由舊到新排列。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleTimeline {
+    pub url: String,
+    pub points: Vec<ArticleTimelinePoint>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleTimelinePoint {
+    /// 記錄時間 (Unix 秒)
+    pub taken_at: u64,
+    pub commenter_count: usize,
+    pub comment_count: u32,
+    pub highlighted_users: Vec<UserId>,
+    /// 與前一個快照相比新被高亮的使用者；第一個快照為所有高亮的使用者
+    pub highlighted_added: Vec<UserId>,
+    /// 與前一個快照相比不再被高亮的使用者
+    pub highlighted_removed: Vec<UserId>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct HistoryData {
+    /// 文章網址 → 文章
+    articles: BTreeMap<String, ArticleRecord>,
+    /// 使用者 → 出現過的文章網址
+    appearances: HashMap<UserId, BTreeSet<String>>,
+    /// 文章網址 → 各使用者的留言時間 (當年的第幾分鐘)
+    #[serde(default)]
+    comment_minutes: HashMap<String, HashMap<UserId, Vec<u32>>>,
+    /// 文章網址 → 各使用者第一則推文距發文的分鐘數；沒有發文時間的文章不列入
+    #[serde(default)]
+    first_push_latency: HashMap<String, HashMap<UserId, u32>>,
+    /// 文章網址 → 每次分析的快照，依時間由舊到新
+    #[serde(default)]
+    snapshots: HashMap<String, Vec<ArticleSnapshot>>,
+}
+
+/// 所有分析過的文章與留言者，每次記錄後立即寫回磁碟。
+#[derive(Default)]
+pub struct AppearanceHistory {
+    path: Option<PathBuf>,
+    data: Mutex<HistoryData>,
+}
+
+impl AppearanceHistory {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從空的紀錄開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(HISTORY_FILE_NAME));
+        let data = storage::load_json(path.as_deref());
+        AppearanceHistory {
+            path,
+            data: Mutex::new(data),
+        }
+    }
+
+    /// 記錄一篇文章的所有留言者與留言時間；寫入失敗時只保留在記憶體中並回傳錯誤。
+    pub fn record(&self, url: &str, article: &PttArticleData) -> Result<(), Error> {
+        let mut data = self.data.lock().unwrap();
+        data.articles.insert(
+            url.to_string(),
+            ArticleRecord {
+                url: url.to_string(),
+                title: article.title.clone(),
+                board: article.board.clone(),
+                author: article.author.clone(),
+                analyzed_at: unix_now(),
+                report_key: None,
+            },
+        );
+        for user in article.user_comment_counts.keys() {
+            data.appearances
+                .entry(user.clone())
+                .or_default()
+                .insert(url.to_string());
+        }
+        data.comment_minutes
+            .insert(url.to_string(), article.comment_minutes.clone());
+        if article.posted_minute.is_some() {
+            data.first_push_latency
+                .insert(url.to_string(), article.first_push_latency.clone());
+        }
+        match &self.path {
+            Some(path) => storage::save_json(path, &*data),
+            None => Ok(()),
+        }
+    }
+
+    /// 記錄分析結果的快照 (留言者數、留言數與高亮的使用者)；寫入失敗時只保留在記憶體中並回傳錯誤。
+    pub fn record_snapshot(&self, result: &AnalysisResult) -> Result<(), Error> {
+        let rows = result.highlighted_data.iter().chain(&result.normal_data);
+        let mut highlighted_users: Vec<UserId> = result
+            .highlighted_data
+            .iter()
+            .map(|row| row.user.clone())
+            .collect();
+        highlighted_users.sort();
+        let snapshot = ArticleSnapshot {
+            taken_at: unix_now(),
+            commenter_count: result.highlighted_data.len() + result.normal_data.len(),
+            comment_count: rows.map(|row| row.article_comments).sum(),
+            highlighted_users,
+        };
+
+        let mut data = self.data.lock().unwrap();
+        let snapshots = data
+            .snapshots
+            .entry(result.metadata.url.clone())
+            .or_default();
+        snapshots.push(snapshot);
+        let excess = snapshots.len().saturating_sub(MAX_SNAPSHOTS_PER_ARTICLE);
+        snapshots.drain(..excess);
+        match &self.path {
+            Some(path) => storage::save_json(path, &*data),
+            None => Ok(()),
+        }
+    }
+
+    /// 所有文章的快照 (文章網址 → 依時間由舊到新的快照)。
+    pub fn snapshots(&self) -> HashMap<String, Vec<ArticleSnapshot>> {
+        self.data.lock().unwrap().snapshots.clone()
+    }
+
+    /// 文章每次分析時的留言者數、留言數，以及高亮使用者的變化。
+    pub fn article_timeline(&self, url: &str) -> ArticleTimeline {
+        let data = self.data.lock().unwrap();
+        let mut previous: &[UserId] = &[];
+        let points = data
+            .snapshots
+            .get(url)
+            .into_iter()
+            .flatten()
+            .map(|snapshot| {
+                let current = &snapshot.highlighted_users;
+                let point = ArticleTimelinePoint {
+                    taken_at: snapshot.taken_at,
+                    commenter_count: snapshot.commenter_count,
+                    comment_count: snapshot.comment_count,
+                    highlighted_users: current.clone(),
+                    highlighted_added: current
+                        .iter()
+                        .filter(|user| !previous.contains(user))
+                        .cloned()
+                        .collect(),
+                    highlighted_removed: previous
+                        .iter()
+                        .filter(|user| !current.contains(user))
+                        .cloned()
+                        .collect(),
+                };
+                previous = current;
+                point
+            })
+            .collect();
+        ArticleTimeline {
+            url: url.to_string(),
+            points,
+        }
+    }
+
+    /// 記錄文章最後一次分析的報告位置，供「最近的分析」選單直接開啟。
+    pub fn set_report_key(&self, url: &str, key: &str) -> Result<(), Error> {
+        let mut data = self.data.lock().unwrap();
+        let Some(article) = data.articles.get_mut(url) else {
+            return Ok(());
+        };
+        article.report_key = Some(key.to_string());
+        match &self.path {
+            Some(path) => storage::save_json(path, &*data),
+            None => Ok(()),
+        }
+    }
+
+    /// 最近分析的 `limit` 篇文章，最近的在前。
+    pub fn recent(&self, limit: usize) -> Vec<ArticleRecord> {
+        let data = self.data.lock().unwrap();
+        let mut articles: Vec<ArticleRecord> = data.articles.values().cloned().collect();
+        articles.sort_by(|a, b| b.analyzed_at.cmp(&a.analyzed_at));
+        articles.truncate(limit);
+        articles
+    }
+
+    pub fn get(&self, url: &str) -> Option<ArticleRecord> {
+        self.data.lock().unwrap().articles.get(url).cloned()
+    }
+
+    /// 使用者出現過的其他文章 (不含 `except_url`) 的網址。
+    pub fn seen_in(&self, user: &UserId, except_url: &str) -> Vec<String> {
+        self.data
+            .lock()
+            .unwrap()
+            .appearances
+            .get(user)
+            .map(|urls| {
+                urls.iter()
+                    .filter(|url| url.as_str() != except_url)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn query(&self, user: &UserId) -> UserHistory {
+        let data = self.data.lock().unwrap();
+        let mut articles: Vec<ArticleRecord> = data
+            .appearances
+            .get(user)
+            .into_iter()
+            .flatten()
+            .filter_map(|url| data.articles.get(url).cloned())
+            .collect();
+        articles.sort_by(|a, b| b.analyzed_at.cmp(&a.analyzed_at));
+        UserHistory {
+            user: user.clone(),
+            times_seen: articles.len(),
+            total_articles: data.articles.len(),
+            articles,
+        }
+    }
+
+    /// 在紀錄中與 `user` 反覆於同一篇文章、間隔 `window_minutes` 分鐘內留言的帳號，
+    /// 依同時出現的文章數 (其次依比例) 由高到低排序。
+    pub fn find_similar(&self, user: &UserId, window_minutes: u32) -> Vec<SimilarAccount> {
+        let data = self.data.lock().unwrap();
+        let Some(user_articles) = data.appearances.get(user) else {
+            return Vec::new();
+        };
+
+        let mut evidence: HashMap<&UserId, Vec<CoOccurrence>> = HashMap::new();
+        for url in user_articles {
+            let Some(minutes) = data.comment_minutes.get(url) else {
+                continue;
+            };
+            let Some(own_minutes) = minutes.get(user) else {
+                continue;
+            };
+            let title = data
+                .articles
+                .get(url)
+                .map(|article| article.title.clone())
+                .unwrap_or_default();
+            for (other, other_minutes) in minutes {
+                if other == user {
+                    continue;
+                }
+                let gap = own_minutes
+                    .iter()
+                    .flat_map(|a| other_minutes.iter().map(move |b| a.abs_diff(*b)))
+                    .min();
+                if let Some(gap) = gap.filter(|gap| *gap <= window_minutes) {
+                    evidence.entry(other).or_default().push(CoOccurrence {
+                        url: url.clone(),
+                        title: title.clone(),
+                        gap_minutes: gap,
+                    });
+                }
+            }
+        }
+
+        let mut similar: Vec<SimilarAccount> = evidence
+            .into_iter()
+            .filter(|(_, evidence)| evidence.len() >= MIN_CO_OCCURRENCES)
+            .map(|(other, evidence)| {
+                let other_articles = data.appearances.get(other).map_or(0, BTreeSet::len);
+                let union = user_articles.len() + other_articles - evidence.len();
+                SimilarAccount {
+                    user: other.clone(),
+                    co_occurrences: evidence.len(),
+                    score: evidence.len() as f64 / union.max(1) as f64,
+                    evidence,
+                }
+            })
+            .collect();
+        similar.sort_by(|a, b| {
+            b.co_occurrences
+                .cmp(&a.co_occurrences)
+                .then(b.score.total_cmp(&a.score))
+        });
+        similar.truncate(SIMILAR_ACCOUNTS_LIMIT);
+        similar
+    }
+
+    /// 在紀錄中幾乎每次都在 `author` 發文後 `within_minutes` 分鐘內留言的帳號，
+    /// 依快速回應的文章數由高到低排序。
+    pub fn fast_responders(&self, author: &UserId, within_minutes: u32) -> Vec<FastResponder> {
+        let data = self.data.lock().unwrap();
+        let author_articles: Vec<(&ArticleRecord, &HashMap<UserId, u32>)> = data
+            .articles
+            .values()
+            .filter(|article| article.author.as_ref() == Some(author))
+            .filter_map(|article| Some((article, data.first_push_latency.get(&article.url)?)))
+            .collect();
+
+        let mut evidence: HashMap<&UserId, Vec<FastResponse>> = HashMap::new();
+        for (article, latencies) in &author_articles {
+            for (user, &latency) in *latencies {
+                if latency <= within_minutes {
+                    evidence.entry(user).or_default().push(FastResponse {
+                        url: article.url.clone(),
+                        title: article.title.clone(),
+                        latency_minutes: latency,
+                    });
+                }
+            }
+        }
+
+        let mut responders: Vec<FastResponder> = evidence
+            .into_iter()
+            .filter(|(_, evidence)| {
+                evidence.len() >= MIN_FAST_RESPONSES
+                    && evidence.len() as f64 / author_articles.len() as f64
+                        >= MIN_FAST_RESPONSE_SHARE
+            })
+            .map(|(user, evidence)| FastResponder {
+                user: user.clone(),
+                fast_responses: evidence.len(),
+                author_articles: author_articles.len(),
+                evidence,
+            })
+            .collect();
+        responders.sort_by(|a, b| {
+            b.fast_responses
+                .cmp(&a.fast_responses)
+                .then(a.user.cmp(&b.user))
+        });
+        responders
+    }
+}
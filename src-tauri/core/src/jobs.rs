@@ -0,0 +1,366 @@
+use crate::config::ConcurrentAnalysisPolicy;
+use crate::diagnostics::unix_now;
+use crate::error::Error;
+use crate::report::AnalysisResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Semaphore, SemaphorePermit};
+use tokio_util::sync::CancellationToken;
+
+pub type JobId = u64;
+
+/// 最多保留幾筆已結束的工作紀錄，超過時移除最舊的 (仍在使用中的報告除外，見 [`JobManager::pin`])。
+const FINISHED_JOB_LIMIT: usize = 50;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// 工作失敗時保存的錯誤內容 (與 `Error` 序列化後的格式相同)。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobError {
+    code: String,
+    message: String,
+    context: serde_json::Value,
+}
+
+impl From<&Error> for JobError {
+    fn from(error: &Error) -> Self {
+        JobError {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            context: error.context(),
+        }
+    }
+}
+
+/// `list_jobs` 回傳的工作摘要，時間皆為 Unix 時間 (秒)。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSummary {
+    id: JobId,
+    url: String,
+    status: JobStatus,
+    created_at: u64,
+    started_at: Option<u64>,
+    finished_at: Option<u64>,
+    error: Option<JobError>,
+}
+
+/// `job_status` 回傳的完整工作狀態，完成時附上分析結果。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDetail {
+    #[serde(flatten)]
+    summary: JobSummary,
+    result: Option<AnalysisResult>,
+}
+
+struct JobRecord {
+    summary: JobSummary,
+    result: Option<AnalysisResult>,
+    control: JobControl,
+    /// 使用中 (追蹤新推文、開啟的報告視窗) 的數量，大於 0 時不會被清除
+    pins: u32,
+}
+
+/// 暫停閘門：暫停期間，新的 pttweb.cc 查詢會在開始前等待，已完成的結果不受影響。
+#[derive(Clone)]
+pub struct PauseGate {
+    paused: watch::Sender<bool>,
+}
+
+impl Default for PauseGate {
+    fn default() -> Self {
+        PauseGate {
+            paused: watch::Sender::new(false),
+        }
+    }
+}
+
+impl PauseGate {
+    fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    /// 若目前為暫停狀態，等待直到恢復。
+    pub async fn wait_if_paused(&self) {
+        let mut receiver = self.paused.subscribe();
+        let _ = receiver.wait_for(|paused| !paused).await;
+    }
+}
+
+/// 控制執行中工作的取消與暫停。
+#[derive(Clone, Default)]
+pub struct JobControl {
+    pub cancel: CancellationToken,
+    pub pause: PauseGate,
+}
+
+/// 放在 Tauri managed state 中的工作佇列：分配工作 ID、記錄狀態，
+/// 並透過 run slot 讓分析依序執行。
+pub struct JobManager {
+    next_id: AtomicU64,
+    run_slot: Semaphore,
+    jobs: Mutex<BTreeMap<JobId, JobRecord>>,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        JobManager {
+            next_id: AtomicU64::new(1),
+            run_slot: Semaphore::new(1),
+            jobs: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl JobManager {
+    pub fn next_id(&self) -> JobId {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// 登記一個新的分析工作 (狀態為排隊中)，回傳工作 ID 與控制用的 handle。
+    pub fn register(&self, url: &str) -> (JobId, JobControl) {
+        let id = self.next_id();
+        let control = JobControl::default();
+        let record = JobRecord {
+            summary: JobSummary {
+                id,
+                url: url.to_string(),
+                status: JobStatus::Queued,
+                created_at: unix_now(),
+                started_at: None,
+                finished_at: None,
+                error: None,
+            },
+            result: None,
+            control: control.clone(),
+            pins: 0,
+        };
+        self.jobs.lock().unwrap().insert(id, record);
+        (id, control)
+    }
+
+    pub fn mark_running(&self, id: JobId) {
+        if let Some(record) = self.jobs.lock().unwrap().get_mut(&id) {
+            // 排隊期間就被暫停的工作，開始後維持暫停狀態
+            if record.summary.status != JobStatus::Paused {
+                record.summary.status = JobStatus::Running;
+            }
+            record.summary.started_at = Some(unix_now());
+        }
+    }
+
+    /// 依分析結果更新工作狀態，並清除過舊的已結束工作。
+    pub fn finish(&self, id: JobId, result: &Result<AnalysisResult, Error>) {
+        self.finish_with(id, result.as_ref().map(|analysis| Some(analysis.clone())));
+    }
+
+    /// 沒有分析結果的工作 (例如重新查詢失敗的使用者) 結束時使用，成功時狀態為已完成。
+    pub fn finish_without_result<T>(&self, id: JobId, result: &Result<T, Error>) {
+        self.finish_with(id, result.as_ref().map(|_| None));
+    }
+
+    fn finish_with(&self, id: JobId, result: Result<Option<AnalysisResult>, &Error>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(record) = jobs.get_mut(&id) {
+            record.summary.finished_at = Some(unix_now());
+            match result {
+                Ok(analysis) => {
+                    record.summary.status = JobStatus::Completed;
+                    record.result = analysis;
+                }
+                Err(Error::Cancelled) => record.summary.status = JobStatus::Cancelled,
+                Err(e) => {
+                    record.summary.status = JobStatus::Failed;
+                    record.summary.error = Some(JobError::from(e));
+                }
+            }
+        }
+
+        let finished: Vec<JobId> = jobs
+            .values()
+            .filter(|record| record.summary.status.is_finished() && record.pins == 0)
+            .map(|record| record.summary.id)
+            .collect();
+        for id in finished
+            .iter()
+            .take(finished.len().saturating_sub(FINISHED_JOB_LIMIT))
+        {
+            jobs.remove(id);
+        }
+    }
+
+    /// 標記工作的結果仍在使用中，在對應的 [`JobManager::unpin`] 之前不會因已結束的工作過多而被清除。
+    pub fn pin(&self, id: JobId) -> Result<(), Error> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let record = jobs.get_mut(&id).ok_or(Error::JobNotFound(id))?;
+        record.pins += 1;
+        Ok(())
+    }
+
+    pub fn unpin(&self, id: JobId) {
+        if let Some(record) = self.jobs.lock().unwrap().get_mut(&id) {
+            record.pins = record.pins.saturating_sub(1);
+        }
+    }
+
+    /// 取消排隊中或執行中的工作。
+    pub fn cancel(&self, id: JobId) -> Result<(), Error> {
+        let jobs = self.jobs.lock().unwrap();
+        let record = jobs.get(&id).ok_or(Error::JobNotFound(id))?;
+        record.control.cancel.cancel();
+        // 讓暫停中的工作也能立即結束
+        record.control.pause.set_paused(false);
+        Ok(())
+    }
+
+    /// 暫停工作；已結束的工作不受影響。
+    pub fn pause(&self, id: JobId) -> Result<(), Error> {
+        self.set_paused(id, true)
+    }
+
+    pub fn resume(&self, id: JobId) -> Result<(), Error> {
+        self.set_paused(id, false)
+    }
+
+    fn set_paused(&self, id: JobId, paused: bool) -> Result<(), Error> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let record = jobs.get_mut(&id).ok_or(Error::JobNotFound(id))?;
+        if record.summary.status.is_finished() {
+            return Ok(());
+        }
+        record.control.pause.set_paused(paused);
+        record.summary.status = match (paused, record.summary.started_at) {
+            (true, _) => JobStatus::Paused,
+            (false, Some(_)) => JobStatus::Running,
+            (false, None) => JobStatus::Queued,
+        };
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<JobSummary> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|record| record.summary.clone())
+            .collect()
+    }
+
+    pub fn status(&self, id: JobId) -> Result<JobDetail, Error> {
+        let jobs = self.jobs.lock().unwrap();
+        let record = jobs.get(&id).ok_or(Error::JobNotFound(id))?;
+        Ok(JobDetail {
+            summary: record.summary.clone(),
+            result: record.result.clone(),
+        })
+    }
+
+    /// 讀取已完成工作的分析結果，不需複製整份結果。
+    pub fn with_result<R>(
+        &self,
+        id: JobId,
+        f: impl FnOnce(&AnalysisResult) -> R,
+    ) -> Result<R, Error> {
+        let jobs = self.jobs.lock().unwrap();
+        let record = jobs.get(&id).ok_or(Error::JobNotFound(id))?;
+        record
+            .result
+            .as_ref()
+            .map(f)
+            .ok_or(Error::ResultNotReady(id))
+    }
+
+    /// 修改已完成工作的分析結果，例如追蹤文章時併入新推文。
+    pub fn update_result<R>(
+        &self,
+        id: JobId,
+        f: impl FnOnce(&mut AnalysisResult) -> R,
+    ) -> Result<R, Error> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let record = jobs.get_mut(&id).ok_or(Error::JobNotFound(id))?;
+        record
+            .result
+            .as_mut()
+            .map(f)
+            .ok_or(Error::ResultNotReady(id))
+    }
+
+    /// 取得執行權；已有分析在跑時依設定排隊等待或直接拒絕。
+    pub async fn acquire_slot(
+        &self,
+        policy: ConcurrentAnalysisPolicy,
+    ) -> Result<SemaphorePermit<'_>, Error> {
+        match policy {
+            ConcurrentAnalysisPolicy::Queue => {
+                Ok(self.run_slot.acquire().await.expect("run slot 不會被關閉"))
+            }
+            ConcurrentAnalysisPolicy::Reject => {
+                self.run_slot.try_acquire().map_err(|_| Error::AnalysisBusy)
+            }
+        }
+    }
+}
+
+/// 所有分析相關事件的外層結構，附上工作 ID 讓前端分辨事件屬於哪個分析。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct JobEvent<T> {
+    job_id: JobId,
+    #[serde(flatten)]
+    payload: T,
+}
+
+/// 事件的實際發送端 (例如 Tauri 視窗)，讓分析流程不依賴特定的前端。
+pub trait EventSink: Send + Sync {
+    fn emit(&self, event: &str, payload: serde_json::Value);
+}
+
+/// 綁定工作 ID 的事件發送器。
+#[derive(Clone)]
+pub struct JobEmitter {
+    sink: Arc<dyn EventSink>,
+    job_id: JobId,
+}
+
+impl JobEmitter {
+    pub fn new(sink: Arc<dyn EventSink>, job_id: JobId) -> Self {
+        JobEmitter { sink, job_id }
+    }
+
+    pub fn job_id(&self) -> JobId {
+        self.job_id
+    }
+
+    pub fn emit<T: Serialize>(&self, event: &str, payload: T) {
+        let event_payload = JobEvent {
+            job_id: self.job_id,
+            payload,
+        };
+        match serde_json::to_value(event_payload) {
+            Ok(value) => self.sink.emit(event, value),
+            Err(e) => tracing::warn!(event, error = %e, "無法序列化事件 payload"),
+        }
+    }
+}
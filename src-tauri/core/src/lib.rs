@@ -0,0 +1,41 @@
+//! PTT 文章留言者分析的核心邏輯：爬取文章、查詢留言者在 pttweb.cc 的統計、排序與高亮。
+//!
+//! 不依賴 Tauri；事件經由 [`jobs::EventSink`] 發送，由呼叫端 (桌面 app、CLI 或測試) 決定送往何處。
+
+pub mod alerts;
+pub mod analysis;
+pub mod analyzers;
+pub mod article_url;
+pub mod backoff;
+pub mod batch;
+pub mod board_stats;
+pub mod concurrency;
+pub mod config;
+pub mod diagnostics;
+pub mod digest;
+pub mod disk_cache;
+pub mod domain;
+pub mod error;
+pub mod events;
+pub mod fetcher;
+pub mod groups;
+pub mod health;
+pub mod history;
+pub mod jobs;
+pub mod metrics;
+pub mod notes;
+pub mod profile;
+pub mod progress;
+pub mod providers;
+pub mod ptt_term;
+pub mod report;
+pub mod schedules;
+pub mod scraper;
+pub mod selectors;
+pub mod singleflight;
+pub mod sources;
+pub mod storage;
+pub mod timeline;
+pub mod trends;
+pub mod watchlists;
+pub mod webhooks;
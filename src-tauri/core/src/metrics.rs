@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// 查詢 pttweb.cc 期間的即時吞吐量統計，在併發查詢間共用。
+#[derive(Default)]
+pub struct ScrapeMetrics {
+    in_flight: AtomicUsize,
+    completed: AtomicUsize,
+    rate_limit_hits: AtomicU32,
+    retries: AtomicU32,
+    cache_hits: AtomicU32,
+}
+
+/// `SCRAPE_METRICS` 事件的 payload。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsPayload {
+    pub requests_per_sec: f64,
+    pub in_flight: usize,
+    pub completed: usize,
+    pub rate_limit_hits: u32,
+    pub cache_hit_ratio: f64,
+    /// 目前的併發上限 (依回應速度與錯誤自動調整)
+    pub concurrency: usize,
+}
+
+/// 請求進行期間持有，離開作用域時視為請求結束。
+pub struct InFlightGuard<'a> {
+    metrics: &'a ScrapeMetrics,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.metrics.completed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl ScrapeMetrics {
+    pub fn start_request(&self) -> InFlightGuard<'_> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard { metrics: self }
+    }
+
+    pub fn record_rate_limit(&self) {
+        self.rate_limit_hits.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn cache_hits(&self) -> u32 {
+        self.cache_hits.load(Ordering::SeqCst)
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::SeqCst)
+    }
+
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::SeqCst)
+    }
+
+    /// 產生目前的統計快照；`requests_per_sec` 與 `concurrency` 由呼叫端傳入。
+    pub fn snapshot(&self, requests_per_sec: f64, concurrency: usize) -> MetricsPayload {
+        let completed = self.completed();
+        let cache_hits = self.cache_hits.load(Ordering::SeqCst) as usize;
+        // 快取命中不經過 `start_request`，不計入 `completed`
+        let lookups = completed + cache_hits;
+        let cache_hit_ratio = if lookups > 0 {
+            cache_hits as f64 / lookups as f64
+        } else {
+            0.0
+        };
+
+        MetricsPayload {
+            requests_per_sec,
+            in_flight: self.in_flight.load(Ordering::SeqCst),
+            completed,
+            rate_limit_hits: self.rate_limit_hits.load(Ordering::SeqCst),
+            cache_hit_ratio,
+            concurrency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_hit_ratio_counts_cache_hits_as_lookups() {
+        let metrics = ScrapeMetrics::default();
+        for _ in 0..10 {
+            drop(metrics.start_request());
+        }
+        for _ in 0..30 {
+            metrics.record_cache_hit();
+        }
+
+        let snapshot = metrics.snapshot(0.0, 1);
+
+        assert_eq!(snapshot.completed, 10);
+        assert_eq!(snapshot.cache_hit_ratio, 0.75);
+    }
+}
@@ -0,0 +1,83 @@
+//! 使用者對帳號的備註與標籤 (例如「側翼」、「已證實分身」)，之後的分析結果會附上對應的備註。
+
+use crate::domain::UserId;
+use crate::error::Error;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 備註檔名稱，存放於 app data 目錄下。
+pub const NOTES_FILE_NAME: &str = "user_notes.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserNote {
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl UserNote {
+    fn is_empty(&self) -> bool {
+        self.note.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// 所有帳號的備註，每次修改後立即寫回磁碟。
+#[derive(Default)]
+pub struct NoteStore {
+    path: Option<PathBuf>,
+    notes: Mutex<HashMap<UserId, UserNote>>,
+}
+
+impl NoteStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從空的備註開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(NOTES_FILE_NAME));
+        let notes = storage::load_json(path.as_deref());
+        NoteStore {
+            path,
+            notes: Mutex::new(notes),
+        }
+    }
+
+    pub fn all(&self) -> HashMap<UserId, UserNote> {
+        self.notes.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, user: &UserId) -> Option<UserNote> {
+        self.notes.lock().unwrap().get(user).cloned()
+    }
+
+    /// 設定帳號的備註與標籤，兩者皆為空時移除該帳號的備註；寫入失敗時維持原本的備註。
+    pub fn set(&self, user: UserId, note: UserNote) -> Result<(), Error> {
+        let note = UserNote {
+            note: note.note.trim().to_string(),
+            tags: note
+                .tags
+                .into_iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        };
+        let mut notes = self.notes.lock().unwrap();
+        let previous = if note.is_empty() {
+            notes.remove(&user)
+        } else {
+            notes.insert(user.clone(), note)
+        };
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*notes) {
+                match previous {
+                    Some(previous) => notes.insert(user, previous),
+                    None => notes.remove(&user),
+                };
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
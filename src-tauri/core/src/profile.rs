@@ -0,0 +1,257 @@
+//! 單一使用者的 pttweb.cc 詳細資料：完整看板列表、近期留言、近期發文與活動日期，供前端的詳細資料面板使用。
+
+use crate::backoff::HostBackoff;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::fetcher::Fetcher;
+use crate::scraper::{parse_user_page, PushTypeCounts};
+use crate::selectors::{self, UserPageSelectors};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+const PTTWEB_ORIGIN: &str = "https://www.pttweb.cc";
+/// 分析結果中每位使用者最多列出的近期發文數。
+const RECENT_ARTICLES_LIMIT: usize = 10;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub user: UserId,
+    pub total_comments: Option<u32>,
+    /// 所有有留言的看板，留言數多的在前
+    pub boards: Vec<BoardActivity>,
+    pub recent_comments: Vec<CommentSample>,
+    pub recent_articles: Vec<ArticleEntry>,
+    /// 近期留言中推、噓、→ 的數量
+    pub recent_push_types: PushTypeCounts,
+    /// 近期留言與發文出現過的日期 (YYYY-MM-DD)，新的在前
+    pub active_dates: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardActivity {
+    pub board: BoardName,
+    pub comments: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentSample {
+    pub board: Option<BoardName>,
+    pub article_title: String,
+    pub article_url: Option<String>,
+    /// `push`、`hate`、`arrow`，無法辨識時為 `None`
+    pub comment_type: Option<&'static str>,
+    pub content: String,
+    /// 頁面上顯示的時間
+    pub time: String,
+}
+
+/// 使用者發表的一篇文章。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleEntry {
+    pub board: Option<BoardName>,
+    pub title: String,
+    pub url: Option<String>,
+    /// 頁面上顯示的時間
+    pub time: String,
+}
+
+/// 依序下載使用者的留言頁與文章頁並解析為完整的詳細資料；請求前遵守 pttweb.cc 的限流暫停。
+pub async fn fetch_user_profile(
+    user_id: &UserId,
+    fetcher: &dyn Fetcher,
+    backoff: &HostBackoff,
+) -> Result<UserProfile, Error> {
+    let message_url = format!("{}/user/{}?t=message", PTTWEB_ORIGIN, user_id);
+    let message_html = fetch_page(&message_url, user_id, fetcher, backoff).await?;
+    let article_url = format!("{}/user/{}?t=article", PTTWEB_ORIGIN, user_id);
+    let article_html = fetch_page(&article_url, user_id, fetcher, backoff).await?;
+
+    let selectors = Arc::clone(&selectors::current().pttweb_user);
+    let user = user_id.clone();
+    tokio::task::spawn_blocking(move || {
+        parse_profile(user, &message_html, &article_html, &selectors)
+    })
+    .await
+    .map_err(|e| Error::Internal(e.to_string()))?
+}
+
+/// 下載使用者的文章頁，只保留在 `boards` 中的近期發文 (最多 [`RECENT_ARTICLES_LIMIT`] 篇)。
+pub async fn fetch_recent_articles(
+    user_id: &UserId,
+    boards: &[BoardName],
+    fetcher: &dyn Fetcher,
+    backoff: &HostBackoff,
+) -> Result<Vec<ArticleEntry>, Error> {
+    let url = format!("{}/user/{}?t=article", PTTWEB_ORIGIN, user_id);
+    let html = fetch_page(&url, user_id, fetcher, backoff).await?;
+    let selectors = Arc::clone(&selectors::current().pttweb_user);
+    let articles = tokio::task::spawn_blocking(move || parse_articles(&html, &selectors))
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))?;
+    Ok(articles
+        .into_iter()
+        .filter(|article| article.board.as_ref().is_some_and(|b| boards.contains(b)))
+        .take(RECENT_ARTICLES_LIMIT)
+        .collect())
+}
+
+async fn fetch_page(
+    url: &str,
+    user_id: &UserId,
+    fetcher: &dyn Fetcher,
+    backoff: &HostBackoff,
+) -> Result<String, Error> {
+    backoff.wait_ready().await;
+    let response = fetcher.get(url).await?;
+    if let Err(e) = response.check_rate_limited(backoff.host()) {
+        if let Error::RateLimited {
+            retry_after_secs, ..
+        } = &e
+        {
+            backoff.on_rate_limited(retry_after_secs.map(Duration::from_secs));
+        }
+        return Err(e);
+    }
+    backoff.on_success();
+    if response.is_not_found() {
+        return Err(Error::PttWebUserNotFound(user_id.to_string()));
+    }
+    Ok(response.body)
+}
+
+/// 解析兩個頁面 (在 blocking 執行緒上執行)。
+fn parse_profile(
+    user: UserId,
+    message_html: &str,
+    article_html: &str,
+    selectors: &UserPageSelectors,
+) -> Result<UserProfile, Error> {
+    let page = parse_user_page(message_html, selectors);
+    if page.not_found {
+        return Err(Error::PttWebUserNotFound(user.to_string()));
+    }
+    let mut boards: Vec<BoardActivity> = page
+        .board_counts
+        .into_iter()
+        .map(|(board, comments)| BoardActivity { board, comments })
+        .collect();
+    boards.sort_by(|a, b| b.comments.cmp(&a.comments));
+
+    let message_document = Html::parse_document(message_html);
+    let recent_comments: Vec<CommentSample> = message_document
+        .select(&selectors.comment_item)
+        .map(|item| {
+            let (article_title, article_url) = link(item, selectors);
+            CommentSample {
+                board: board(item, selectors),
+                article_title,
+                article_url,
+                comment_type: comment_type(&text(item, &selectors.entry_tag)),
+                content: text(item, &selectors.entry_content),
+                time: text(item, &selectors.entry_time),
+            }
+        })
+        .collect();
+    let recent_articles = parse_articles(article_html, selectors);
+    let mut recent_push_types = PushTypeCounts::default();
+    for comment in &recent_comments {
+        match comment.comment_type {
+            Some("push") => recent_push_types.push += 1,
+            Some("hate") => recent_push_types.hate += 1,
+            Some("arrow") => recent_push_types.arrow += 1,
+            _ => {}
+        }
+    }
+
+    let active_dates: BTreeSet<String> = recent_comments
+        .iter()
+        .map(|comment| comment.time.as_str())
+        .chain(recent_articles.iter().map(|article| article.time.as_str()))
+        .filter_map(date_of)
+        .collect();
+
+    Ok(UserProfile {
+        user,
+        total_comments: page.total_comments,
+        boards,
+        recent_comments,
+        recent_articles,
+        recent_push_types,
+        active_dates: active_dates.into_iter().rev().collect(),
+    })
+}
+
+/// 解析使用者文章頁 (`?t=article`) 中的文章列表。
+fn parse_articles(html: &str, selectors: &UserPageSelectors) -> Vec<ArticleEntry> {
+    let document = Html::parse_document(html);
+    document
+        .select(&selectors.article_item)
+        .map(|item| {
+            let (title, url) = link(item, selectors);
+            ArticleEntry {
+                board: board(item, selectors),
+                title,
+                url,
+                time: text(item, &selectors.entry_time),
+            }
+        })
+        .collect()
+}
+
+fn comment_type(tag: &str) -> Option<&'static str> {
+    if tag.contains('推') {
+        Some("push")
+    } else if tag.contains('噓') {
+        Some("hate")
+    } else if tag.contains('→') {
+        Some("arrow")
+    } else {
+        None
+    }
+}
+
+fn text(item: ElementRef, selector: &Selector) -> String {
+    item.select(selector)
+        .next()
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .unwrap_or_default()
+}
+
+fn board(item: ElementRef, selectors: &UserPageSelectors) -> Option<BoardName> {
+    Some(text(item, &selectors.entry_board))
+        .filter(|name| !name.is_empty())
+        .map(BoardName::new)
+}
+
+/// 文章連結的標題與絕對網址。
+fn link(item: ElementRef, selectors: &UserPageSelectors) -> (String, Option<String>) {
+    let Some(anchor) = item.select(&selectors.entry_link).next() else {
+        return (String::new(), None);
+    };
+    let title = anchor.text().collect::<String>().trim().to_string();
+    let url = anchor.value().attr("href").map(|href| {
+        if href.starts_with('/') {
+            format!("{}{}", PTTWEB_ORIGIN, href)
+        } else {
+            href.to_string()
+        }
+    });
+    (title, url)
+}
+
+/// 取出時間字串開頭的日期 (`YYYY-MM-DD` 或 `YYYY/MM/DD`)，統一為 `YYYY-MM-DD`。
+fn date_of(time: &str) -> Option<String> {
+    let date = time.get(..10)?.replace('/', "-");
+    let is_date = date.char_indices().all(|(i, c)| match i {
+        4 | 7 => c == '-',
+        _ => c.is_ascii_digit(),
+    });
+    is_date.then_some(date)
+}
@@ -0,0 +1,103 @@
+use crate::domain::UserId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 計算移動平均速率時參考最近幾筆完成紀錄。
+const RATE_WINDOW: usize = 20;
+
+/// `SCRAPE_PROGRESS` 事件的 payload。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProgressPayload {
+    pub current: usize,
+    pub total: usize,
+    pub user_id: UserId,
+    pub completed: usize,
+    pub failed: usize,
+    pub cached: usize,
+    /// 最近完成的查詢的移動平均速率 (每秒幾位使用者)。
+    pub rate_per_sec: f64,
+    /// 依目前速率估計的剩餘秒數，速率未知時為 `None`。
+    pub eta_secs: Option<u64>,
+}
+
+/// 單筆查詢的結果類型，用於進度統計。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupOutcome {
+    Succeeded,
+    /// 由本機快取取得，沒有發出請求
+    Cached,
+    Failed,
+}
+
+/// 在併發查詢間共用的進度統計。
+pub struct ProgressTracker {
+    total: usize,
+    started: Instant,
+    inner: Mutex<ProgressInner>,
+}
+
+#[derive(Default)]
+struct ProgressInner {
+    completed: usize,
+    failed: usize,
+    cached: usize,
+    recent: VecDeque<Instant>,
+}
+
+impl ProgressTracker {
+    pub fn new(total: usize) -> Self {
+        ProgressTracker {
+            total,
+            started: Instant::now(),
+            inner: Mutex::new(ProgressInner::default()),
+        }
+    }
+
+    /// 記錄一位使用者查詢完成，並回傳要發送給前端的進度。
+    pub fn record(&self, user_id: &UserId, outcome: LookupOutcome) -> ProgressPayload {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        inner.completed += 1;
+        match outcome {
+            LookupOutcome::Succeeded => {}
+            LookupOutcome::Cached => inner.cached += 1,
+            LookupOutcome::Failed => inner.failed += 1,
+        }
+        if inner.recent.len() == RATE_WINDOW {
+            inner.recent.pop_front();
+        }
+        inner.recent.push_back(now);
+
+        let rate_per_sec = self.rate(&inner.recent, now);
+        let remaining = self.total.saturating_sub(inner.completed);
+        let eta_secs =
+            (rate_per_sec > 0.0).then(|| (remaining as f64 / rate_per_sec).ceil() as u64);
+
+        ProgressPayload {
+            current: inner.completed,
+            total: self.total,
+            user_id: user_id.clone(),
+            completed: inner.completed,
+            failed: inner.failed,
+            cached: inner.cached,
+            rate_per_sec,
+            eta_secs,
+        }
+    }
+
+    /// 以時間窗內的完成數計算速率；紀錄不足兩筆時以開始時間為起點。
+    fn rate(&self, recent: &VecDeque<Instant>, now: Instant) -> f64 {
+        let (count, since) = match recent.front() {
+            Some(&first) if recent.len() >= 2 => (recent.len() - 1, first),
+            _ => (recent.len(), self.started),
+        };
+        let elapsed = now.duration_since(since).as_secs_f64();
+        if elapsed > 0.0 {
+            count as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+}
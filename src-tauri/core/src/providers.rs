@@ -0,0 +1,200 @@
+use crate::config::UserStatsSource;
+use crate::diagnostics::unix_now;
+use crate::disk_cache;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::fetcher::Fetcher;
+use crate::report::PttWebData;
+use crate::scraper;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const CACHE_FILE_NAME: &str = "user_stats.json.zst";
+/// 快取資料的有效期限 (秒)。
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// 使用者留言統計的資料來源，依設定的順序組成查詢鏈。
+pub trait UserStatsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// 是否為本機快取；其他來源查到的資料會寫回快取。
+    fn is_cache(&self) -> bool {
+        false
+    }
+
+    /// 查詢使用者在指定看板的留言數；`Ok(None)` 代表此來源沒有資料，交給下一個來源。
+    fn lookup<'a>(
+        &'a self,
+        user_id: &'a UserId,
+        target_boards: &'a [BoardName],
+    ) -> BoxFuture<'a, Result<Option<PttWebData>, Error>>;
+}
+
+/// 即時查詢 pttweb.cc 的使用者頁面。
+pub struct PttWebProvider<'a> {
+    lookups: &'a scraper::UserPageLookups,
+    fetcher: &'a dyn Fetcher,
+}
+
+impl UserStatsProvider for PttWebProvider<'_> {
+    fn name(&self) -> &'static str {
+        "pttweb"
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        user_id: &'a UserId,
+        target_boards: &'a [BoardName],
+    ) -> BoxFuture<'a, Result<Option<PttWebData>, Error>> {
+        scraper::scrape_ptt_web(user_id, target_boards, self.lookups, self.fetcher)
+            .map(|result| result.map(Some))
+            .boxed()
+    }
+}
+
+/// 快取中的一筆使用者統計。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CachedStats {
+    data: PttWebData,
+    /// 查詢時指定的看板，快取只能回答這些看板的留言數
+    boards: Vec<BoardName>,
+    /// Unix 時間 (秒)
+    fetched_at: u64,
+}
+
+/// 放在 Tauri managed state 中的使用者統計快取，存放於 app cache 目錄。
+#[derive(Default)]
+pub struct UserStatsCache {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<UserId, CachedStats>>,
+}
+
+impl UserStatsCache {
+    /// 從 `cache_dir` (app cache 目錄) 載入 (zstd 壓縮的) 快取，檔案不存在或格式錯誤時從空的快取開始。
+    pub fn load(cache_dir: Option<&Path>) -> Self {
+        let path = cache_dir.map(|dir| dir.join(CACHE_FILE_NAME));
+        let entries = path
+            .as_ref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|compressed| disk_cache::decompress(&compressed).ok())
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        UserStatsCache {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// 取得未過期且涵蓋所有指定看板的快取資料。
+    fn get(&self, user_id: &UserId, target_boards: &[BoardName]) -> Option<PttWebData> {
+        let entries = self.entries.lock().unwrap();
+        let cached = entries.get(user_id)?;
+        let fresh = unix_now().saturating_sub(cached.fetched_at) < CACHE_TTL_SECS;
+        let covers_boards = target_boards.iter().all(|b| cached.boards.contains(b));
+        if !fresh || !covers_boards {
+            return None;
+        }
+
+        // 沿用呼叫端指定的看板寫法
+        let board_comments = target_boards
+            .iter()
+            .filter_map(|board| {
+                let count = cached.data.board_comments.get(board)?;
+                Some((board.clone(), *count))
+            })
+            .collect();
+        Some(PttWebData {
+            board_comments,
+            total_comments: cached.data.total_comments,
+            all_board_comments: cached.data.all_board_comments.clone(),
+        })
+    }
+
+    pub fn insert(&self, user_id: &UserId, target_boards: &[BoardName], data: &PttWebData) {
+        self.entries.lock().unwrap().insert(
+            user_id.clone(),
+            CachedStats {
+                data: data.clone(),
+                boards: target_boards.to_vec(),
+                fetched_at: unix_now(),
+            },
+        );
+    }
+
+    /// 將快取寫回磁碟，並順便移除過期的資料。
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let content = {
+            let mut entries = self.entries.lock().unwrap();
+            let now = unix_now();
+            entries.retain(|_, cached| now.saturating_sub(cached.fetched_at) < CACHE_TTL_SECS);
+            serde_json::to_vec(&*entries)
+        };
+        let result = content
+            .map_err(std::io::Error::from)
+            .and_then(|content| disk_cache::compress(&content))
+            .and_then(|compressed| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(path, compressed)
+            });
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), error = %e, "無法寫入使用者統計快取");
+        }
+    }
+}
+
+/// 從本機快取讀取先前查過的使用者統計。
+pub struct CacheProvider<'a> {
+    cache: &'a UserStatsCache,
+}
+
+impl UserStatsProvider for CacheProvider<'_> {
+    fn name(&self) -> &'static str {
+        "cache"
+    }
+
+    fn is_cache(&self) -> bool {
+        true
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        user_id: &'a UserId,
+        target_boards: &'a [BoardName],
+    ) -> BoxFuture<'a, Result<Option<PttWebData>, Error>> {
+        futures::future::ready(Ok(self.cache.get(user_id, target_boards))).boxed()
+    }
+}
+
+/// 依設定的來源順序建立查詢鏈，重複的來源只保留第一個。
+pub fn build_chain<'a>(
+    sources: &[UserStatsSource],
+    cache: &'a UserStatsCache,
+    lookups: &'a scraper::UserPageLookups,
+    fetcher: &'a dyn Fetcher,
+) -> Vec<Box<dyn UserStatsProvider + 'a>> {
+    let mut seen = Vec::new();
+    sources
+        .iter()
+        .filter(|source| {
+            let first = !seen.contains(*source);
+            seen.push(**source);
+            first
+        })
+        .map(|source| -> Box<dyn UserStatsProvider + 'a> {
+            match source {
+                UserStatsSource::Cache => Box::new(CacheProvider { cache }),
+                UserStatsSource::PttWeb => Box::new(PttWebProvider { lookups, fetcher }),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,219 @@
+use crate::config::PttTermConfig;
+use crate::error::Error;
+use futures::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// term.ptt.cc 網頁版終端機使用的 WebSocket 端點。
+const PTT_WS_URL: &str = "wss://ws.ptt.cc/bbs";
+const PTT_WS_ORIGIN: &str = "https://term.ptt.cc";
+/// 等待畫面出現預期文字的時間上限。
+const SCREEN_TIMEOUT: Duration = Duration::from_secs(10);
+/// 登入後最多處理幾個過場畫面 (重複登入、錯誤紀錄、按任意鍵繼續等)。
+const MAX_LOGIN_SCREENS: usize = 10;
+
+/// 去除 ANSI 控制碼 (顏色、游標移動)。
+static ANSI_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap());
+static LOGIN_COUNT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"《登入次數》\s*(\d+)\s*次").unwrap());
+static LAST_LOGIN_IP_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"《上次故鄉》\s*([0-9A-Za-z.:\-]+)").unwrap());
+static REGISTERED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"《(?:註冊日期|帳號建立)》\s*([0-9/]+)").unwrap());
+
+/// 只有 BBS `query` 指令才查得到的使用者資料，是判斷分身帳號最有力的依據。
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PttUserInfo {
+    /// 帳號建立日期，格式依 BBS 顯示 (例如 `2010/01/31`)
+    pub registered_at: Option<String>,
+    pub login_count: Option<u32>,
+    pub last_login_ip: Option<String>,
+}
+
+impl PttUserInfo {
+    /// 帳號建立至 `now` (Unix 秒) 的天數；註冊日期不是 `YYYY/MM/DD` 格式時為 `None`。
+    pub fn account_age_days(&self, now: u64) -> Option<u64> {
+        let date = self.registered_at.as_deref()?;
+        let mut parts = date.split('/').map(|part| part.trim().parse::<i64>().ok());
+        let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        let registered = days_from_civil(year, month, day);
+        let today = (now / 86_400) as i64;
+        u64::try_from(today - registered).ok()
+    }
+}
+
+/// 西元日期距 1970-01-01 的天數 (Howard Hinnant 的 days_from_civil 演算法)。
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// 以使用者帳號登入 term.ptt.cc 的連線，依序查詢使用者資料。
+pub struct PttTermClient {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    /// 上次送出按鍵後收到的原始資料
+    raw: Vec<u8>,
+    /// `raw` 解碼並去除控制碼後的畫面內容
+    screen: String,
+}
+
+impl PttTermClient {
+    /// 連線並登入，停在主功能表。
+    pub async fn connect(config: &PttTermConfig) -> Result<Self, Error> {
+        let mut request = PTT_WS_URL
+            .into_client_request()
+            .map_err(|e| Error::PttTerm(e.to_string()))?;
+        request
+            .headers_mut()
+            .insert("Origin", HeaderValue::from_static(PTT_WS_ORIGIN));
+        let (ws, _) = connect_async(request)
+            .await
+            .map_err(|e| Error::PttTerm(e.to_string()))?;
+
+        let mut client = PttTermClient {
+            ws,
+            raw: Vec::new(),
+            screen: String::new(),
+        };
+        client.login(config).await?;
+        Ok(client)
+    }
+
+    async fn login(&mut self, config: &PttTermConfig) -> Result<(), Error> {
+        self.wait_for(&["請輸入代號"]).await?;
+        self.send(&format!("{}\r", config.username)).await?;
+        self.wait_for(&["請輸入您的密碼"]).await?;
+        self.send(&format!("{}\r", config.password)).await?;
+
+        for _ in 0..MAX_LOGIN_SCREENS {
+            let screen = self
+                .wait_for(&[
+                    "密碼不對",
+                    "刪除其他重複登入",
+                    "錯誤嘗試",
+                    "請按任意鍵繼續",
+                    "主功能表",
+                ])
+                .await?;
+            match screen {
+                0 => return Err(Error::PttTermLogin(config.username.clone())),
+                // 保留其他裝置上的連線，也不刪除錯誤登入紀錄
+                1 | 2 => self.send("n\r").await?,
+                3 => self.send("\r").await?,
+                _ => return Ok(()),
+            }
+        }
+        Err(Error::PttTerm("unexpected screens after login".to_string()))
+    }
+
+    /// 在主功能表以 `T` (聊天說話) → `Q` (查詢網友) 查詢使用者，結束後回到主功能表。
+    pub async fn query_user(&mut self, user_id: &str) -> Result<PttUserInfo, Error> {
+        self.send("T\r").await?;
+        // 選單項目顯示為 `(Q)uery`
+        self.wait_for(&["uery"]).await?;
+        self.send("Q\r").await?;
+        self.wait_for(&["代號"]).await?;
+        self.send(&format!("{}\r", user_id)).await?;
+
+        let result = self
+            .wait_for(&["《登入次數》", "主功能表", "聊天說話"])
+            .await?;
+        if result != 0 {
+            return Err(Error::PttTerm(format!("user {} not found", user_id)));
+        }
+        // 等畫面其餘欄位送達
+        self.wait_for(&["請按任意鍵繼續"]).await?;
+        let info = parse_query_screen(&self.screen);
+
+        self.send("\r").await?;
+        // 左方向鍵離開聊天選單
+        self.send("\x1b[D").await?;
+        self.wait_for(&["主功能表"]).await?;
+        Ok(info)
+    }
+
+    /// 登出並關閉連線。
+    pub async fn close(mut self) {
+        let _ = self.send("G\rY\r").await;
+        let _ = self.ws.close(None).await;
+    }
+
+    async fn send(&mut self, keys: &str) -> Result<(), Error> {
+        self.raw.clear();
+        self.screen.clear();
+        let (bytes, _, _) = encoding_rs::BIG5.encode(keys);
+        self.ws
+            .send(Message::Binary(bytes.into_owned().into()))
+            .await
+            .map_err(|e| Error::PttTerm(e.to_string()))
+    }
+
+    /// 持續讀取畫面直到出現其中一段文字，回傳符合的是第幾段。
+    async fn wait_for(&mut self, patterns: &[&str]) -> Result<usize, Error> {
+        let read = async {
+            loop {
+                if let Some(index) = patterns.iter().position(|p| self.screen.contains(p)) {
+                    return Ok(index);
+                }
+                let message = self
+                    .ws
+                    .next()
+                    .await
+                    .ok_or_else(|| Error::PttTerm("connection closed".to_string()))?
+                    .map_err(|e| Error::PttTerm(e.to_string()))?;
+                self.raw.extend_from_slice(&message.into_data());
+                self.screen = decode_screen(&self.raw);
+            }
+        };
+        timeout(SCREEN_TIMEOUT, read)
+            .await
+            .map_err(|_| Error::PttTerm(format!("timed out waiting for {:?}", patterns)))?
+    }
+}
+
+/// 去除 telnet 協商 (IAC) 後以 Big5 解碼，並移除 ANSI 控制碼。
+fn decode_screen(raw: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut iter = raw.iter().copied();
+    while let Some(byte) = iter.next() {
+        if byte == 0xFF {
+            // IAC 後接指令與選項各一個位元組
+            iter.next();
+            iter.next();
+            continue;
+        }
+        bytes.push(byte);
+    }
+    let (text, _, _) = encoding_rs::BIG5.decode(&bytes);
+    ANSI_RE.replace_all(&text, "").into_owned()
+}
+
+/// 解析查詢結果畫面中的欄位，找不到的欄位為 `None`。
+fn parse_query_screen(screen: &str) -> PttUserInfo {
+    PttUserInfo {
+        registered_at: REGISTERED_RE
+            .captures(screen)
+            .map(|caps| caps[1].to_string()),
+        login_count: LOGIN_COUNT_RE
+            .captures(screen)
+            .and_then(|caps| caps[1].parse().ok()),
+        last_login_ip: LAST_LOGIN_IP_RE
+            .captures(screen)
+            .map(|caps| caps[1].to_string()),
+    }
+}
@@ -0,0 +1,719 @@
+//! 分析報告的資料結構，以及排序與高亮的規則。
+
+use crate::board_stats::{self, BoardOverlap};
+use crate::config::SortingConfig;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::groups::UserGroup;
+use crate::jobs::JobId;
+use crate::notes::UserNote;
+use crate::profile::ArticleEntry;
+use crate::ptt_term::PttUserInfo;
+use crate::scraper::{ArticleEdit, ArticleSource, ForwardedFrom, ParseWarning, PushTypeCounts};
+use crate::timeline::PushTimeline;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// `get_result_page` 單次最多回傳的資料列數。
+pub const MAX_RESULT_PAGE_SIZE: usize = 500;
+
+/// 依序將舊版報告轉換為下一版的函式，`MIGRATIONS[n]` 將第 n + 1 版轉為第 n + 2 版。
+/// 報告結構變更時在此加入轉換，並不需要另外修改 `SCHEMA_VERSION`。
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v1_to_v2];
+
+/// 依推噓比排序或高亮時使用的欄位名稱，推噓比以推文佔推、噓總數的百分比計算。
+pub const PUSH_RATIO_KEY: &str = "推噓比";
+
+/// 高亮時使用的新帳號欄位名稱，符合時值為 1，否則為 0，例如 `新帳號,==,1`。
+pub const NEW_ACCOUNT_KEY: &str = "新帳號";
+
+/// 排序或高亮時使用的生涯總留言數欄位名稱，例如 `生涯總留言數,<,300`。
+pub const TOTAL_COMMENTS_KEY: &str = "生涯總留言數";
+
+/// 目前產生的報告格式版本。
+pub const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+// --- 核心資料結構 (Core Data Structures) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PttWebData {
+    pub board_comments: HashMap<BoardName, u32>,
+    pub total_comments: u32,
+    // 使用者頁面上所有看板的留言數 (不限追蹤看板)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub all_board_comments: HashMap<BoardName, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserReportData {
+    pub user: UserId,
+    pub article_comments: u32,
+    pub board_comments: HashMap<BoardName, u32>,
+    pub total_comments: u32,
+    // 所有看板的留言數，需在分析選項中啟用 full_board_distribution
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub all_board_comments: HashMap<BoardName, u32>,
+    // 本文中推、噓、→ 的數量
+    #[serde(default)]
+    pub push_types: PushTypeCounts,
+    // 從 term.ptt.cc 查詢到的註冊日期、登入次數等資料，未啟用或查詢失敗時為 None
+    #[serde(default)]
+    pub term_info: Option<PttUserInfo>,
+    // 分析器管線計算的附加欄位 (例如 `score`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, serde_json::Value>,
+    // 包含此使用者的觀察名單名稱
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watchlists: Vec<String>,
+    // 使用者對此帳號的備註與標籤
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<UserNote>,
+    // 群組檢視中合併為此列的帳號；此時 `user` 為群組名稱
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<UserId>,
+    // 此使用者在先前分析過的其他文章中出現的次數與文章網址
+    #[serde(default)]
+    pub times_seen: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub articles_seen_in: Vec<String>,
+    // 此使用者在目標看板的近期發文，需在分析選項中啟用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_articles: Vec<ArticleEntry>,
+    // 第一則推文距發文的分鐘數，取不到發文或推文時間時為 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_push_latency_minutes: Option<u32>,
+    // 計入的留言所在的樓層 (1 起算)，由小到大；可看出帳號是早期、後期還是持續留言
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub floors: Vec<usize>,
+    // 在紀錄中幾乎每次都在此作者發文後兩分鐘內留言
+    #[serde(default)]
+    pub fast_responder: bool,
+    // 生涯總留言數過少或註冊未滿設定天數，條件見 `NewAccountConfig`
+    #[serde(default)]
+    pub is_new_account: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisResult {
+    // 報告格式版本，讀取時依此轉換舊版報告
+    pub schema_version: u64,
+    pub job_id: JobId,
+    pub metadata: ReportMetadata,
+    pub highlighted_data: Vec<UserReportData>,
+    pub normal_data: Vec<UserReportData>,
+    // pttweb.cc 查詢失敗 (非「查無此人」) 的使用者，其資料列數值不可信
+    pub failed_users: Vec<FailedUser>,
+    // pttweb.cc 上查無資料的使用者
+    pub not_found_users: Vec<UserId>,
+    // 本次分析的耗時與請求統計
+    pub diagnostics: AnalysisDiagnostics,
+    // 無法辨識的推文，代表部分留言可能被誤算
+    pub warnings: Vec<ParseWarning>,
+    // 因超過分析時限而提前結束，`skipped_users` 的資料列數值不完整
+    pub partial: bool,
+    pub skipped_users: Vec<UserId>,
+    // 分析器管線產生的附加區塊 (例如 `ipGroups`)
+    #[serde(default)]
+    pub sections: BTreeMap<String, serde_json::Value>,
+    // 整體概況，由資料列計算；匯入報告時重新計算
+    #[serde(default)]
+    pub summary: SummaryStats,
+    // 推文時間軸，推文沒有時間資料時為 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<PushTimeline>,
+}
+
+impl AnalysisResult {
+    /// 各留言者在本文的留言數，追蹤文章時作為比對新推文的起點。
+    pub fn comment_counts(&self) -> HashMap<UserId, u32> {
+        self.highlighted_data
+            .iter()
+            .chain(&self.normal_data)
+            .map(|row| (row.user.clone(), row.article_comments))
+            .collect()
+    }
+
+    /// 不含資料列的結果摘要，資料列另外以 `page` 分頁取得。
+    pub fn summary(&self) -> ResultSummary {
+        ResultSummary {
+            job_id: self.job_id,
+            metadata: self.metadata.clone(),
+            highlighted_count: self.highlighted_data.len(),
+            normal_count: self.normal_data.len(),
+            failed_users: self.failed_users.clone(),
+            not_found_users: self.not_found_users.clone(),
+            diagnostics: self.diagnostics.clone(),
+            warnings: self.warnings.clone(),
+            partial: self.partial,
+            skipped_users: self.skipped_users.clone(),
+            sections: self.sections.clone(),
+            summary: self.summary.clone(),
+            timeline: self.timeline.clone(),
+        }
+    }
+
+    /// 依排序後的順序 (高亮資料列在前) 取出一段資料列。
+    pub fn page(&self, offset: usize, limit: usize) -> ResultPage {
+        page_of(
+            self.job_id,
+            &self.highlighted_data,
+            &self.normal_data,
+            offset,
+            limit,
+        )
+    }
+
+    /// 群組檢視的分頁：同一群組的帳號合併為一列，見 [`group_rows`]。
+    pub fn grouped_page(&self, groups: &[UserGroup], offset: usize, limit: usize) -> ResultPage {
+        let (highlighted, normal) = group_rows(&self.highlighted_data, &self.normal_data, groups);
+        page_of(self.job_id, &highlighted, &normal, offset, limit)
+    }
+}
+
+fn page_of(
+    job_id: JobId,
+    highlighted: &[UserReportData],
+    normal: &[UserReportData],
+    offset: usize,
+    limit: usize,
+) -> ResultPage {
+    let rows = highlighted
+        .iter()
+        .chain(normal)
+        .skip(offset)
+        .take(limit.min(MAX_RESULT_PAGE_SIZE))
+        .cloned()
+        .collect();
+    ResultPage {
+        job_id,
+        offset,
+        total: highlighted.len() + normal.len(),
+        highlighted_count: highlighted.len(),
+        rows,
+    }
+}
+
+/// 將同一群組的帳號合併為一列：本文、各看板與生涯總留言數加總，`members` 列出出現在結果中的成員。
+/// 合併列位於排名最前的成員原本的位置，任一成員被高亮時整列高亮；不屬於任何群組的資料列維持不變。
+/// 帳號屬於多個群組時併入 `groups` 中較前面的群組。
+pub fn group_rows(
+    highlighted: &[UserReportData],
+    normal: &[UserReportData],
+    groups: &[UserGroup],
+) -> (Vec<UserReportData>, Vec<UserReportData>) {
+    let mut group_of: HashMap<&UserId, &str> = HashMap::new();
+    for group in groups {
+        for member in &group.members {
+            group_of.entry(member).or_insert(&group.name);
+        }
+    }
+
+    // (資料列, 是否高亮)；群組的合併列以群組名稱記錄位置
+    let mut rows: Vec<(UserReportData, bool)> = Vec::new();
+    let mut group_positions: HashMap<&str, usize> = HashMap::new();
+    let tagged = highlighted
+        .iter()
+        .map(|row| (row, true))
+        .chain(normal.iter().map(|row| (row, false)));
+    for (row, is_highlighted) in tagged {
+        let Some(&group) = group_of.get(&row.user) else {
+            rows.push((row.clone(), is_highlighted));
+            continue;
+        };
+        match group_positions.get(group) {
+            Some(&position) => {
+                let (merged, merged_highlighted) = &mut rows[position];
+                merge_into(merged, row);
+                *merged_highlighted |= is_highlighted;
+            }
+            None => {
+                group_positions.insert(group, rows.len());
+                let mut merged = UserReportData {
+                    user: UserId::from(group),
+                    article_comments: 0,
+                    board_comments: HashMap::new(),
+                    total_comments: 0,
+                    all_board_comments: HashMap::new(),
+                    push_types: PushTypeCounts::default(),
+                    term_info: None,
+                    fields: BTreeMap::new(),
+                    watchlists: Vec::new(),
+                    note: None,
+                    members: Vec::new(),
+                    times_seen: 0,
+                    articles_seen_in: Vec::new(),
+                    recent_articles: Vec::new(),
+                    first_push_latency_minutes: None,
+                    floors: Vec::new(),
+                    fast_responder: false,
+                    is_new_account: false,
+                };
+                merge_into(&mut merged, row);
+                rows.push((merged, is_highlighted));
+            }
+        }
+    }
+
+    let (highlighted, normal): (Vec<_>, Vec<_>) = rows
+        .into_iter()
+        .partition(|(_, is_highlighted)| *is_highlighted);
+    (
+        highlighted.into_iter().map(|(row, _)| row).collect(),
+        normal.into_iter().map(|(row, _)| row).collect(),
+    )
+}
+
+fn merge_into(merged: &mut UserReportData, row: &UserReportData) {
+    merged.article_comments += row.article_comments;
+    merged.push_types.push += row.push_types.push;
+    merged.push_types.hate += row.push_types.hate;
+    merged.push_types.arrow += row.push_types.arrow;
+    merged.total_comments += row.total_comments;
+    for (board, count) in &row.board_comments {
+        *merged.board_comments.entry(board.clone()).or_insert(0) += count;
+    }
+    for (board, count) in &row.all_board_comments {
+        *merged.all_board_comments.entry(board.clone()).or_insert(0) += count;
+    }
+    for watchlist in &row.watchlists {
+        if !merged.watchlists.contains(watchlist) {
+            merged.watchlists.push(watchlist.clone());
+        }
+    }
+    merged.first_push_latency_minutes = match (
+        merged.first_push_latency_minutes,
+        row.first_push_latency_minutes,
+    ) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    merged.floors.extend(&row.floors);
+    merged.floors.sort_unstable();
+    merged.fast_responder |= row.fast_responder;
+    merged.is_new_account |= row.is_new_account;
+    merged.members.push(row.user.clone());
+}
+
+/// `get_result_summary` 的回傳值：除了資料列以外的完整分析結果。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultSummary {
+    pub job_id: JobId,
+    pub metadata: ReportMetadata,
+    pub highlighted_count: usize,
+    pub normal_count: usize,
+    pub failed_users: Vec<FailedUser>,
+    pub not_found_users: Vec<UserId>,
+    pub diagnostics: AnalysisDiagnostics,
+    pub warnings: Vec<ParseWarning>,
+    pub partial: bool,
+    pub skipped_users: Vec<UserId>,
+    pub sections: BTreeMap<String, serde_json::Value>,
+    pub summary: SummaryStats,
+    pub timeline: Option<PushTimeline>,
+}
+
+/// 分析結果的整體概況，供前端一眼看出留言者的組成。
+/// 平均、中位數與比例只計入有查到統計的留言者 (不含查無此人、查詢失敗與未查詢的使用者)。
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryStats {
+    pub commenter_count: usize,
+    pub highlighted_count: usize,
+    /// 有查到統計的留言者數
+    pub enriched_count: usize,
+    pub mean_total_comments: f64,
+    pub median_total_comments: f64,
+    pub mean_article_comments: f64,
+    /// 在文章所屬看板沒有任何留言的帳號比例 (0 ~ 1)
+    pub zero_board_activity_share: f64,
+    pub not_found_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    #[serde(default)]
+    pub histograms: Histograms,
+    /// 追蹤看板以外，最多留言者經常留言的看板 (需取得完整的看板分布)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub board_overlap: Vec<BoardOverlap>,
+    /// 最多留言者列為留言數前 5 名的看板 (需取得完整的看板分布)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub home_boards: Vec<BoardOverlap>,
+}
+
+/// 生涯總留言數的分組下限，最後一組沒有上限。
+const TOTAL_COMMENTS_EDGES: &[f64] = &[0.0, 1.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+/// 看板留言比例的分組下限 (每 10% 一組)，最後一組包含 100%。
+const BOARD_RATIO_EDGES: &[f64] = &[0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+
+/// 已分組的分布資料，前端可直接畫成長條圖，不必取得所有資料列再計算。
+/// 與平均值相同，只計入有查到統計的留言者。
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Histograms {
+    pub total_comments: Vec<HistogramBucket>,
+    /// 各追蹤看板的留言佔生涯總留言的比例 (0 ~ 1)，生涯總留言為 0 的帳號不列入
+    pub board_ratios: BTreeMap<BoardName, Vec<HistogramBucket>>,
+}
+
+/// 落在 `[lower, upper)` 的數量；`upper` 為 `None` 時沒有上限。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: Option<f64>,
+    pub count: usize,
+}
+
+impl Histograms {
+    fn compute(rows: &[&UserReportData]) -> Self {
+        let total_comments = histogram(
+            rows.iter().map(|row| row.total_comments as f64),
+            TOTAL_COMMENTS_EDGES,
+        );
+        let boards: BTreeSet<&BoardName> = rows
+            .iter()
+            .flat_map(|row| row.board_comments.keys())
+            .collect();
+        let board_ratios = boards
+            .into_iter()
+            .map(|board| {
+                let ratios = rows.iter().filter(|row| row.total_comments > 0).map(|row| {
+                    let count = row.board_comments.get(board).copied().unwrap_or(0);
+                    count as f64 / row.total_comments as f64
+                });
+                (board.clone(), histogram(ratios, BOARD_RATIO_EDGES))
+            })
+            .collect();
+        Histograms {
+            total_comments,
+            board_ratios,
+        }
+    }
+}
+
+fn histogram(values: impl Iterator<Item = f64>, edges: &[f64]) -> Vec<HistogramBucket> {
+    let mut buckets: Vec<HistogramBucket> = edges
+        .iter()
+        .enumerate()
+        .map(|(i, &lower)| HistogramBucket {
+            lower,
+            upper: edges.get(i + 1).copied(),
+            count: 0,
+        })
+        .collect();
+    for value in values {
+        // 小於第一組下限的值歸入第一組
+        let index = edges.iter().rposition(|&lower| value >= lower).unwrap_or(0);
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+impl SummaryStats {
+    pub fn compute(result: &AnalysisResult) -> Self {
+        let rows: Vec<&UserReportData> = result
+            .highlighted_data
+            .iter()
+            .chain(&result.normal_data)
+            .collect();
+        let excluded: HashSet<&UserId> = result
+            .not_found_users
+            .iter()
+            .chain(&result.skipped_users)
+            .chain(result.failed_users.iter().map(|failed| &failed.user))
+            .collect();
+        let enriched: Vec<&UserReportData> = rows
+            .iter()
+            .copied()
+            .filter(|row| !excluded.contains(&row.user))
+            .collect();
+
+        let mut totals: Vec<u32> = enriched.iter().map(|row| row.total_comments).collect();
+        totals.sort_unstable();
+        let median_total_comments = match totals.len() {
+            0 => 0.0,
+            n if n % 2 == 1 => totals[n / 2] as f64,
+            n => (totals[n / 2 - 1] as f64 + totals[n / 2] as f64) / 2.0,
+        };
+        let zero_board_activity = enriched
+            .iter()
+            .filter(|row| {
+                row.board_comments
+                    .get(&result.metadata.board)
+                    .is_none_or(|count| *count == 0)
+            })
+            .count();
+
+        SummaryStats {
+            commenter_count: rows.len(),
+            highlighted_count: result.highlighted_data.len(),
+            enriched_count: enriched.len(),
+            mean_total_comments: mean(totals.iter().map(|&total| total as f64)),
+            median_total_comments,
+            mean_article_comments: mean(rows.iter().map(|row| row.article_comments as f64)),
+            zero_board_activity_share: ratio(zero_board_activity, enriched.len()),
+            not_found_count: result.not_found_users.len(),
+            failed_count: result.failed_users.len(),
+            skipped_count: result.skipped_users.len(),
+            histograms: Histograms::compute(&enriched),
+            board_overlap: board_stats::board_overlap(&enriched),
+            home_boards: board_stats::home_boards(&enriched),
+        }
+    }
+}
+
+fn mean(values: impl ExactSizeIterator<Item = f64>) -> f64 {
+    let count = values.len();
+    if count == 0 {
+        return 0.0;
+    }
+    values.sum::<f64>() / count as f64
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// `get_result_page` 的回傳值；索引小於 `highlighted_count` 的資料列為高亮資料列。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultPage {
+    pub job_id: JobId,
+    pub offset: usize,
+    pub total: usize,
+    pub highlighted_count: usize,
+    pub rows: Vec<UserReportData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisDiagnostics {
+    pub total_ms: u64,
+    pub phases: PhaseTimings,
+    pub request_count: u32,
+    pub retry_count: u32,
+    pub cache_hits: u32,
+    pub slowest_users: Vec<UserTiming>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTimings {
+    pub article_ms: u64,
+    pub enrichment_ms: u64,
+    pub post_processing_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTiming {
+    pub user: UserId,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FailedUser {
+    pub user: UserId,
+    pub error_code: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportMetadata {
+    pub title: String,
+    pub url: String,
+    pub board: BoardName,
+    #[serde(default)]
+    pub author: Option<UserId>,
+    /// 文章內文，不含標題列、推文與簽名檔
+    #[serde(default)]
+    pub body: String,
+    /// 發文時間 (RFC 3339，台灣時間)，取不到時為空
+    #[serde(default)]
+    pub posted_at: Option<String>,
+    /// 文章的修改紀錄，沒有修改過時為空陣列
+    #[serde(default)]
+    pub edits: Vec<ArticleEdit>,
+    /// 文章的推文總數，與資料列的 `floors` 對照可看出留言在討論串中的位置
+    #[serde(default)]
+    pub total_floors: usize,
+    /// 轉錄文章的原文看板與網址，不是轉錄文章時為空
+    #[serde(default)]
+    pub forwarded_from: Option<ForwardedFrom>,
+    pub filter_types: Vec<String>,
+    pub keywords: Option<Vec<String>>,
+    pub min_content_length: Option<usize>,
+    pub max_content_length: Option<usize>,
+    pub push_range_start: Option<usize>,
+    pub push_range_end: Option<usize>,
+    pub highlight_condition: Option<String>,
+    // 推文資料的來源；原文被刪除時為 pttWebMirror
+    pub source: ArticleSource,
+}
+
+/// 依設定的欄位 (本文留言數、生涯總留言數、推噓比或看板名稱) 與方向排序資料列。
+/// 依推噓比排序時，沒有推也沒有噓的使用者視為最低。
+pub fn sort_rows(rows: &mut [UserReportData], sorting: &SortingConfig) {
+    // 依看板排序時使用的看板，在排序前建立一次
+    let sort_board = BoardName::from(sorting.sort_by.as_str());
+    rows.sort_by(|a, b| {
+        let val_a: u32;
+        let val_b: u32;
+
+        match sorting.sort_by.as_str() {
+            "本文留言數" => {
+                val_a = a.article_comments;
+                val_b = b.article_comments;
+            }
+            TOTAL_COMMENTS_KEY => {
+                val_a = a.total_comments;
+                val_b = b.total_comments;
+            }
+            PUSH_RATIO_KEY => {
+                let key = |row: &UserReportData| {
+                    row.push_types
+                        .push_ratio()
+                        .map_or(0, |ratio| (ratio * 1000.0).round() as u32 + 1)
+                };
+                val_a = key(a);
+                val_b = key(b);
+            }
+            _ => {
+                val_a = *a.board_comments.get(&sort_board).unwrap_or(&0);
+                val_b = *b.board_comments.get(&sort_board).unwrap_or(&0);
+            }
+        }
+
+        if sorting.order == "desc" {
+            val_b.cmp(&val_a)
+        } else {
+            val_a.cmp(&val_b)
+        }
+    });
+}
+
+/// 高亮條件，格式為 `看板,運算子,數值`，數值結尾為 `%` 時比較該看板留言佔生涯總留言的比例。
+/// 看板為 [`PUSH_RATIO_KEY`] 時改為比較本文推噓比 (百分比)，例如 `推噓比,<=,20`；
+/// 為 [`NEW_ACCOUNT_KEY`] 時比較是否為新帳號 (1 或 0)；為 [`TOTAL_COMMENTS_KEY`] 時比較生涯總留言數。
+/// 警示規則 ([`crate::alerts`]) 的每個條件也使用同樣的格式。
+pub(crate) struct HighlightRule<'a> {
+    board: BoardName,
+    operator: &'a str,
+    threshold: f64,
+    is_percentage: bool,
+}
+
+impl<'a> HighlightRule<'a> {
+    /// 解析高亮條件，空字串或格式錯誤時為 `None`。
+    pub(crate) fn parse(condition: &'a str) -> Option<Self> {
+        let parts: Vec<&str> = condition.split(',').collect();
+        if parts.len() != 3 {
+            return None;
+        }
+        let value_str = parts[2].trim();
+        let threshold = value_str
+            .trim_end_matches('%')
+            .parse::<f64>()
+            .unwrap_or(-1.0);
+        if threshold < 0.0 {
+            return None;
+        }
+        Some(HighlightRule {
+            board: BoardName::from(parts[0]),
+            operator: parts[1].trim(),
+            threshold,
+            is_percentage: value_str.ends_with('%'),
+        })
+    }
+
+    pub(crate) fn matches(&self, user: &UserReportData) -> bool {
+        if self.board.as_str() == PUSH_RATIO_KEY {
+            return user
+                .push_types
+                .push_ratio()
+                .is_some_and(|ratio| self.compare(ratio * 100.0));
+        }
+        if self.board.as_str() == NEW_ACCOUNT_KEY {
+            return self.compare(if user.is_new_account { 1.0 } else { 0.0 });
+        }
+        if self.board.as_str() == TOTAL_COMMENTS_KEY {
+            return self.compare(user.total_comments as f64);
+        }
+        let board_comments = *user.board_comments.get(&self.board).unwrap_or(&0) as f64;
+        let total_comments = user.total_comments as f64;
+        let value_to_compare = if self.is_percentage && total_comments > 0.0 {
+            (board_comments / total_comments) * 100.0
+        } else {
+            board_comments
+        };
+        self.compare(value_to_compare)
+    }
+
+    fn compare(&self, value_to_compare: f64) -> bool {
+        match self.operator {
+            "<" => value_to_compare < self.threshold,
+            "<=" => value_to_compare <= self.threshold,
+            ">" => value_to_compare > self.threshold,
+            ">=" => value_to_compare >= self.threshold,
+            "==" => (value_to_compare - self.threshold).abs() < 1e-9,
+            _ => false,
+        }
+    }
+}
+
+/// 依高亮條件將資料列分為 (高亮, 一般) 兩組，保留原本的排序；沒有有效條件時全部為一般資料列。
+pub fn split_highlighted(
+    rows: Vec<UserReportData>,
+    condition: Option<&str>,
+) -> (Vec<UserReportData>, Vec<UserReportData>) {
+    match condition
+        .filter(|s| !s.is_empty())
+        .and_then(HighlightRule::parse)
+    {
+        Some(rule) => rows.into_iter().partition(|user| rule.matches(user)),
+        None => (vec![], rows),
+    }
+}
+
+/// 讀取儲存或匯入的報告，舊版報告會先轉換為目前的格式。
+pub fn load_report(bytes: &[u8]) -> Result<AnalysisResult, Error> {
+    let mut report: Value =
+        serde_json::from_slice(bytes).map_err(|e| Error::InvalidReport(e.to_string()))?;
+    let fields = report
+        .as_object_mut()
+        .ok_or_else(|| Error::InvalidReport("report is not a JSON object".to_string()))?;
+
+    // 加入版本欄位之前的報告視為第 1 版
+    let version = fields
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .unwrap_or(1);
+    if version > SCHEMA_VERSION {
+        return Err(Error::UnsupportedReportVersion {
+            version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    for migrate in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migrate(fields);
+    }
+    fields.insert("schemaVersion".to_string(), SCHEMA_VERSION.into());
+
+    let mut result: AnalysisResult =
+        serde_json::from_value(report).map_err(|e| Error::InvalidReport(e.to_string()))?;
+    result.summary = SummaryStats::compute(&result);
+    Ok(result)
+}
+
+/// 第 1 版沒有分析器管線產生的 `sections`。
+fn migrate_v1_to_v2(report: &mut Map<String, Value>) {
+    report
+        .entry("sections")
+        .or_insert_with(|| Value::Object(Map::new()));
+}
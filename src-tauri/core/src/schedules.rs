@@ -0,0 +1,262 @@
+//! 定期執行的分析排程：每隔固定時間分析指定的文章，或指定看板的最新文章。
+//! 排程定義存放於 app data 目錄，由桌面 app 的背景工作依 [`ScheduleStore::take_due`] 執行。
+
+use crate::analysis::AnalysisOptions;
+use crate::config::MissedSchedulePolicy;
+use crate::diagnostics::unix_now;
+use crate::domain::BoardName;
+use crate::error::Error;
+use crate::jobs::JobId;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 排程檔名稱，存放於 app data 目錄下。
+pub const SCHEDULES_FILE_NAME: &str = "schedules.json";
+/// 最短的執行間隔 (分鐘)，避免對 ptt.cc 與 pttweb.cc 造成負擔。
+pub const MIN_INTERVAL_MINUTES: u32 = 5;
+/// 每個排程保留的執行紀錄數。
+const MAX_SCHEDULE_HISTORY: usize = 20;
+
+/// 排程分析的對象。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ScheduleTarget {
+    /// 固定的一篇文章 (網址或 `#AID (看板)` 形式的文章代碼)，例如持續有人留言的熱門文章
+    Article { url: String },
+    /// 看板的最新文章，同一篇文章只分析一次
+    Board { board: BoardName },
+}
+
+/// 排程一次執行 (或錯過執行) 的結果。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "outcome")]
+pub enum ScheduleRunOutcome {
+    #[serde(rename_all = "camelCase")]
+    Completed { job_id: JobId, url: String },
+    Failed {
+        url: Option<String>,
+        code: String,
+        message: String,
+    },
+    /// 看板沒有新文章，沒有分析
+    NoNewArticle,
+    /// app 關閉期間錯過 `missed` 次，啟動時補執行
+    CaughtUp { missed: u64 },
+    /// app 關閉期間錯過 `missed` 次，依設定略過
+    Skipped { missed: u64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRun {
+    /// 記錄時間 (Unix 秒)
+    pub at: u64,
+    #[serde(flatten)]
+    pub outcome: ScheduleRunOutcome,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: u64,
+    pub target: ScheduleTarget,
+    pub interval_minutes: u32,
+    /// 分析選項；未指定時使用執行當下的設定檔與預設選項
+    #[serde(default)]
+    pub options: Option<AnalysisOptions>,
+    /// 上次執行的時間 (Unix 秒)，尚未執行過時為 `None`
+    #[serde(default)]
+    pub last_run_at: Option<u64>,
+    /// 上次分析的文章網址，看板排程以此判斷是否有新文章
+    #[serde(default)]
+    pub last_url: Option<String>,
+    /// 最近的執行紀錄，依時間由舊到新
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+impl Schedule {
+    /// 下次執行的時間 (Unix 秒)；尚未執行過時為 `None`，在下一次檢查時執行。
+    /// `interval_factor` 為間隔的倍數，安靜時段中放慢時大於 1。
+    pub fn next_run_at(&self, interval_factor: u32) -> Option<u64> {
+        let interval_secs = u64::from(self.interval_minutes) * u64::from(interval_factor) * 60;
+        self.last_run_at.map(|last| last + interval_secs)
+    }
+
+    fn is_due(&self, now: u64, interval_factor: u32) -> bool {
+        self.next_run_at(interval_factor)
+            .is_none_or(|next| now >= next)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleData {
+    next_id: u64,
+    schedules: BTreeMap<u64, Schedule>,
+}
+
+/// 所有排程，每次修改後立即寫回磁碟。
+#[derive(Default)]
+pub struct ScheduleStore {
+    path: Option<PathBuf>,
+    data: Mutex<ScheduleData>,
+}
+
+impl ScheduleStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從沒有排程開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(SCHEDULES_FILE_NAME));
+        let data = storage::load_json(path.as_deref());
+        ScheduleStore {
+            path,
+            data: Mutex::new(data),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Schedule> {
+        self.data
+            .lock()
+            .unwrap()
+            .schedules
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// 新增排程，建立後在下一次檢查時立即執行第一次。
+    pub fn create(
+        &self,
+        target: ScheduleTarget,
+        interval_minutes: u32,
+        options: Option<AnalysisOptions>,
+    ) -> Result<Schedule, Error> {
+        if interval_minutes < MIN_INTERVAL_MINUTES {
+            return Err(Error::InvalidSchedule(format!(
+                "interval must be at least {} minutes",
+                MIN_INTERVAL_MINUTES
+            )));
+        }
+        if let ScheduleTarget::Article { url } = &target {
+            crate::article_url::resolve_input(url)?;
+        }
+        self.update(|data| {
+            data.next_id += 1;
+            let schedule = Schedule {
+                id: data.next_id,
+                target,
+                interval_minutes,
+                options,
+                last_run_at: None,
+                last_url: None,
+                history: Vec::new(),
+            };
+            data.schedules.insert(schedule.id, schedule.clone());
+            Ok(schedule)
+        })
+    }
+
+    pub fn delete(&self, id: u64) -> Result<(), Error> {
+        self.update(|data| {
+            data.schedules
+                .remove(&id)
+                .map(|_| ())
+                .ok_or(Error::ScheduleNotFound(id))
+        })
+    }
+
+    /// 取出到期的排程並記錄執行時間，同一個排程在下次到期前不會再被取出。
+    /// `interval_factor` 為間隔的倍數，安靜時段中放慢時大於 1。
+    pub fn take_due(&self, interval_factor: u32) -> Vec<Schedule> {
+        let now = unix_now();
+        let mut data = self.data.lock().unwrap();
+        let due: Vec<Schedule> = data
+            .schedules
+            .values_mut()
+            .filter(|schedule| schedule.is_due(now, interval_factor))
+            .map(|schedule| {
+                schedule.last_run_at = Some(now);
+                schedule.clone()
+            })
+            .collect();
+        if let (false, Some(path)) = (due.is_empty(), &self.path) {
+            // 寫入失敗時仍執行，只是重新啟動後可能提早再執行一次
+            if let Err(e) = storage::save_json(path, &*data) {
+                tracing::warn!(error = %e, "無法儲存排程的執行時間");
+            }
+        }
+        due
+    }
+
+    /// 啟動時處理 app 關閉期間錯過的排程並記錄在執行紀錄中：依設定讓它們在第一次檢查時補執行，
+    /// 或略過並從現在起重新計算間隔。
+    pub fn catch_up(&self, policy: MissedSchedulePolicy) -> Result<(), Error> {
+        let now = unix_now();
+        self.update(|data| {
+            for schedule in data.schedules.values_mut() {
+                let Some(last) = schedule.last_run_at else {
+                    continue;
+                };
+                let interval_secs = u64::from(schedule.interval_minutes.max(1)) * 60;
+                let missed = now.saturating_sub(last) / interval_secs;
+                if missed == 0 {
+                    continue;
+                }
+                let outcome = match policy {
+                    MissedSchedulePolicy::RunImmediately => ScheduleRunOutcome::CaughtUp { missed },
+                    MissedSchedulePolicy::Skip => {
+                        schedule.last_run_at = Some(now);
+                        ScheduleRunOutcome::Skipped { missed }
+                    }
+                };
+                push_run(schedule, now, outcome);
+            }
+            Ok(())
+        })
+    }
+
+    /// 在排程的執行紀錄中加入一次結果。
+    pub fn record_run(&self, id: u64, outcome: ScheduleRunOutcome) -> Result<(), Error> {
+        self.update(|data| {
+            if let Some(schedule) = data.schedules.get_mut(&id) {
+                push_run(schedule, unix_now(), outcome);
+            }
+            Ok(())
+        })
+    }
+
+    /// 記錄排程這次分析的文章網址。
+    pub fn set_last_url(&self, id: u64, url: &str) -> Result<(), Error> {
+        self.update(|data| {
+            if let Some(schedule) = data.schedules.get_mut(&id) {
+                schedule.last_url = Some(url.to_string());
+            }
+            Ok(())
+        })
+    }
+
+    /// 修改排程並寫回磁碟；寫入失敗時還原修改並回傳錯誤。
+    fn update<T>(&self, f: impl FnOnce(&mut ScheduleData) -> Result<T, Error>) -> Result<T, Error> {
+        let mut data = self.data.lock().unwrap();
+        let previous_id = data.next_id;
+        let previous = data.schedules.clone();
+        let result = f(&mut data)?;
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*data) {
+                data.next_id = previous_id;
+                data.schedules = previous;
+                return Err(e);
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn push_run(schedule: &mut Schedule, at: u64, outcome: ScheduleRunOutcome) {
+    schedule.history.push(ScheduleRun { at, outcome });
+    let excess = schedule.history.len().saturating_sub(MAX_SCHEDULE_HISTORY);
+    schedule.history.drain(..excess);
+}
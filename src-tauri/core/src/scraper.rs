@@ -0,0 +1,1210 @@
+use crate::article_url::{self, ArticleInput, ArticleRef};
+use crate::disk_cache::DiskCache;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::fetcher::Fetcher;
+use crate::report::PttWebData;
+use crate::selectors::{self, ArticleSelectors, UserPageSelectors};
+use crate::singleflight::SingleFlight;
+use crate::sources::{self, MirrorPage, MirrorSite};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+/// 用於儲存 `scrape_ptt_article` 函式爬取結果的結構。
+#[derive(Debug)]
+pub struct PttArticleData {
+    pub user_comment_counts: HashMap<UserId, u32>,
+    /// 各使用者計入的留言依類型 (推/噓/→) 的數量
+    pub user_push_types: HashMap<UserId, PushTypeCounts>,
+    /// 各使用者計入的留言所在的樓層 (1 起算)，由小到大
+    pub user_floors: HashMap<UserId, Vec<usize>>,
+    /// 文章的推文總數 (不套用篩選條件、不合併 →)，即最後一則推文的樓層
+    pub total_floors: usize,
+    /// 各使用者每則計入的留言時間 (當年的第幾分鐘)，取不到時間的留言不列入
+    pub comment_minutes: HashMap<UserId, Vec<u32>>,
+    /// 計入統計的推文內容 (已套用篩選條件與 → 合併)
+    pub push_contents: Vec<String>,
+    /// 所有取得到時間的推文 (不套用篩選條件)，依文章中的順序排列
+    pub timed_pushes: Vec<TimedPush>,
+    /// 各使用者第一則推文距發文的分鐘數 (不套用篩選條件)，取不到發文或推文時間時不列入
+    pub first_push_latency: HashMap<UserId, u32>,
+    pub board: BoardName,
+    pub title: String,
+    pub author: Option<UserId>,
+    /// 文章內文，不含標題列、推文與簽名檔
+    pub body: String,
+    /// 發文時間 (當年的第幾分鐘)，見 [`minute_of_year`]
+    pub posted_minute: Option<u32>,
+    /// 發文時間 (RFC 3339，台灣時間)，見 [`posted_at`]
+    pub posted_at: Option<String>,
+    /// 文章中「※ 編輯:」的修改紀錄，依頁面順序排列
+    pub edits: Vec<ArticleEdit>,
+    /// 轉錄文章的來源，不是轉錄文章時為 `None`
+    pub forwarded_from: Option<ForwardedFrom>,
+    /// 解析過程中無法辨識的推文，可能導致統計有誤。
+    pub warnings: Vec<ParseWarning>,
+    pub source: ArticleSource,
+}
+
+impl PttArticleData {
+    /// 換算推文時間的起點 (見 [`minute_after`])：發文時間，取不到時為頁面上第一則推文的時間。
+    pub fn time_anchor(&self) -> Option<u32> {
+        self.posted_minute
+            .or_else(|| self.timed_pushes.first().map(|push| push.minute))
+    }
+}
+
+/// 一位使用者在文章中推、噓、→ 的數量。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PushTypeCounts {
+    pub push: u32,
+    pub hate: u32,
+    pub arrow: u32,
+}
+
+impl PushTypeCounts {
+    /// 推文佔推、噓總數的比例 (0 ~ 1)；沒有推也沒有噓時為 `None`。
+    pub fn push_ratio(&self) -> Option<f64> {
+        let total = self.push + self.hate;
+        (total > 0).then(|| self.push as f64 / total as f64)
+    }
+}
+
+/// 有時間的一則推文，用於推文時間軸。
+#[derive(Debug, Clone)]
+pub struct TimedPush {
+    /// 當年的第幾分鐘，見 [`minute_of_year`]
+    pub minute: u32,
+    /// `push`、`hate`、`arrow` 或 `unknown`
+    pub comment_type: &'static str,
+    pub user: UserId,
+}
+
+/// 文章的一筆修改紀錄，例如「※ 編輯: foo (1.2.3.4 臺灣), 01/02/2022 12:34:56」。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleEdit {
+    pub editor: UserId,
+    /// 括號中的 IP 與地區
+    pub from: Option<String>,
+    /// 修改時間 (RFC 3339，台灣時間)；舊格式沒有年份時為空
+    pub edited_at: Option<String>,
+}
+
+/// 轉錄文章的原文，取自「※ [本文轉錄自 Gossiping 看板 #1XyZabcd ]」。
+/// 只有標題的「Fw:」前綴而找不到轉錄說明時，兩個欄位皆為空。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardedFrom {
+    pub board: Option<BoardName>,
+    /// 由文章代碼換算的原文網址
+    pub url: Option<String>,
+}
+
+/// 無法辨識推文類型 (推/噓/→) 時記錄的警告，附上原始推文內容。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseWarning {
+    pub floor: usize,
+    pub user: UserId,
+    pub raw_line: String,
+}
+
+/// 篩選推文時使用的條件。
+#[derive(Debug, Default, Clone)]
+pub struct ScrapeOptions {
+    /// 要保留的推文類型 ("push" / "hate" / "arrow")，空陣列代表全部保留。
+    pub filter_types: Vec<String>,
+    /// 推文內容需包含其中任一關鍵字。
+    pub keywords: Option<Vec<String>>,
+    /// 推文內容 (去除前後空白後) 的最少字數，用來排除一字推之類的灌水留言。
+    pub min_content_length: Option<usize>,
+    /// 推文內容 (去除前後空白後) 的最多字數。
+    pub max_content_length: Option<usize>,
+    /// 將同一使用者緊接在後的 → 推文併入前一則留言，視為一則完整的留言。
+    pub merge_arrow_continuations: bool,
+    /// 只統計從第幾樓開始的推文 (1 起算，含)。
+    pub push_range_start: Option<usize>,
+    /// 只統計到第幾樓為止的推文 (1 起算，含)。
+    pub push_range_end: Option<usize>,
+}
+
+impl ScrapeOptions {
+    /// 判斷一則推文是否符合所有篩選條件。
+    fn matches(&self, push: &Push) -> bool {
+        let type_match = self.filter_types.is_empty()
+            || self.filter_types.iter().any(|t| t == push.comment_type);
+
+        let keyword_match = self
+            .keywords
+            .as_ref()
+            .is_none_or(|k_vec| k_vec.iter().any(|k| push.content.contains(k)));
+
+        type_match
+            && keyword_match
+            && self.length_matches(&push.content)
+            && self.floor_matches(push.floor)
+    }
+
+    /// 判斷推文樓層是否落在設定的範圍內。
+    fn floor_matches(&self, floor: usize) -> bool {
+        self.push_range_start.is_none_or(|start| floor >= start)
+            && self.push_range_end.is_none_or(|end| floor <= end)
+    }
+
+    /// 判斷推文內容的長度是否落在設定的範圍內。
+    fn length_matches(&self, content: &str) -> bool {
+        let length = content.trim().chars().count();
+        self.min_content_length.is_none_or(|min| length >= min)
+            && self.max_content_length.is_none_or(|max| length <= max)
+    }
+}
+
+/// 解析後的單則推文。
+#[derive(Debug)]
+struct Push {
+    /// 推文在文章中的樓層 (1 起算)，合併後的留言沿用第一行的樓層。
+    floor: usize,
+    comment_type: &'static str,
+    user: UserId,
+    content: String,
+    /// 推文時間 (當年的第幾分鐘)，見 [`minute_of_year`]
+    minute: Option<u32>,
+}
+
+/// 文章內容的來源。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArticleSource {
+    /// 原始的 www.ptt.cc 文章頁面
+    Ptt,
+    /// 原文已被刪除，改由 pttweb.cc 的文章鏡像取得
+    PttWebMirror,
+    /// 原文與 pttweb.cc 鏡像都不存在，改由 Internet Archive 的快照取得
+    WaybackMachine,
+    /// 原文與 pttweb.cc 鏡像都不存在，改用之前分析時存下的本機快照
+    CachedSnapshot,
+    /// disp.cc 的文章頁面 (頁面中找不到原文連結時)
+    DispCc,
+    /// 使用者提供的本機 HTML 檔
+    LocalFile,
+}
+
+/// Internet Archive availability API 的回應 (只取需要的欄位)。
+#[derive(Deserialize, Debug)]
+struct WaybackAvailability {
+    archived_snapshots: WaybackSnapshots,
+}
+
+#[derive(Deserialize, Debug)]
+struct WaybackSnapshots {
+    closest: Option<WaybackSnapshot>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WaybackSnapshot {
+    available: bool,
+    timestamp: String,
+}
+
+/// 鏡像站頁面對應的 selectors 與文章來源。
+fn mirror_selectors(site: MirrorSite) -> (Arc<ArticleSelectors>, ArticleSource) {
+    let selectors = selectors::current();
+    match site {
+        MirrorSite::DispCc => (Arc::clone(&selectors.dispcc), ArticleSource::DispCc),
+        // moptt 的網址一律換算回原文，不會抓取其頁面
+        MirrorSite::Moptt => (Arc::clone(&selectors.ptt), ArticleSource::Ptt),
+    }
+}
+
+/// 依輸入來源取得文章：線上文章經由 `fetcher` 抓取，本機檔案直接讀檔解析。
+pub async fn load_article(
+    input: &ArticleInput,
+    options: &ScrapeOptions,
+    snapshots: &DiskCache,
+    fetcher: &dyn Fetcher,
+) -> Result<PttArticleData, Error> {
+    match input {
+        ArticleInput::Remote(article) => {
+            scrape_ptt_article(article, options, snapshots, fetcher).await
+        }
+        ArticleInput::Mirror(page) => scrape_mirror_page(page, options, snapshots, fetcher).await,
+        ArticleInput::LocalFile(path) => parse_local_article(path, options).await,
+    }
+}
+
+/// 解析本機儲存的 PTT 文章 HTML 檔 (例如之前另存的已刪除文章)。
+pub async fn parse_local_article(
+    path: &Path,
+    options: &ScrapeOptions,
+) -> Result<PttArticleData, Error> {
+    let html = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| Error::LocalFile {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+    parse_article_blocking(
+        html,
+        &path.display().to_string(),
+        Arc::clone(&selectors::current().ptt),
+        "Unknown",
+        options,
+        ArticleSource::LocalFile,
+    )
+    .await
+}
+
+/// 抓取鏡像站的文章頁面：頁面中有原文連結時改抓原文，否則以該站的 selectors 解析。
+pub async fn scrape_mirror_page(
+    page: &MirrorPage,
+    options: &ScrapeOptions,
+    snapshots: &DiskCache,
+    fetcher: &dyn Fetcher,
+) -> Result<PttArticleData, Error> {
+    let html = fetch_article_html(&page.url, fetcher)
+        .await?
+        .ok_or_else(|| Error::ArticleNotFound(page.url.clone()))?;
+
+    let (html, canonical) = find_canonical_link_blocking(html).await?;
+    if let Some(article) = canonical {
+        tracing::info!(url = %page.url, canonical = %article.canonical_url(), "鏡像頁面附有原文連結，改抓原文");
+        return scrape_ptt_article(&article, options, snapshots, fetcher).await;
+    }
+
+    let (selectors, source) = mirror_selectors(page.site);
+    parse_article_blocking(
+        html,
+        &page.url,
+        selectors,
+        page.board.as_deref().unwrap_or("Unknown"),
+        options,
+        source,
+    )
+    .await
+}
+
+/// 在 blocking 執行緒上尋找鏡像頁面中的原文連結；鏡像頁面與原文同樣龐大，
+/// 解析不能卡住 tokio worker。HTML 原樣交還，沒有原文連結時接著解析推文。
+async fn find_canonical_link_blocking(html: String) -> Result<(String, Option<ArticleRef>), Error> {
+    tokio::task::spawn_blocking(move || {
+        let canonical = sources::find_canonical_link(&html);
+        (html, canonical)
+    })
+    .await
+    .map_err(|e| Error::Internal(e.to_string()))
+}
+
+/// (對應 TS: scrapePttArticle) 爬取指定 PTT 文章，篩選並統計留言者。
+/// 原文回應 404 或只剩刪除公告時，改從 pttweb.cc 的文章鏡像解析推文。
+/// 成功取得的原文會壓縮存成本機快照，之後原文被刪除時仍可分析。
+pub async fn scrape_ptt_article(
+    article: &ArticleRef,
+    options: &ScrapeOptions,
+    snapshots: &DiskCache,
+    fetcher: &dyn Fetcher,
+) -> Result<PttArticleData, Error> {
+    let url = article.canonical_url();
+    let snapshot_key = format!("articles/{}/{}.html", article.board, article.article_id);
+    // 原文只剩刪除公告時，所有來源都找不到才回報 ArticleDeleted
+    let mut deleted = false;
+    if let Some(html) = fetch_article_html(&url, fetcher).await? {
+        // 刪除公告不能存成快照，否則會蓋掉之前保存的原文；可能是公告時先留一份，解析成功再存
+        let unsaved = if may_be_deletion_notice(&html) {
+            Some(html.clone())
+        } else {
+            snapshots.put(&snapshot_key, html.as_bytes());
+            None
+        };
+        match parse_article_blocking(
+            html,
+            &url,
+            Arc::clone(&selectors::current().ptt),
+            &article.board,
+            options,
+            ArticleSource::Ptt,
+        )
+        .await
+        {
+            Err(Error::ArticleDeleted(_)) => deleted = true,
+            result => {
+                if let (Ok(_), Some(html)) = (&result, unsaved) {
+                    snapshots.put(&snapshot_key, html.as_bytes());
+                }
+                return result;
+            }
+        }
+    }
+
+    tracing::info!(url = %url, deleted, "原文已不存在，改用 pttweb.cc 的文章鏡像");
+    let mirror_url = article.pttweb_mirror_url();
+    if let Some(html) = fetch_article_html(&mirror_url, fetcher).await? {
+        match parse_article_blocking(
+            html,
+            &mirror_url,
+            Arc::clone(&selectors::current().pttweb),
+            &article.board,
+            options,
+            ArticleSource::PttWebMirror,
+        )
+        .await
+        {
+            Err(Error::ArticleDeleted(_)) => {}
+            result => return result,
+        }
+    }
+
+    if let Some(html) = snapshots
+        .get(&snapshot_key)
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+    {
+        tracing::info!(url = %url, "pttweb.cc 鏡像也不存在，改用本機快照");
+        return parse_article_blocking(
+            html,
+            &url,
+            Arc::clone(&selectors::current().ptt),
+            &article.board,
+            options,
+            ArticleSource::CachedSnapshot,
+        )
+        .await;
+    }
+
+    tracing::info!(url = %url, "pttweb.cc 鏡像也不存在，改查 Internet Archive 快照");
+    let not_found = |url: String| {
+        if deleted {
+            Error::ArticleDeleted(url)
+        } else {
+            Error::ArticleNotFound(url)
+        }
+    };
+    let Some(snapshot_url) = find_wayback_snapshot(&url, fetcher).await? else {
+        return Err(not_found(url));
+    };
+    let Some(html) = fetch_article_html(&snapshot_url, fetcher).await? else {
+        return Err(not_found(url));
+    };
+    parse_article_blocking(
+        html,
+        &url,
+        Arc::clone(&selectors::current().ptt),
+        &article.board,
+        options,
+        ArticleSource::WaybackMachine,
+    )
+    .await
+}
+
+/// 文章列表顯示「爆」時的推文數。
+pub const EXPLODED_PUSH_COUNT: i32 = 100;
+
+/// 看板文章列表中的一篇文章。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardIndexEntry {
+    pub url: String,
+    pub title: String,
+    /// 推文數減噓文數：「爆」為 [`EXPLODED_PUSH_COUNT`]，`X1` ~ `X9` 為 -10 ~ -90，`XX` 為 -100
+    pub push_count: i32,
+}
+
+/// 看板文章列表第一頁 (最新的文章) 中的非置底文章，依頁面順序 (舊到新) 排列；
+/// 已刪除的文章不列入，看板不存在時為空。
+pub async fn board_index(
+    board: &BoardName,
+    fetcher: &dyn Fetcher,
+) -> Result<Vec<BoardIndexEntry>, Error> {
+    let index_url = format!("https://www.ptt.cc/bbs/{}/index.html", board);
+    let Some(html) = fetch_article_html(&index_url, fetcher).await? else {
+        return Ok(Vec::new());
+    };
+    let selectors = Arc::clone(&selectors::current().ptt_board);
+    let document = Html::parse_document(&html);
+    let mut entries = Vec::new();
+    for element in document.select(&selectors.entry) {
+        if element.value().has_class(
+            &selectors.separator_class,
+            scraper::CaseSensitivity::AsciiCaseInsensitive,
+        ) {
+            break;
+        }
+        let Some(link) = element.select(&selectors.link).next() else {
+            continue;
+        };
+        let Some(href) = link.value().attr("href") else {
+            continue;
+        };
+        let push_count = element
+            .select(&selectors.push_count)
+            .next()
+            .map(|nrec| parse_push_count(&nrec.text().collect::<String>()))
+            .unwrap_or(0);
+        entries.push(BoardIndexEntry {
+            url: format!("https://www.ptt.cc{}", href),
+            title: link.text().collect::<String>().trim().to_string(),
+            push_count,
+        });
+    }
+    Ok(entries)
+}
+
+/// 解析文章列表的推文數欄位，空白或無法辨識時為 0。
+fn parse_push_count(text: &str) -> i32 {
+    match text.trim() {
+        "爆" => EXPLODED_PUSH_COUNT,
+        "XX" => -EXPLODED_PUSH_COUNT,
+        text => match text.strip_prefix('X') {
+            Some(tens) => tens.parse::<i32>().map(|n| -10 * n).unwrap_or(0),
+            None => text.parse().unwrap_or(0),
+        },
+    }
+}
+
+/// 看板文章列表第一頁中最新一篇非置底文章的網址；看板不存在或沒有文章時為 `None`。
+pub async fn latest_article_url(
+    board: &BoardName,
+    fetcher: &dyn Fetcher,
+) -> Result<Option<String>, Error> {
+    Ok(board_index(board, fetcher)
+        .await?
+        .pop()
+        .map(|entry| entry.url))
+}
+
+/// 向 Internet Archive 查詢最接近的快照，回傳不含 Wayback 工具列的原始頁面網址。
+async fn find_wayback_snapshot(url: &str, fetcher: &dyn Fetcher) -> Result<Option<String>, Error> {
+    let api_url =
+        reqwest::Url::parse_with_params("https://archive.org/wayback/available", &[("url", url)])
+            .map_err(|e| Error::Internal(e.to_string()))?;
+    let response = fetcher.get(api_url.as_str()).await?;
+    if response.is_not_found() {
+        return Ok(None);
+    }
+    let availability: WaybackAvailability = serde_json::from_str(&response.body)
+        .map_err(|e| Error::Internal(format!("invalid wayback response: {}", e)))?;
+
+    Ok(availability
+        .archived_snapshots
+        .closest
+        .filter(|snapshot| snapshot.available)
+        // `id_` 讓 Wayback 回傳原始 HTML，不改寫連結也不插入工具列
+        .map(|snapshot| {
+            format!(
+                "https://web.archive.org/web/{}id_/{}",
+                snapshot.timestamp, url
+            )
+        }))
+}
+
+/// 取得文章頁面的 HTML，頁面不存在 (404) 時回傳 `None`。
+async fn fetch_article_html(url: &str, fetcher: &dyn Fetcher) -> Result<Option<String>, Error> {
+    let response = fetcher.get(url).await?;
+    if response.is_not_found() {
+        return Ok(None);
+    }
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .unwrap_or_default();
+    response.check_rate_limited(&host)?;
+    Ok(Some(response.body))
+}
+
+/// 文章被刪除後頁面上只剩的公告文字。
+const DELETION_NOTICES: [&str; 2] = ["已被刪除", "404 - Not Found"];
+
+/// 頁面中有刪除公告的文字；還需要解析後確認沒有標題列與推文才算是刪除公告頁面。
+fn may_be_deletion_notice(html: &str) -> bool {
+    DELETION_NOTICES.iter().any(|notice| html.contains(notice))
+}
+
+/// 從文章頁面取出的最少資料。大型文章 (例如上千則推文的八卦板文章) 的 DOM 相當龐大，
+/// 先取出需要的文字再釋放整份 DOM，避免在統計與後續查詢期間一直佔用記憶體。
+struct ExtractedArticle {
+    /// 頁面只有刪除公告 (沒有標題列也沒有推文)
+    deleted: bool,
+    title: String,
+    board: Option<String>,
+    author: Option<UserId>,
+    body: String,
+    posted_minute: Option<u32>,
+    posted_at: Option<String>,
+    edits: Vec<ArticleEdit>,
+    forwarded_from: Option<ForwardedFrom>,
+    pushes: Vec<Push>,
+    warnings: Vec<ParseWarning>,
+}
+
+/// 在 blocking 執行緒上解析文章頁面，
+/// 避免大型頁面的解析卡住同時負責網路請求與事件發送的 tokio worker。
+/// 頁面只有刪除公告時回傳 [`Error::ArticleDeleted`]，錯誤中附上 `url`。
+async fn parse_article_blocking(
+    html: String,
+    url: &str,
+    selectors: Arc<ArticleSelectors>,
+    fallback_board: &str,
+    options: &ScrapeOptions,
+    source: ArticleSource,
+) -> Result<PttArticleData, Error> {
+    let fallback_board = fallback_board.to_string();
+    let options = options.clone();
+    // span 在呼叫端建立，才會掛在 fetch span 底下
+    let span = tracing::info_span!("parse", bytes = html.len());
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| parse_article(&html, &selectors, &fallback_board, &options, source))
+    })
+    .await
+    .map_err(|e| Error::Internal(e.to_string()))?
+    .ok_or_else(|| Error::ArticleDeleted(url.to_string()))
+}
+
+/// 依指定來源的 selectors 解析文章頁面，篩選並統計留言者；頁面只有刪除公告時為 `None`。
+fn parse_article(
+    html: &str,
+    selectors: &ArticleSelectors,
+    fallback_board: &str,
+    options: &ScrapeOptions,
+    source: ArticleSource,
+) -> Option<PttArticleData> {
+    let ExtractedArticle {
+        deleted,
+        title,
+        board,
+        author,
+        body,
+        posted_minute,
+        posted_at,
+        edits,
+        forwarded_from,
+        pushes,
+        warnings,
+    } = extract_article(html, selectors);
+    if deleted {
+        return None;
+    }
+
+    let mut first_push_latency: HashMap<UserId, u32> = HashMap::new();
+    if let Some(posted) = posted_minute {
+        for push in &pushes {
+            // 推文時間早於發文時間時視為跨年後的推文
+            let Some(latency) = push
+                .minute
+                .map(|minute| minute_after(posted, minute) - posted)
+            else {
+                continue;
+            };
+            let first = first_push_latency
+                .entry(push.user.clone())
+                .or_insert(latency);
+            *first = (*first).min(latency);
+        }
+    }
+
+    let total_floors = pushes.last().map_or(0, |push| push.floor);
+    let timed_pushes = pushes
+        .iter()
+        .filter_map(|push| {
+            Some(TimedPush {
+                minute: push.minute?,
+                comment_type: push.comment_type,
+                user: push.user.clone(),
+            })
+        })
+        .collect();
+
+    // 同一使用者連續以 → 接續的推文視為同一則留言
+    let mut merged: Vec<Push> = Vec::with_capacity(pushes.len());
+    for push in pushes {
+        if options.merge_arrow_continuations && push.comment_type == "arrow" {
+            if let Some(last) = merged.last_mut().filter(|last| last.user == push.user) {
+                last.content.push_str(&push.content);
+                continue;
+            }
+        }
+        merged.push(push);
+    }
+
+    // 留言者數不會超過留言數，預先配置以免大型文章在統計時反覆擴充
+    let capacity = merged.len();
+    let mut user_comment_counts = HashMap::with_capacity(capacity);
+    let mut comment_minutes: HashMap<UserId, Vec<u32>> = HashMap::with_capacity(capacity);
+    let mut user_push_types: HashMap<UserId, PushTypeCounts> = HashMap::with_capacity(capacity);
+    let mut user_floors: HashMap<UserId, Vec<usize>> = HashMap::with_capacity(capacity);
+    let mut push_contents = Vec::with_capacity(capacity);
+    for push in merged.into_iter().filter(|push| options.matches(push)) {
+        user_floors
+            .entry(push.user.clone())
+            .or_default()
+            .push(push.floor);
+        let types = user_push_types.entry(push.user.clone()).or_default();
+        match push.comment_type {
+            "push" => types.push += 1,
+            "hate" => types.hate += 1,
+            "arrow" => types.arrow += 1,
+            _ => {}
+        }
+        if let Some(minute) = push.minute {
+            comment_minutes
+                .entry(push.user.clone())
+                .or_default()
+                .push(minute);
+        }
+        *user_comment_counts.entry(push.user).or_insert(0) += 1;
+        push_contents.push(push.content);
+    }
+
+    Some(PttArticleData {
+        user_comment_counts,
+        user_push_types,
+        user_floors,
+        total_floors,
+        comment_minutes,
+        push_contents,
+        timed_pushes,
+        first_push_latency,
+        // 看板名稱取不到時，以網址中的看板為準
+        board: BoardName::new(board.unwrap_or_else(|| fallback_board.to_string())),
+        title,
+        author,
+        body,
+        posted_minute,
+        posted_at,
+        edits,
+        forwarded_from,
+        warnings,
+        source,
+    })
+}
+
+/// 解析 HTML 並只取出標題、看板、作者、內文、發文時間、修改紀錄、轉錄來源與推文文字，函式結束時 DOM 即被釋放。
+fn extract_article(html: &str, selectors: &ArticleSelectors) -> ExtractedArticle {
+    let document = Html::parse_document(html);
+
+    let has_title = document
+        .select(&selectors.title)
+        .nth(selectors.title_index)
+        .is_some();
+    let title = document
+        .select(&selectors.title)
+        .nth(selectors.title_index)
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            // 沒有 metaline 的頁面 (例如新版網頁介面) 改用 og:title
+            document
+                .select(&Selector::parse(r#"meta[property="og:title"]"#).unwrap())
+                .next()
+                .and_then(|el| el.value().attr("content"))
+                .map(|content| content.trim().to_string())
+                .filter(|s| !s.is_empty())
+        })
+        .unwrap_or_else(|| {
+            document
+                .select(&Selector::parse("title").unwrap())
+                .next()
+                .map(|t| t.text().collect::<String>().trim().to_string())
+                .unwrap_or_else(|| "未知標題".to_string())
+        });
+
+    let board = document
+        .select(&selectors.board)
+        .next()
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    // 作者欄位通常附帶暱稱，例如「foo (暱稱)」，只取帳號
+    let author = document.select(&selectors.author).next().and_then(|el| {
+        el.text()
+            .collect::<String>()
+            .split_whitespace()
+            .next()
+            .map(UserId::new)
+    });
+    let body = document
+        .select(&selectors.body)
+        .next()
+        .map(|el| article_body(el, &selectors.body_exclude))
+        .unwrap_or_default();
+    // 時間欄位在標題之後，取最後一個以免標題中的日期被誤認
+    let post_times: Vec<String> = document
+        .select(&selectors.post_time)
+        .map(|el| el.text().collect())
+        .collect();
+    let posted_minute = post_times
+        .iter()
+        .filter_map(|text| post_minute_of_year(text))
+        .last();
+    let posted_at = post_times.iter().filter_map(|text| posted_at(text)).last();
+    let edits = document
+        .select(&selectors.edit_notice)
+        .flat_map(|el| parse_edits(&el.text().collect::<String>()))
+        .collect();
+    let forwarded_from = document
+        .select(&selectors.forward_notice)
+        .find_map(|el| parse_forward_notice(&el.text().collect::<String>()))
+        .or_else(|| {
+            FORWARDED_TITLE_RE
+                .is_match(&title)
+                .then_some(ForwardedFrom {
+                    board: None,
+                    url: None,
+                })
+        });
+
+    // 上千則推文的文章中這段迴圈佔解析時間的大半：各欄位的文字盡量直接借用 DOM 中的字串，
+    // 每則推文只為帳號與內容各配置一次
+    let mut pushes: Vec<Push> = Vec::new();
+    let mut warnings: Vec<ParseWarning> = Vec::new();
+    let mut floor = 0;
+    for element in document.select(&selectors.push) {
+        let user = element
+            .select(&selectors.user)
+            .next()
+            .map(element_text)
+            .unwrap_or_default();
+        let content_raw = element
+            .select(&selectors.content)
+            .next()
+            .map(element_text)
+            .unwrap_or_default();
+        if user.trim().is_empty() || content_raw.is_empty() {
+            continue;
+        }
+        floor += 1;
+
+        let minute = element
+            .select(&selectors.time)
+            .next()
+            .and_then(|t| minute_of_year(&element_text(t)));
+        let user = UserId::new(user.trim());
+        let content = content_raw
+            .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+            .to_string();
+
+        let tag = element.select(&selectors.tag).next();
+        let tag_contains = |c: char| tag.is_some_and(|t| t.text().any(|s| s.contains(c)));
+        let comment_type = if tag_contains('推') {
+            "push"
+        } else if tag_contains('噓') {
+            "hate"
+        } else if tag_contains('→') {
+            "arrow"
+        } else {
+            warnings.push(ParseWarning {
+                floor,
+                user: user.clone(),
+                raw_line: element.text().collect::<String>().trim().to_string(),
+            });
+            "unknown"
+        };
+
+        pushes.push(Push {
+            floor,
+            comment_type,
+            user,
+            content,
+            minute,
+        });
+    }
+    pushes.shrink_to_fit();
+    let deleted = !has_title
+        && pushes.is_empty()
+        && document
+            .root_element()
+            .text()
+            .any(|text| DELETION_NOTICES.iter().any(|notice| text.contains(notice)));
+
+    ExtractedArticle {
+        deleted,
+        title,
+        board,
+        author,
+        body,
+        posted_minute,
+        posted_at,
+        edits,
+        forwarded_from,
+        pushes,
+        warnings,
+    }
+}
+
+/// 元素的文字；只有一個文字節點時 (推文各欄位的常見情況) 直接借用，不另外配置字串。
+fn element_text(element: ElementRef<'_>) -> Cow<'_, str> {
+    let mut texts = element.text();
+    let first = texts.next().unwrap_or_default();
+    match texts.next() {
+        None => Cow::Borrowed(first),
+        Some(second) => {
+            let mut text = String::from(first);
+            text.push_str(second);
+            text.extend(texts);
+            Cow::Owned(text)
+        }
+    }
+}
+
+/// 取出 `body` 中不在 `exclude` 元素內的文字，並去掉結尾的簽名檔分隔線 `--`。
+fn article_body(body: ElementRef, exclude: &Selector) -> String {
+    let text: String = body
+        .descendants()
+        .filter_map(|node| {
+            let text: &str = node.value().as_text()?;
+            let excluded = node
+                .ancestors()
+                .take_while(|ancestor| ancestor.id() != body.id())
+                .filter_map(ElementRef::wrap)
+                .any(|el| exclude.matches(&el));
+            (!excluded).then_some(text)
+        })
+        .collect();
+    let text = text.trim_end();
+    text.strip_suffix("--")
+        .filter(|rest| rest.is_empty() || rest.ends_with('\n'))
+        .unwrap_or(text)
+        .trim()
+        .to_string()
+}
+
+static PUSH_TIME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d{1,2})[/-](\d{1,2})\s+(\d{1,2}):(\d{2})").unwrap());
+
+/// 將推文時間 (`MM/DD HH:MM`，前面可能有 IP 或年份) 換算為當年的第幾分鐘，以每月 31 天概算；
+/// 只用於比較同一篇文章中推文的先後與間隔。
+pub fn minute_of_year(text: &str) -> Option<u32> {
+    let caps = PUSH_TIME_RE.captures(text)?;
+    let field = |i: usize| caps.get(i)?.as_str().parse::<u32>().ok();
+    let (month, day, hour, minute) = (field(1)?, field(2)?, field(3)?, field(4)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    Some((((month - 1) * 31 + (day - 1)) * 24 + hour) * 60 + minute)
+}
+
+/// [`minute_of_year`] 一整年的分鐘數 (同樣以每月 31 天概算)。
+pub const MINUTES_PER_YEAR: u32 = 12 * 31 * 24 * 60;
+
+/// 以 `anchor` (通常是發文時間) 為起點換算推文時間，讓跨年文章的推文仍可直接相減：
+/// 比起點早的時間視為隔年，加上一年的分鐘數。
+pub fn minute_after(anchor: u32, minute: u32) -> u32 {
+    if minute < anchor {
+        minute + MINUTES_PER_YEAR
+    } else {
+        minute
+    }
+}
+
+static POST_TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(\d{1,2})\s+(\d{1,2}):(\d{2})")
+        .unwrap()
+});
+
+/// 將發文時間換算為當年的第幾分鐘，與 [`minute_of_year`] 的推文時間可直接相減。
+/// ptt.cc 的格式為 `Sun Jan  2 12:34:56 2022`，其他來源為 `2022/01/02 12:34` 等數字格式。
+fn post_minute_of_year(text: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let Some(caps) = POST_TIME_RE.captures(text) else {
+        return minute_of_year(text);
+    };
+    let month = MONTHS.iter().position(|m| *m == &caps[1])? + 1;
+    minute_of_year(&format!("{}/{} {}:{}", month, &caps[2], &caps[3], &caps[4]))
+}
+
+static POST_DATETIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(\d{1,2})\s+(\d{1,2}):(\d{2}):(\d{2})\s+(\d{4})",
+    )
+    .unwrap()
+});
+
+static NUMERIC_DATETIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d{4})[/-](\d{1,2})[/-](\d{1,2})\s+(\d{1,2}):(\d{2})(?::(\d{2}))?").unwrap()
+});
+
+/// 將發文時間轉為 RFC 3339 格式 (例如 `2022-01-02T12:34:56+08:00`)；沒有年份的格式無法轉換。
+pub fn posted_at(text: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let number = |m: Option<regex::Match>| m?.as_str().parse::<u32>().ok();
+    let (year, month, day, hour, minute, second) =
+        if let Some(caps) = POST_DATETIME_RE.captures(text) {
+            let month = MONTHS.iter().position(|m| *m == &caps[1])? as u32 + 1;
+            (
+                number(caps.get(6))?,
+                month,
+                number(caps.get(2))?,
+                number(caps.get(3))?,
+                number(caps.get(4))?,
+                number(caps.get(5))?,
+            )
+        } else {
+            let caps = NUMERIC_DATETIME_RE.captures(text)?;
+            (
+                number(caps.get(1))?,
+                number(caps.get(2))?,
+                number(caps.get(3))?,
+                number(caps.get(4))?,
+                number(caps.get(5))?,
+                number(caps.get(6)).unwrap_or(0),
+            )
+        };
+    taiwan_rfc3339(year, month, day, hour, minute, second)
+}
+
+/// 頁面上的時間皆為台灣時間，直接加上 +08:00；欄位超出範圍時為 `None`。
+fn taiwan_rfc3339(
+    year: u32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> Option<String> {
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+08:00",
+        year, month, day, hour, minute, second
+    ))
+}
+
+static EDIT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"※ 編輯:\s*([A-Za-z0-9_]+)(?:\s*\(([^)]*)\))?(?:,\s*(\d{1,2})/(\d{1,2})/(\d{4})\s+(\d{1,2}):(\d{2})(?::(\d{2}))?)?",
+    )
+    .unwrap()
+});
+
+/// 取出文字中所有「※ 編輯:」修改紀錄。
+fn parse_edits(text: &str) -> Vec<ArticleEdit> {
+    let number = |m: Option<regex::Match>| m?.as_str().parse::<u32>().ok();
+    // 日期格式為 MM/DD/YYYY
+    let edited_at = |caps: &regex::Captures| {
+        taiwan_rfc3339(
+            number(caps.get(5))?,
+            number(caps.get(3))?,
+            number(caps.get(4))?,
+            number(caps.get(6))?,
+            number(caps.get(7))?,
+            number(caps.get(8)).unwrap_or(0),
+        )
+    };
+    EDIT_RE
+        .captures_iter(text)
+        .map(|caps| ArticleEdit {
+            editor: UserId::new(&caps[1]),
+            from: caps.get(2).map(|m| m.as_str().trim().to_string()),
+            edited_at: edited_at(&caps),
+        })
+        .collect()
+}
+
+static FORWARD_NOTICE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"※ \[本文轉錄自\s*([A-Za-z0-9_\-]+)\s*看板(?:\s*#([0-9A-Za-z\-_]{8}))?\s*\]")
+        .unwrap()
+});
+
+static FORWARDED_TITLE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^\s*fw\s*:").unwrap());
+
+/// 取出「※ [本文轉錄自 … 看板 #AID ]」中的原文看板，並以文章代碼換算原文網址。
+fn parse_forward_notice(text: &str) -> Option<ForwardedFrom> {
+    let caps = FORWARD_NOTICE_RE.captures(text)?;
+    let board = caps[1].to_string();
+    let url = caps
+        .get(2)
+        .and_then(|aid| article_url::aid_to_article_id(aid.as_str()))
+        .map(|article_id| {
+            ArticleRef {
+                board: board.clone(),
+                article_id,
+            }
+            .canonical_url()
+        });
+    Some(ForwardedFrom {
+        board: Some(BoardName::new(board)),
+        url,
+    })
+}
+
+/// [`minute_of_year`] 的反向換算，格式為 `MM/DD HH:MM`；[`minute_after`] 換算到隔年的時間同樣適用。
+pub fn format_minute_of_year(minute_of_year: u32) -> String {
+    let minute_of_year = minute_of_year % MINUTES_PER_YEAR;
+    let (days, minutes) = (minute_of_year / (24 * 60), minute_of_year % (24 * 60));
+    format!(
+        "{:02}/{:02} {:02}:{:02}",
+        days / 31 + 1,
+        days % 31 + 1,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+static TOTAL_COMMENTS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r", 共(\d+)則").unwrap());
+
+/// 同一位使用者進行中的 pttweb.cc 查詢，放在 Tauri managed state 中由所有分析共用。
+pub type UserPageLookups = SingleFlight<UserPage>;
+
+/// 查詢使用者在指定看板的留言數；其他分析正在查詢同一位使用者時，等待並共用該次結果。
+pub async fn scrape_ptt_web(
+    user_id: &UserId,
+    target_boards: &[BoardName],
+    lookups: &UserPageLookups,
+    fetcher: &dyn Fetcher,
+) -> Result<PttWebData, Error> {
+    let page = lookups
+        .run(&user_id.normalized(), || fetch_user_page(user_id, fetcher))
+        .await?;
+
+    if page.not_found {
+        return Err(Error::PttWebUserNotFound(user_id.to_string()));
+    }
+    let total_comments = page.total_comments.ok_or_else(|| Error::PttWebParse {
+        user_id: user_id.to_string(),
+        field: "totalComments",
+    })?;
+    let all_board_comments = page.board_counts.iter().cloned().collect();
+    // 看板名稱不分大小寫比對，結果沿用呼叫端指定的寫法
+    let board_comments = page
+        .board_counts
+        .into_iter()
+        .filter_map(|(board_name, count)| {
+            target_boards
+                .iter()
+                .find(|target| **target == board_name)
+                .map(|target| (target.clone(), count))
+        })
+        .collect();
+
+    Ok(PttWebData {
+        board_comments,
+        total_comments,
+        all_board_comments,
+    })
+}
+
+/// 下載並解析 pttweb.cc 的使用者頁面。
+#[tracing::instrument(name = "pttweb_lookup", skip_all, fields(user = %user_id))]
+async fn fetch_user_page(user_id: &UserId, fetcher: &dyn Fetcher) -> Result<UserPage, Error> {
+    // (新增) 在每次請求前加入 250 毫秒的延遲
+    sleep(Duration::from_millis(250)).await;
+
+    let url = format!("https://www.pttweb.cc/user/{}?t=message", user_id);
+    tracing::debug!(user = %user_id, "查詢 pttweb.cc");
+
+    let response = fetcher.get(&url).await?;
+    response.check_rate_limited("www.pttweb.cc")?;
+    if response.is_not_found() {
+        return Ok(UserPage {
+            not_found: true,
+            total_comments: None,
+            board_counts: Vec::new(),
+        });
+    }
+    let html = response.body;
+    let selectors = Arc::clone(&selectors::current().pttweb_user);
+    tokio::task::spawn_blocking(move || parse_user_page(&html, &selectors))
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))
+}
+
+/// pttweb.cc 使用者頁面中取出的原始資料，看板篩選交由呼叫端處理。
+#[derive(Debug, Clone)]
+pub struct UserPage {
+    pub(crate) not_found: bool,
+    pub(crate) total_comments: Option<u32>,
+    pub(crate) board_counts: Vec<(BoardName, u32)>,
+}
+
+/// 解析 pttweb.cc 的使用者頁面 (在 blocking 執行緒上執行)。
+pub(crate) fn parse_user_page(html: &str, selectors: &UserPageSelectors) -> UserPage {
+    let document = Html::parse_document(html);
+
+    let not_found = document
+        .select(&selectors.title)
+        .next()
+        .is_some_and(|title_element| {
+            title_element
+                .text()
+                .collect::<String>()
+                .contains("沒有此作者")
+        });
+
+    let headline_text = document
+        .select(&selectors.headline)
+        .next()
+        .map(|el| el.text().collect::<String>());
+
+    let total_comments = headline_text
+        .as_ref()
+        .and_then(|text| TOTAL_COMMENTS_RE.captures(text))
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<u32>().ok());
+
+    let mut board_counts = Vec::new();
+
+    for element in document.select(&selectors.board_item) {
+        if let Some(name_el) = element.select(&selectors.board_name).next() {
+            let board_name = BoardName::new(name_el.text().collect::<String>());
+
+            if let Some(count_el) = element.select(&selectors.board_count).next() {
+                let count_str = count_el.text().collect::<String>();
+                if let Ok(count) = count_str.trim().parse::<u32>() {
+                    board_counts.push((board_name, count));
+                }
+            }
+        }
+    }
+
+    UserPage {
+        not_found,
+        total_comments,
+        board_counts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_push_latency_counts_pushes_after_new_year() {
+        let html = r#"<html><body><div id="main-content">
+<div class="article-metaline"><span class="article-meta-tag">作者</span><span class="article-meta-value">alice (Alice)</span></div>
+<div class="article-metaline-right"><span class="article-meta-tag">看板</span><span class="article-meta-value">Test</span></div>
+<div class="article-metaline"><span class="article-meta-tag">標題</span><span class="article-meta-value">[問卦] 跨年</span></div>
+<div class="article-metaline"><span class="article-meta-tag">時間</span><span class="article-meta-value">Fri Dec 31 23:58:00 2021</span></div>
+內文
+<div class="push"><span class="hl push-tag">推 </span><span class="f3 hl push-userid">bob</span><span class="f3 push-content">: 新年快樂</span><span class="push-ipdatetime"> 1.2.3.4 01/01 00:01
+</span></div></div></body></html>"#;
+
+        let article = parse_article(
+            html,
+            &selectors::current().ptt,
+            "Test",
+            &ScrapeOptions::default(),
+            ArticleSource::Ptt,
+        )
+        .unwrap();
+
+        assert_eq!(
+            article.first_push_latency.get(&UserId::new("bob")),
+            Some(&3)
+        );
+    }
+}
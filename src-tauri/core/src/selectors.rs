@@ -0,0 +1,229 @@
+use crate::error::Error;
+use once_cell::sync::Lazy;
+use scraper::Selector;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// 覆寫用的定義檔名稱，存放於 app data 目錄下。
+pub const SELECTORS_FILE_NAME: &str = "selectors.toml";
+/// 內建的預設定義，覆寫檔中未列出的欄位沿用此值。
+const DEFAULT_SELECTORS: &str = include_str!("../selectors.toml");
+
+/// 解析文章頁面所需的 CSS selectors，不同來源的頁面結構不同。
+pub struct ArticleSelectors {
+    pub title: Selector,
+    /// 標題是第幾個符合 `title` 的元素
+    pub title_index: usize,
+    pub board: Selector,
+    pub push: Selector,
+    pub tag: Selector,
+    pub user: Selector,
+    pub content: Selector,
+    pub time: Selector,
+    pub author: Selector,
+    pub post_time: Selector,
+    pub body: Selector,
+    /// `body` 中不屬於內文的元素
+    pub body_exclude: Selector,
+    pub edit_notice: Selector,
+    pub forward_notice: Selector,
+}
+
+/// 解析 ptt.cc 看板文章列表所需的 CSS selectors。
+pub struct BoardIndexSelectors {
+    pub entry: Selector,
+    pub link: Selector,
+    pub push_count: Selector,
+    /// 置底文分隔線的 class，`entry` 選到此元素時停止
+    pub separator_class: String,
+}
+
+/// 解析 pttweb.cc 使用者頁面所需的 CSS selectors。
+pub struct UserPageSelectors {
+    pub title: Selector,
+    pub headline: Selector,
+    pub board_item: Selector,
+    pub board_name: Selector,
+    pub board_count: Selector,
+    pub comment_item: Selector,
+    pub article_item: Selector,
+    pub entry_board: Selector,
+    pub entry_link: Selector,
+    pub entry_content: Selector,
+    pub entry_tag: Selector,
+    pub entry_time: Selector,
+}
+
+/// 所有網站的 selectors；以 `Arc` 保存，解析時不必複製。
+pub struct SelectorSet {
+    pub ptt: Arc<ArticleSelectors>,
+    pub pttweb: Arc<ArticleSelectors>,
+    pub dispcc: Arc<ArticleSelectors>,
+    pub ptt_board: Arc<BoardIndexSelectors>,
+    pub pttweb_user: Arc<UserPageSelectors>,
+}
+
+#[derive(Deserialize)]
+struct ArticleSelectorsDef {
+    title: String,
+    title_index: usize,
+    board: String,
+    push: String,
+    tag: String,
+    user: String,
+    content: String,
+    time: String,
+    author: String,
+    post_time: String,
+    body: String,
+    body_exclude: String,
+    edit_notice: String,
+    forward_notice: String,
+}
+
+#[derive(Deserialize)]
+struct BoardIndexSelectorsDef {
+    entry: String,
+    link: String,
+    push_count: String,
+    separator_class: String,
+}
+
+#[derive(Deserialize)]
+struct UserPageSelectorsDef {
+    title: String,
+    headline: String,
+    board_item: String,
+    board_name: String,
+    board_count: String,
+    comment_item: String,
+    article_item: String,
+    entry_board: String,
+    entry_link: String,
+    entry_content: String,
+    entry_tag: String,
+    entry_time: String,
+}
+
+#[derive(Deserialize)]
+struct SelectorsDef {
+    ptt: ArticleSelectorsDef,
+    pttweb: ArticleSelectorsDef,
+    dispcc: ArticleSelectorsDef,
+    ptt_board: BoardIndexSelectorsDef,
+    pttweb_user: UserPageSelectorsDef,
+}
+
+/// 目前使用中的 selectors，啟動時或使用者要求時以覆寫檔重新載入。
+static ACTIVE: Lazy<RwLock<Arc<SelectorSet>>> = Lazy::new(|| {
+    let defaults = parse_definitions(None).expect("內建的 selectors.toml 必須有效");
+    RwLock::new(Arc::new(defaults))
+});
+
+/// 取得目前使用中的 selectors。
+pub fn current() -> Arc<SelectorSet> {
+    Arc::clone(&ACTIVE.read().unwrap())
+}
+
+/// 以 `path` 的覆寫檔 (不存在時為內建預設值) 重新載入 selectors。
+/// 覆寫檔格式錯誤時回傳錯誤，並繼續使用原本的 selectors。
+pub fn reload(path: Option<&Path>) -> Result<(), Error> {
+    let overrides = match path {
+        Some(path) if path.exists() => Some(
+            std::fs::read_to_string(path)
+                .map_err(|e| Error::InvalidSelectors(format!("{}: {}", path.display(), e)))?,
+        ),
+        _ => None,
+    };
+    let selectors = parse_definitions(overrides.as_deref())?;
+    *ACTIVE.write().unwrap() = Arc::new(selectors);
+    tracing::info!(overridden = overrides.is_some(), "已載入 selectors 定義");
+    Ok(())
+}
+
+/// 將覆寫內容合併到內建預設值之上，再編譯所有 selectors。
+fn parse_definitions(overrides: Option<&str>) -> Result<SelectorSet, Error> {
+    let mut merged: toml::Table = DEFAULT_SELECTORS
+        .parse()
+        .map_err(|e: toml::de::Error| Error::InvalidSelectors(e.to_string()))?;
+    if let Some(overrides) = overrides {
+        let overrides: toml::Table = overrides
+            .parse()
+            .map_err(|e: toml::de::Error| Error::InvalidSelectors(e.to_string()))?;
+        merge_tables(&mut merged, overrides);
+    }
+    let defs: SelectorsDef = toml::Value::Table(merged)
+        .try_into()
+        .map_err(|e: toml::de::Error| Error::InvalidSelectors(e.to_string()))?;
+
+    Ok(SelectorSet {
+        ptt: Arc::new(compile_article("ptt", defs.ptt)?),
+        pttweb: Arc::new(compile_article("pttweb", defs.pttweb)?),
+        dispcc: Arc::new(compile_article("dispcc", defs.dispcc)?),
+        ptt_board: Arc::new(BoardIndexSelectors {
+            entry: compile("ptt_board.entry", &defs.ptt_board.entry)?,
+            link: compile("ptt_board.link", &defs.ptt_board.link)?,
+            push_count: compile("ptt_board.push_count", &defs.ptt_board.push_count)?,
+            separator_class: defs.ptt_board.separator_class,
+        }),
+        pttweb_user: Arc::new(compile_user_page(defs.pttweb_user)?),
+    })
+}
+
+/// 逐欄覆寫：兩邊都是 table 時遞迴合併，否則以覆寫值為準。
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                merge_tables(base_table, override_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn compile_article(section: &str, def: ArticleSelectorsDef) -> Result<ArticleSelectors, Error> {
+    let field = |name: &str, selector: &str| compile(&format!("{}.{}", section, name), selector);
+    Ok(ArticleSelectors {
+        title: field("title", &def.title)?,
+        title_index: def.title_index,
+        board: field("board", &def.board)?,
+        push: field("push", &def.push)?,
+        tag: field("tag", &def.tag)?,
+        user: field("user", &def.user)?,
+        content: field("content", &def.content)?,
+        time: field("time", &def.time)?,
+        author: field("author", &def.author)?,
+        post_time: field("post_time", &def.post_time)?,
+        body: field("body", &def.body)?,
+        body_exclude: field("body_exclude", &def.body_exclude)?,
+        edit_notice: field("edit_notice", &def.edit_notice)?,
+        forward_notice: field("forward_notice", &def.forward_notice)?,
+    })
+}
+
+fn compile_user_page(def: UserPageSelectorsDef) -> Result<UserPageSelectors, Error> {
+    let field = |name: &str, selector: &str| compile(&format!("pttweb_user.{}", name), selector);
+    Ok(UserPageSelectors {
+        title: field("title", &def.title)?,
+        headline: field("headline", &def.headline)?,
+        board_item: field("board_item", &def.board_item)?,
+        board_name: field("board_name", &def.board_name)?,
+        board_count: field("board_count", &def.board_count)?,
+        comment_item: field("comment_item", &def.comment_item)?,
+        article_item: field("article_item", &def.article_item)?,
+        entry_board: field("entry_board", &def.entry_board)?,
+        entry_link: field("entry_link", &def.entry_link)?,
+        entry_content: field("entry_content", &def.entry_content)?,
+        entry_tag: field("entry_tag", &def.entry_tag)?,
+        entry_time: field("entry_time", &def.entry_time)?,
+    })
+}
+
+fn compile(field: &str, selector: &str) -> Result<Selector, Error> {
+    Selector::parse(selector)
+        .map_err(|e| Error::InvalidSelectors(format!("{} ({}): {}", field, selector, e)))
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// 合併同一個 key 同時進行中的請求：第一個呼叫者實際發出請求，
+/// 其他呼叫者等待並共用其結果 (singleflight)。
+pub struct SingleFlight<T> {
+    /// 進行中的請求；值為 `None` 代表尚未完成，`Some(None)` 代表請求失敗
+    flights: Mutex<HashMap<String, watch::Receiver<Option<Option<T>>>>>,
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        SingleFlight {
+            flights: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// 負責發出請求的呼叫者持有，結束 (包含被取消) 時移除進行中的紀錄。
+struct FlightGuard<'a, T> {
+    owner: &'a SingleFlight<T>,
+    key: &'a str,
+}
+
+impl<T> Drop for FlightGuard<'_, T> {
+    fn drop(&mut self) {
+        self.owner.flights.lock().unwrap().remove(self.key);
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    /// 執行 `fetch`，若同一個 key 已有請求進行中則等待其結果。
+    /// 先發出的請求失敗或被取消時，等待者會自行重新發出請求，錯誤不會被共用。
+    pub async fn run<F, Fut, E>(&self, key: &str, fetch: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let sender = {
+            let mut flights = self.flights.lock().unwrap();
+            match flights.get(key) {
+                Some(receiver) => Err(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    flights.insert(key.to_string(), receiver);
+                    Ok(sender)
+                }
+            }
+        };
+
+        let sender = match sender {
+            Ok(sender) => sender,
+            Err(mut receiver) => {
+                let shared = receiver
+                    .wait_for(|state| state.is_some())
+                    .await
+                    .ok()
+                    .and_then(|state| state.clone().flatten());
+                return match shared {
+                    Some(value) => Ok(value),
+                    None => fetch().await,
+                };
+            }
+        };
+
+        let _guard = FlightGuard { owner: self, key };
+        let result = fetch().await;
+        sender.send_replace(Some(result.as_ref().ok().cloned()));
+        result
+    }
+}
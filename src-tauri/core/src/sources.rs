@@ -0,0 +1,105 @@
+use crate::article_url::{parse_article_url, ArticleRef};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// moptt 的文章路徑：`/p/<看板>.<文章 ID>`，文章 ID 與官方網站相同。
+static MOPTT_PATH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/p/([A-Za-z0-9_\-]+)\.(M\.\d+\.A\.[0-9A-Fa-f]{1,4})/?$").unwrap());
+
+/// disp.cc 的文章路徑：`/b/<看板>-<disp.cc 文章代碼>`，看板也可能是數字編號。
+static DISPCC_PATH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/b/([A-Za-z0-9_\-]+?)-([A-Za-z0-9]+)/?$").unwrap());
+
+/// 可辨識的 PTT 鏡像 / 聚合站。
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MirrorSite {
+    /// moptt.tw，網址帶有原文的文章 ID，可直接對應回原文
+    Moptt,
+    /// disp.cc，使用自己的文章代碼，需從頁面找回原文連結或直接解析頁面
+    DispCc,
+}
+
+/// 依序嘗試的鏡像站，新增來源時在此登記。
+const MIRROR_SITES: &[MirrorSite] = &[MirrorSite::Moptt, MirrorSite::DispCc];
+
+/// 無法直接對應回原文、需要抓取鏡像頁面的文章。
+#[derive(Debug, Clone)]
+pub struct MirrorPage {
+    pub site: MirrorSite,
+    pub url: String,
+    /// 網址中可辨識的看板名稱，頁面上取不到看板時使用
+    pub board: Option<String>,
+}
+
+/// 鏡像網址的解析結果。
+#[derive(Debug, Clone)]
+pub enum MirrorResolution {
+    /// 可直接換算為原文網址
+    Canonical(ArticleRef),
+    /// 需要抓取並解析鏡像頁面
+    Page(MirrorPage),
+}
+
+impl MirrorSite {
+    fn matches_host(self, host: &str) -> bool {
+        let domain = match self {
+            MirrorSite::Moptt => "moptt.tw",
+            MirrorSite::DispCc => "disp.cc",
+        };
+        host == domain || host.ends_with(&format!(".{}", domain))
+    }
+
+    fn resolve(self, url: &Url) -> Option<MirrorResolution> {
+        match self {
+            MirrorSite::Moptt => {
+                let caps = MOPTT_PATH_RE.captures(url.path())?;
+                Some(MirrorResolution::Canonical(ArticleRef {
+                    board: caps[1].to_string(),
+                    article_id: caps[2].to_string(),
+                }))
+            }
+            MirrorSite::DispCc => {
+                let caps = DISPCC_PATH_RE.captures(url.path())?;
+                // 數字編號的看板無法對應到 PTT 看板名稱
+                let board = Some(caps[1].to_string())
+                    .filter(|board| !board.chars().all(|c| c.is_ascii_digit()));
+                Some(MirrorResolution::Page(MirrorPage {
+                    site: self,
+                    url: url.to_string(),
+                    board,
+                }))
+            }
+        }
+    }
+}
+
+/// 辨識常見的 PTT 鏡像網址，回傳對應的原文或需要解析的鏡像頁面。
+pub fn resolve_mirror_url(input: &str) -> Option<MirrorResolution> {
+    let trimmed = input.trim();
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+    let url = Url::parse(&with_scheme).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+
+    MIRROR_SITES
+        .iter()
+        .find(|site| site.matches_host(&host))
+        .and_then(|site| site.resolve(&url))
+}
+
+/// 在鏡像頁面中尋找原文連結 (例如 disp.cc 文末的「文章網址」)。
+pub fn find_canonical_link(html: &str) -> Option<ArticleRef> {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse(r#"a[href*="ptt.cc/bbs/"]"#).unwrap();
+    document
+        .select(&link_selector)
+        .filter_map(|el| el.value().attr("href"))
+        .find_map(|href| parse_article_url(href).ok())
+}
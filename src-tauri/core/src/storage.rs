@@ -0,0 +1,27 @@
+//! app data 目錄中使用者資料 (觀察名單、備註等) 的 JSON 讀寫。
+
+use crate::error::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// 讀取 JSON 檔，檔案不存在或格式錯誤時回傳預設值。
+pub fn load_json<T: DeserializeOwned + Default>(path: Option<&Path>) -> T {
+    path.and_then(|path| std::fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 寫入 JSON 檔，必要時建立目錄。
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Error> {
+    let storage_error = |e: std::io::Error| Error::Storage {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    };
+    let content =
+        serde_json::to_vec_pretty(value).map_err(|e| storage_error(std::io::Error::from(e)))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(storage_error)?;
+    }
+    std::fs::write(path, content).map_err(storage_error)
+}
@@ -0,0 +1,109 @@
+//! 推文時間軸：依推文時間分段統計推、噓、→ 的數量，讓前端畫出文章何時開始熱烈。
+
+use crate::scraper::{self, TimedPush};
+use serde::{Deserialize, Serialize};
+
+/// 可選用的分段長度 (分鐘)，取分段數不超過 [`MAX_BUCKETS`] 的最短者。
+const BUCKET_SIZES: &[u32] = &[1, 5, 15, 60, 360, 1440];
+const MAX_BUCKETS: u32 = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PushTimeline {
+    pub bucket_minutes: u32,
+    /// 從第一則到最後一則推文的連續分段，沒有推文的分段數量為 0
+    pub buckets: Vec<TimelineBucket>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineBucket {
+    /// 分段起始時間 (`MM/DD HH:MM`)
+    pub start: String,
+    pub push: u32,
+    pub hate: u32,
+    pub arrow: u32,
+}
+
+/// 依推文時間分段統計；沒有任何推文取得到時間時為 `None`。
+/// 推文時間以 `anchor` (見 [`PttArticleData::time_anchor`]) 為起點換算，跨年的文章仍是連續的一段。
+///
+/// [`PttArticleData::time_anchor`]: crate::scraper::PttArticleData::time_anchor
+pub fn build(pushes: &[TimedPush], anchor: Option<u32>) -> Option<PushTimeline> {
+    let anchor = anchor?;
+    let minute = |push: &TimedPush| scraper::minute_after(anchor, push.minute);
+    let first = pushes.iter().map(minute).min()?;
+    let last = pushes.iter().map(minute).max()?;
+    let span = last - first + 1;
+    let bucket_minutes = BUCKET_SIZES
+        .iter()
+        .copied()
+        .find(|size| span.div_ceil(*size) <= MAX_BUCKETS)
+        .unwrap_or(*BUCKET_SIZES.last().unwrap());
+    // 分段對齊整點 (例如每 5 分鐘從 :00、:05 起算)
+    let start = first - first % bucket_minutes;
+
+    let count = (last - start) / bucket_minutes + 1;
+    let mut buckets: Vec<TimelineBucket> = (0..count)
+        .map(|i| TimelineBucket {
+            start: scraper::format_minute_of_year(start + i * bucket_minutes),
+            ..Default::default()
+        })
+        .collect();
+    for push in pushes {
+        let bucket = &mut buckets[((minute(push) - start) / bucket_minutes) as usize];
+        match push.comment_type {
+            "push" => bucket.push += 1,
+            "hate" => bucket.hate += 1,
+            "arrow" => bucket.arrow += 1,
+            _ => {}
+        }
+    }
+    Some(PushTimeline {
+        bucket_minutes,
+        buckets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserId;
+
+    fn push(time: &str, comment_type: &'static str) -> TimedPush {
+        TimedPush {
+            minute: scraper::minute_of_year(time).unwrap(),
+            comment_type,
+            user: UserId::new("user"),
+        }
+    }
+
+    #[test]
+    fn pushes_across_new_year_form_one_continuous_series() {
+        let pushes = [
+            push("12/31 23:58", "push"),
+            push("01/01 00:01", "hate"),
+            push("01/01 00:03", "arrow"),
+        ];
+        let anchor = scraper::minute_of_year("12/31 23:50");
+
+        let timeline = build(&pushes, anchor).unwrap();
+
+        assert_eq!(timeline.bucket_minutes, 1);
+        let starts: Vec<&str> = timeline.buckets.iter().map(|b| b.start.as_str()).collect();
+        assert_eq!(
+            starts,
+            [
+                "12/31 23:58",
+                "12/31 23:59",
+                "01/01 00:00",
+                "01/01 00:01",
+                "01/01 00:02",
+                "01/01 00:03"
+            ]
+        );
+        assert_eq!(timeline.buckets[0].push, 1);
+        assert_eq!(timeline.buckets[3].hate, 1);
+        assert_eq!(timeline.buckets[5].arrow, 1);
+    }
+}
@@ -0,0 +1,94 @@
+//! 使用者統計的歷史紀錄：每次從 pttweb.cc 等來源查到使用者的留言數時附上時間記錄下來，
+//! 讓前端畫出留言數隨時間的變化，找出短時間內在某看板突然活躍的帳號。
+
+use crate::diagnostics::unix_now;
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::report::PttWebData;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 統計紀錄檔名稱，存放於 app data 目錄下。
+pub const TRENDS_FILE_NAME: &str = "user_trends.json";
+/// 每位使用者最多保留的紀錄數，超過時移除最舊的紀錄。
+const MAX_SNAPSHOTS_PER_USER: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StatsSnapshot {
+    /// 查詢時間 (Unix 秒)
+    at: u64,
+    total_comments: u32,
+    /// 只包含查詢時追蹤的看板
+    board_comments: HashMap<BoardName, u32>,
+}
+
+/// `get_user_trend` 回傳的一筆紀錄。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// 查詢時間 (Unix 秒)
+    pub at: u64,
+    pub total_comments: u32,
+    /// 該次查詢未追蹤此看板時為 `None`
+    pub board_comments: Option<u32>,
+}
+
+/// 所有使用者的統計紀錄；查詢期間只記錄在記憶體中，由呼叫端在查詢結束後呼叫 [`UserTrendStore::save`]。
+#[derive(Default)]
+pub struct UserTrendStore {
+    path: Option<PathBuf>,
+    snapshots: Mutex<HashMap<UserId, Vec<StatsSnapshot>>>,
+}
+
+impl UserTrendStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從空的紀錄開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(TRENDS_FILE_NAME));
+        let snapshots = storage::load_json(path.as_deref());
+        UserTrendStore {
+            path,
+            snapshots: Mutex::new(snapshots),
+        }
+    }
+
+    pub fn record(&self, user: &UserId, data: &PttWebData) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let series = snapshots.entry(user.clone()).or_default();
+        series.push(StatsSnapshot {
+            at: unix_now(),
+            total_comments: data.total_comments,
+            board_comments: data.board_comments.clone(),
+        });
+        if series.len() > MAX_SNAPSHOTS_PER_USER {
+            let excess = series.len() - MAX_SNAPSHOTS_PER_USER;
+            series.drain(..excess);
+        }
+    }
+
+    /// 使用者的生涯總留言數與在 `board` 的留言數，依時間由舊到新排列。
+    pub fn trend(&self, user: &UserId, board: &BoardName) -> Vec<TrendPoint> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .get(user)
+            .into_iter()
+            .flatten()
+            .map(|snapshot| TrendPoint {
+                at: snapshot.at,
+                total_comments: snapshot.total_comments,
+                board_comments: snapshot.board_comments.get(board).copied(),
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let snapshots = self.snapshots.lock().unwrap();
+        storage::save_json(path, &*snapshots)
+    }
+}
@@ -0,0 +1,126 @@
+//! 使用者自訂的觀察名單：每份名單是一組帳號，分析結果中出現在任一名單上的留言者會被標記。
+
+use crate::domain::UserId;
+use crate::error::Error;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 觀察名單檔名稱，存放於 app data 目錄下。
+pub const WATCHLISTS_FILE_NAME: &str = "watchlists.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Watchlist {
+    pub name: String,
+    pub users: Vec<UserId>,
+}
+
+/// 所有觀察名單，每次修改後立即寫回磁碟。
+#[derive(Default)]
+pub struct WatchlistStore {
+    path: Option<PathBuf>,
+    /// 名單名稱 → 帳號
+    lists: Mutex<BTreeMap<String, Vec<UserId>>>,
+}
+
+impl WatchlistStore {
+    /// 從 `data_dir` (app data 目錄) 載入，檔案不存在或格式錯誤時從空的名單開始。
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let path = data_dir.map(|dir| dir.join(WATCHLISTS_FILE_NAME));
+        let lists = storage::load_json(path.as_deref());
+        WatchlistStore {
+            path,
+            lists: Mutex::new(lists),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Watchlist> {
+        self.lists
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, users)| Watchlist {
+                name: name.clone(),
+                users: users.clone(),
+            })
+            .collect()
+    }
+
+    /// 建立空的名單；同名的名單已存在時回傳錯誤。
+    pub fn create(&self, name: &str) -> Result<Watchlist, Error> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::InvalidWatchlistName(name.to_string()));
+        }
+        self.update(|lists| {
+            if lists.contains_key(name) {
+                return Err(Error::WatchlistExists(name.to_string()));
+            }
+            lists.insert(name.to_string(), Vec::new());
+            Ok(Watchlist {
+                name: name.to_string(),
+                users: Vec::new(),
+            })
+        })
+    }
+
+    /// 將帳號加入名單，已在名單中時不重複加入。
+    pub fn add_user(&self, name: &str, user: UserId) -> Result<Watchlist, Error> {
+        self.update(|lists| {
+            let users = lists
+                .get_mut(name)
+                .ok_or_else(|| Error::WatchlistNotFound(name.to_string()))?;
+            if !users.contains(&user) {
+                users.push(user);
+            }
+            Ok(Watchlist {
+                name: name.to_string(),
+                users: users.clone(),
+            })
+        })
+    }
+
+    pub fn remove_user(&self, name: &str, user: &UserId) -> Result<Watchlist, Error> {
+        self.update(|lists| {
+            let users = lists
+                .get_mut(name)
+                .ok_or_else(|| Error::WatchlistNotFound(name.to_string()))?;
+            users.retain(|existing| existing != user);
+            Ok(Watchlist {
+                name: name.to_string(),
+                users: users.clone(),
+            })
+        })
+    }
+
+    /// 包含此帳號的所有名單名稱。
+    pub fn lists_containing(&self, user: &UserId) -> Vec<String> {
+        self.lists
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, users)| users.contains(user))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// 修改名單並寫回磁碟；寫入失敗時還原修改並回傳錯誤。
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut BTreeMap<String, Vec<UserId>>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut lists = self.lists.lock().unwrap();
+        let previous = lists.clone();
+        let result = f(&mut lists)?;
+        if let Some(path) = &self.path {
+            if let Err(e) = storage::save_json(path, &*lists) {
+                *lists = previous;
+                return Err(e);
+            }
+        }
+        Ok(result)
+    }
+}
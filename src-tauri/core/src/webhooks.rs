@@ -0,0 +1,196 @@
+//! 外部通知：分析完成或警示觸發時，以 POST 將摘要送到使用者設定的網址
+//! (JSON、Discord 或 Telegram 格式)，暫時性的失敗會重試。
+
+use crate::alerts::AlertEvent;
+use crate::config::{WebhookConfig, WebhookFormat};
+use crate::domain::{BoardName, UserId};
+use crate::error::Error;
+use crate::report::AnalysisResult;
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::json;
+use tokio::time::Duration;
+
+/// 單次請求的逾時。
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// 最多嘗試的次數 (含第一次)。
+const MAX_ATTEMPTS: u32 = 3;
+/// 第 n 次重試前等待 n 倍的此時間。
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+/// 訊息中最多列出的使用者數。
+const MAX_LISTED_USERS: usize = 10;
+/// Discord 訊息的字數上限。
+const DISCORD_MAX_CHARS: usize = 2000;
+
+/// 送到 webhook 的事件；JSON 格式時直接序列化，以 `event` 欄位區分種類。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum WebhookEvent {
+    #[serde(rename_all = "camelCase")]
+    AnalysisCompleted {
+        url: String,
+        title: String,
+        board: BoardName,
+        commenter_count: usize,
+        highlighted_users: Vec<UserId>,
+    },
+    AlertTriggered(AlertEvent),
+    /// `test_webhook` 命令送出的測試訊息
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn analysis_completed(result: &AnalysisResult) -> Self {
+        WebhookEvent::AnalysisCompleted {
+            url: result.metadata.url.clone(),
+            title: result.metadata.title.clone(),
+            board: result.metadata.board.clone(),
+            commenter_count: result.highlighted_data.len() + result.normal_data.len(),
+            highlighted_users: result
+                .highlighted_data
+                .iter()
+                .map(|row| row.user.clone())
+                .collect(),
+        }
+    }
+
+    /// Discord 與 Telegram 格式使用的文字訊息。
+    fn text(&self) -> String {
+        match self {
+            WebhookEvent::AnalysisCompleted {
+                url,
+                title,
+                commenter_count,
+                highlighted_users,
+                ..
+            } => format!(
+                "分析完成：{}\n{}\n{} 位留言者，{} 位符合高亮條件{}",
+                title,
+                url,
+                commenter_count,
+                highlighted_users.len(),
+                list_users(highlighted_users)
+            ),
+            WebhookEvent::AlertTriggered(alert) => format!(
+                "警示：{}\n{}\n{}{}",
+                alert.rule_name,
+                alert.title,
+                alert.url,
+                list_users(&alert.users)
+            ),
+            WebhookEvent::Test => "PTT 留言分析器的測試訊息".to_string(),
+        }
+    }
+
+    fn is_wanted_by(&self, webhook: &WebhookConfig) -> bool {
+        match self {
+            WebhookEvent::AnalysisCompleted { .. } => webhook.on_analysis,
+            WebhookEvent::AlertTriggered(_) => webhook.on_alert,
+            WebhookEvent::Test => true,
+        }
+    }
+}
+
+fn list_users(users: &[UserId]) -> String {
+    if users.is_empty() {
+        return String::new();
+    }
+    let mut listed = users
+        .iter()
+        .take(MAX_LISTED_USERS)
+        .map(|user| user.to_string())
+        .collect::<Vec<_>>()
+        .join("、");
+    if users.len() > MAX_LISTED_USERS {
+        listed.push_str(&format!(" 等 {} 人", users.len()));
+    }
+    format!("\n{}", listed)
+}
+
+/// 依 webhook 的格式組出請求內容。
+fn request_body(webhook: &WebhookConfig, event: &WebhookEvent) -> Result<serde_json::Value, Error> {
+    match webhook.format {
+        WebhookFormat::Json => {
+            serde_json::to_value(event).map_err(|e| Error::Internal(e.to_string()))
+        }
+        WebhookFormat::Discord => Ok(json!({
+            "content": event.text().chars().take(DISCORD_MAX_CHARS).collect::<String>(),
+        })),
+        WebhookFormat::Telegram => {
+            let chat_id = webhook
+                .telegram_chat_id
+                .as_deref()
+                .ok_or_else(|| Error::Webhook {
+                    host: host_of(&webhook.url),
+                    reason: "telegramChatId is not set".to_string(),
+                })?;
+            Ok(json!({ "chat_id": chat_id, "text": event.text() }))
+        }
+    }
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// 發送事件到一個 webhook；連線失敗、逾時、429 與 5xx 時重試。
+pub async fn send(webhook: &WebhookConfig, event: &WebhookEvent) -> Result<(), Error> {
+    let host = host_of(&webhook.url);
+    let url = Url::parse(&webhook.url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "https" | "http"))
+        .ok_or_else(|| Error::Webhook {
+            host: host.clone(),
+            reason: "invalid url".to_string(),
+        })?;
+    let body = request_body(webhook, event)?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let mut attempt = 1;
+    loop {
+        let (retryable, reason) = match client.post(url.clone()).json(&body).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                (
+                    status.is_server_error() || status.as_u16() == 429,
+                    format!("HTTP {}", status.as_u16()),
+                )
+            }
+            // reqwest 的錯誤訊息包含完整網址，只保留錯誤種類
+            Err(e) => (
+                true,
+                if e.is_timeout() {
+                    "timeout".to_string()
+                } else {
+                    "connection failed".to_string()
+                },
+            ),
+        };
+        if !retryable || attempt >= MAX_ATTEMPTS {
+            return Err(Error::Webhook { host, reason });
+        }
+        tracing::info!(host = %host, attempt, reason = %reason, "webhook 發送失敗，稍後重試");
+        tokio::time::sleep(RETRY_DELAY * attempt).await;
+        attempt += 1;
+    }
+}
+
+/// 發送事件到所有訂閱此種事件的 webhook，失敗時只記錄日誌。
+pub async fn dispatch(webhooks: &[WebhookConfig], event: &WebhookEvent) {
+    let sends = webhooks
+        .iter()
+        .filter(|webhook| event.is_wanted_by(webhook))
+        .map(|webhook| send(webhook, event));
+    for result in futures::future::join_all(sends).await {
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "webhook 發送失敗");
+        }
+    }
+}
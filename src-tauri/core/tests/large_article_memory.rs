@@ -0,0 +1,69 @@
+//! 大型文章解析後不應保留整份 DOM：以計數用的 allocator 記錄解析期間的最高用量與解析後仍佔用的記憶體，
+//! 解析期間應建立遠大於原始 HTML 的 DOM，解析完成後保留的資料則應小於原始 HTML。
+
+use ptt_analyzer_core::scraper::{self, ScrapeOptions};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/large_article.html"
+);
+const PUSH_COUNT: usize = 1_500;
+
+#[test]
+fn large_article_dom_is_released_after_parsing() {
+    let path = Path::new(FIXTURE);
+    let html_len = std::fs::metadata(path).expect("找不到 fixture").len() as usize;
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let options = ScrapeOptions::default();
+    let parse = || {
+        runtime
+            .block_on(scraper::parse_local_article(path, &options))
+            .expect("解析失敗")
+    };
+
+    // 先解析一次，讓 selectors、正規表示式與 blocking 執行緒等一次性的配置不計入
+    drop(parse());
+
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let article = parse();
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    let retained = CURRENT.load(Ordering::SeqCst).saturating_sub(baseline);
+
+    assert_eq!(article.total_floors, PUSH_COUNT);
+    assert!(
+        peak > 2 * html_len,
+        "解析期間最高只多用了 {peak} bytes，HTML 為 {html_len} bytes，DOM 似乎沒有建立"
+    );
+    assert!(
+        retained < html_len,
+        "解析後仍佔用 {retained} bytes (解析期間最高 {peak} bytes)，HTML 為 {html_len} bytes，DOM 可能沒有釋放"
+    );
+}
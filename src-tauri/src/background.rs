@@ -0,0 +1,57 @@
+//! 背景執行：登入系統時自動啟動 (不顯示視窗)，關閉視窗時隱藏到系統匣，
+//! 讓佇列中的分析在視窗關閉後繼續執行。
+
+use crate::state::AppState;
+use ptt_analyzer_core::config::BackgroundConfig;
+use ptt_analyzer_core::error::Error;
+use std::sync::atomic::Ordering;
+use tauri::Manager;
+use tauri_plugin_autostart::ManagerExt;
+
+/// 自動啟動時附加的命令列參數，代表啟動時不顯示視窗。
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+/// 依設定檔同步自動啟動狀態；以自動啟動的方式執行時隱藏主視窗。
+pub fn setup(app: &tauri::AppHandle, config: &BackgroundConfig) {
+    if let Err(e) = sync_autostart(app, config.autostart) {
+        tracing::warn!(error = %e, "無法更新開機自動啟動設定");
+    }
+    if std::env::args().any(|arg| arg == MINIMIZED_ARG) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.hide();
+        }
+    }
+}
+
+/// 立即套用新的背景執行設定。
+pub fn apply(app: &tauri::AppHandle, config: &BackgroundConfig) -> Result<(), Error> {
+    app.state::<AppState>()
+        .close_to_tray
+        .store(config.close_to_tray, Ordering::Relaxed);
+    sync_autostart(app, config.autostart)
+}
+
+/// 啟用隱藏到系統匣時，關閉主視窗只隱藏視窗，app 與進行中的分析繼續執行。
+pub fn on_close_requested(window: &tauri::Window, api: &tauri::CloseRequestApi) {
+    let close_to_tray = window
+        .state::<AppState>()
+        .close_to_tray
+        .load(Ordering::Relaxed);
+    if close_to_tray && window.label() == "main" {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
+
+fn sync_autostart(app: &tauri::AppHandle, enabled: bool) -> Result<(), Error> {
+    let autolaunch = app.autolaunch();
+    let currently_enabled = autolaunch
+        .is_enabled()
+        .map_err(|e| Error::Internal(e.to_string()))?;
+    let result = match (enabled, currently_enabled) {
+        (true, false) => autolaunch.enable(),
+        (false, true) => autolaunch.disable(),
+        _ => Ok(()),
+    };
+    result.map_err(|e| Error::Internal(e.to_string()))
+}
@@ -1,31 +1,18 @@
-// src-tauri/src/config.rs
+use ptt_analyzer_core::config::{self, AppConfig};
+use std::path::PathBuf;
+use tauri::Manager;
 
-use serde::{Deserialize, Serialize};
-
-// (新增) 也加上 Serialize，讓這個結構體可以在 Rust 端與前端之間雙向傳遞
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SortingConfig {
-    pub sort_by: String,
-    pub order: String, // "asc" or "desc"
-}
-
-// (新增) 也加上 Serialize
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct AppConfig {
-    pub boards: Vec<String>,
-    pub sorting: SortingConfig,
+/// 設定檔的完整路徑。
+pub fn config_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(config::CONFIG_FILE_NAME))
 }
 
-// Default 實作依然有用，可以作為前端初始狀態的參考
-impl Default for AppConfig {
-    fn default() -> Self {
-        AppConfig {
-            boards: vec!["Gossiping".to_string(), "HatePolitics".to_string()],
-            sorting: SortingConfig {
-                sort_by: "本文留言數".to_string(),
-                order: "desc".to_string(),
-            },
-        }
-    }
+/// 從 app config 目錄載入設定檔，檔案不存在或格式錯誤時使用預設值。
+pub fn load_config(app: &tauri::AppHandle) -> AppConfig {
+    config_path(app)
+        .map(|path| config::load_from(&path))
+        .unwrap_or_default()
 }
@@ -0,0 +1,52 @@
+//! `ptt-analyzer://analyze?url=…` 連結：讓書籤小工具或瀏覽器擴充功能將文章直接交給 app 分析。
+
+use crate::quick_analyze::{self, Completion};
+use tauri::Url;
+use tauri_plugin_deep_link::DeepLinkExt;
+
+const SCHEME: &str = "ptt-analyzer";
+const ANALYZE_ACTION: &str = "analyze";
+
+/// 處理 app 執行期間開啟的連結，以及啟動 app 的連結。
+pub fn setup(app: &tauri::AppHandle) {
+    // Windows 與 Linux 的開發版本沒有安裝程式註冊 scheme，在執行時註冊
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!(error = %e, "無法註冊 ptt-analyzer:// 連結");
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for link in event.urls() {
+            open_link(&handle, &link);
+        }
+    });
+    // Windows 與 Linux 以命令列參數傳入啟動 app 的連結，macOS 則同樣經由 on_open_url
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Ok(Some(links)) = app.deep_link().get_current() {
+        for link in links {
+            open_link(app, &link);
+        }
+    }
+}
+
+/// 將連結中的文章加入分析，完成後顯示視窗；不認得的連結只記錄在日誌中。
+fn open_link(app: &tauri::AppHandle, link: &Url) {
+    match article_input(link) {
+        Some(input) => {
+            // 任何網頁都能開啟這種連結，只接受線上文章，不讀取本機或網路磁碟上的檔案
+            quick_analyze::analyze_remote_input(app, &input, "連結", Completion::ShowWindow);
+        }
+        None => tracing::warn!(link = %link, "無法辨識的 ptt-analyzer:// 連結"),
+    }
+}
+
+/// 取出 `ptt-analyzer://analyze?url=…` 的 `url` 參數。
+fn article_input(link: &Url) -> Option<String> {
+    if link.scheme() != SCHEME || link.host_str() != Some(ANALYZE_ACTION) {
+        return None;
+    }
+    link.query_pairs()
+        .find(|(key, _)| key == "url")
+        .map(|(_, value)| value.into_owned())
+}
@@ -0,0 +1,64 @@
+use ptt_analyzer_core::diagnostics::{DiagnosticsState, RecentError};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// 由 build.rs 寫入的 git commit hash，非 git 環境下建置時為 "unknown"。
+const BUILD_HASH: &str = match option_env!("BUILD_GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+
+/// `get_diagnostics` 命令回傳的診斷資訊，方便回報問題或在設定頁的除錯面板顯示。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    app_version: String,
+    build_hash: String,
+    os: String,
+    arch: String,
+    config_path: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+    log_dir: Option<PathBuf>,
+    cache_size_bytes: u64,
+    active_jobs: usize,
+    error_counts: HashMap<String, u32>,
+    recent_errors: Vec<RecentError>,
+}
+
+pub fn collect(app: &tauri::AppHandle, state: &DiagnosticsState) -> DiagnosticsReport {
+    let path = app.path();
+    let config_path = crate::config::config_path(app);
+    let cache_dir = path.app_cache_dir().ok();
+    let cache_size_bytes = cache_dir.as_deref().map(dir_size).unwrap_or(0);
+
+    DiagnosticsReport {
+        app_version: app.package_info().version.to_string(),
+        build_hash: BUILD_HASH.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        config_path,
+        cache_dir,
+        log_dir: path.app_log_dir().ok(),
+        cache_size_bytes,
+        active_jobs: state.active_jobs(),
+        error_counts: state.error_counts(),
+        recent_errors: state.recent_errors(),
+    }
+}
+
+/// 遞迴計算目錄大小，目錄不存在時為 0。
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
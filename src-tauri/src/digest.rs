@@ -0,0 +1,54 @@
+//! 每日摘要的背景工作：依設定每天固定時間產生過去 24 小時的摘要，
+//! 發送 `DIGEST_READY` 事件與系統通知。
+
+use crate::state::AppState;
+use ptt_analyzer_core::config::DigestConfig;
+use ptt_analyzer_core::diagnostics::unix_now;
+use ptt_analyzer_core::digest::{self, Digest};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 摘要產生後發送，payload 為 [`Digest`]。
+pub const DIGEST_READY: &str = "DIGEST_READY";
+
+/// 設定中啟用每日摘要時啟動背景工作，app 結束前持續執行。
+pub fn start(app: &tauri::AppHandle, config: &DigestConfig) {
+    if !config.enabled {
+        return;
+    }
+    let hour = config.hour;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let now = unix_now();
+            let wait = digest::next_run_at(now, hour) - now;
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            let state = app.state::<AppState>();
+            let digest = state.digests.generate(&state.history, &state.alerts);
+            notify(&app, &digest);
+            let _ = app.emit(DIGEST_READY, digest);
+        }
+    });
+}
+
+fn notify(app: &tauri::AppHandle, digest: &Digest) {
+    let mut body = format!(
+        "分析了 {} 篇文章，{} 個新的高亮帳號，{} 則警示",
+        digest.articles.len(),
+        digest.new_highlighted_users.len(),
+        digest.alerts.len()
+    );
+    if let Some(top) = digest.most_active.first() {
+        body.push_str(&format!("\n最活躍：{} ({} 篇)", top.user, top.articles));
+    }
+    let sent = app
+        .notification()
+        .builder()
+        .title("PTT 留言分析每日摘要")
+        .body(body)
+        .show();
+    if let Err(e) = sent {
+        tracing::warn!(error = %e, "無法發送每日摘要通知");
+    }
+}
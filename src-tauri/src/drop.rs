@@ -0,0 +1,126 @@
+//! 拖放到視窗的檔案與文字：HTML 檔以本機檔案分析，網址 (或網址清單) 驗證後加入分析佇列，
+//! 處理結果以 `DROP_PROCESSED` 事件通知前端。
+
+use crate::state::AppState;
+use ptt_analyzer_core::analysis::{AnalysisOptions, AnalyzePayload};
+use ptt_analyzer_core::article_url;
+use ptt_analyzer_core::batch::{self, ParsedUrlList};
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::jobs::JobId;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+
+/// 拖放處理完成時發送給前端的事件，payload 為 [`DropResult`]。
+const DROP_PROCESSED: &str = "DROP_PROCESSED";
+
+/// 一次拖放的處理結果。
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DropResult {
+    pub accepted: Vec<AcceptedDrop>,
+    pub rejected: Vec<RejectedDrop>,
+}
+
+/// 已加入分析佇列的項目。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptedDrop {
+    /// 拖放的檔案路徑或文字
+    pub input: String,
+    pub url: String,
+    pub job_id: JobId,
+}
+
+/// 無法分析的項目，連同錯誤碼回報給前端。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedDrop {
+    pub input: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// 處理拖放到視窗的檔案：HTML 檔直接分析，其他檔案視為網址清單 (含 Windows 的 .url 捷徑)。
+pub fn handle_paths(app: &tauri::AppHandle, paths: &[PathBuf]) {
+    let mut result = DropResult::default();
+    for path in paths {
+        let input = path.display().to_string();
+        if article_url::local_file_path(&input).is_some() {
+            match article_url::resolve_input(&input) {
+                Ok((_, url)) => {
+                    let job_id = enqueue(app, url.clone());
+                    result.accepted.push(AcceptedDrop { input, url, job_id });
+                }
+                Err(e) => result.rejected.push(RejectedDrop {
+                    input,
+                    code: e.code(),
+                    message: e.to_string(),
+                }),
+            }
+            continue;
+        }
+        match read_links(path) {
+            Ok(content) => queue_list(app, batch::parse_url_list(&content), &mut result),
+            Err(e) => result.rejected.push(RejectedDrop {
+                input,
+                code: e.code(),
+                message: e.to_string(),
+            }),
+        }
+    }
+    let _ = app.emit(DROP_PROCESSED, &result);
+}
+
+/// 處理拖放的文字 (網頁中的連結或選取的網址)，每行一個網址；前端收到 drop 事件時轉交。
+pub fn handle_text(app: &tauri::AppHandle, text: &str) -> DropResult {
+    let mut result = DropResult::default();
+    queue_list(app, batch::parse_url_list(text), &mut result);
+    let _ = app.emit(DROP_PROCESSED, &result);
+    result
+}
+
+/// 讀取網址清單；.url 捷徑只取其中的 `URL=` 欄位。
+fn read_links(path: &Path) -> Result<String, Error> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::LocalFile {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    let is_shortcut = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("url"));
+    if !is_shortcut {
+        return Ok(content);
+    }
+    Ok(content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("URL="))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn queue_list(app: &tauri::AppHandle, parsed: ParsedUrlList, result: &mut DropResult) {
+    for entry in parsed.entries {
+        let job_id = enqueue(app, entry.url.clone());
+        result.accepted.push(AcceptedDrop {
+            input: entry.url.clone(),
+            url: entry.url,
+            job_id,
+        });
+    }
+    result
+        .rejected
+        .extend(parsed.errors.into_iter().map(|error| RejectedDrop {
+            input: error.content,
+            code: error.code,
+            message: error.message,
+        }));
+}
+
+/// 以設定檔的看板與預設選項加入分析佇列。
+fn enqueue(app: &tauri::AppHandle, url: String) -> JobId {
+    let state = app.state::<AppState>();
+    let options = AnalysisOptions::from_config(state.config.clone());
+    crate::enqueue_analysis(app.clone(), state, AnalyzePayload { url, options })
+}
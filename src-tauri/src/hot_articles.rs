@@ -0,0 +1,180 @@
+//! 熱門文章監看：依設定定期檢查看板文章列表，推文數達到門檻的文章發送 `HOT_ARTICLE` 事件與系統通知，
+//! 並可自動加入分析佇列，在集體推文進行中就能分析。每篇文章只通知一次。
+
+use crate::state::AppState;
+use ptt_analyzer_core::analysis::{AnalysisOptions, AnalyzePayload};
+use ptt_analyzer_core::config::{ConcurrentAnalysisPolicy, HotArticleConfig};
+use ptt_analyzer_core::diagnostics::unix_now;
+use ptt_analyzer_core::domain::BoardName;
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::jobs::{JobError, JobId};
+use ptt_analyzer_core::scraper;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 有文章達到推文數門檻時發送，payload 為 [`HotArticlePayload`]。
+pub const HOT_ARTICLE: &str = "HOT_ARTICLE";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HotArticlePayload {
+    pub board: BoardName,
+    #[serde(flatten)]
+    pub article: scraper::BoardIndexEntry,
+    /// 自動加入分析佇列時的工作 ID
+    pub job_id: Option<JobId>,
+}
+
+/// 熱門文章監看的狀態，時間皆為 Unix 時間 (秒)。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HotArticleStatus {
+    pub boards: Vec<BoardName>,
+    pub min_push_count: i32,
+    pub last_check_at: Option<u64>,
+    pub next_check_at: Option<u64>,
+    /// 啟動後通知過的熱門文章數
+    pub notified_count: usize,
+    /// 上次檢查中最後一個失敗的看板與原因，全部成功時為空
+    pub last_error: Option<BoardCheckError>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardCheckError {
+    pub board: BoardName,
+    #[serde(flatten)]
+    pub error: JobError,
+}
+
+/// 監看的執行狀態；沒有監看的看板時為空。
+#[derive(Default)]
+pub struct HotArticleMonitor {
+    status: Mutex<Option<HotArticleStatus>>,
+}
+
+impl HotArticleMonitor {
+    pub fn status(&self) -> Option<HotArticleStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut HotArticleStatus)) {
+        if let Some(status) = self.status.lock().unwrap().as_mut() {
+            f(status);
+        }
+    }
+}
+
+/// 設定中有監看的看板時啟動背景工作，app 結束前持續執行。
+pub fn start(app: &tauri::AppHandle, config: &HotArticleConfig) {
+    if config.boards.is_empty() {
+        return;
+    }
+    *app.state::<AppState>().hot_articles.status.lock().unwrap() = Some(HotArticleStatus {
+        boards: config.boards.clone(),
+        min_push_count: config.min_push_count,
+        last_check_at: None,
+        next_check_at: None,
+        notified_count: 0,
+        last_error: None,
+    });
+    let config = config.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let interval = Duration::from_secs(u64::from(config.interval_minutes.max(1)) * 60);
+        let mut notified = HashSet::new();
+        loop {
+            let state = app.state::<AppState>();
+            let quiet_hours = &state.rate_limiters.quiet_hours;
+            quiet_hours.wait_background().await;
+            let mut last_error = None;
+            for board in &config.boards {
+                if let Err(e) = check_board(&app, &config, board, &mut notified).await {
+                    tracing::warn!(board = %board, error = %e, "無法取得看板文章列表");
+                    last_error = Some(BoardCheckError {
+                        board: board.clone(),
+                        error: JobError::from(&e),
+                    });
+                }
+            }
+            let wait = quiet_hours.background_interval(interval);
+            state.hot_articles.update(|status| {
+                let now = unix_now();
+                status.last_check_at = Some(now);
+                status.next_check_at = Some(now + wait.as_secs());
+                status.notified_count = notified.len();
+                status.last_error = last_error;
+            });
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
+
+async fn check_board(
+    app: &tauri::AppHandle,
+    config: &HotArticleConfig,
+    board: &BoardName,
+    notified: &mut HashSet<String>,
+) -> Result<(), Error> {
+    let state = app.state::<AppState>();
+    state.rate_limiters.ptt.wait_ready().await;
+    let entries = scraper::board_index(board, state.http_client.as_ref()).await?;
+    for article in entries {
+        if article.push_count < config.min_push_count || !notified.insert(article.url.clone()) {
+            continue;
+        }
+        tracing::info!(board = %board, url = %article.url, push_count = article.push_count, "熱門文章");
+        let job_id = config
+            .auto_analyze
+            .then(|| queue_analysis(app, &article.url));
+        let sent = app
+            .notification()
+            .builder()
+            .title(format!("{} 熱門文章", board))
+            .body(format!(
+                "{}\n推文數 {}{}",
+                article.title,
+                article.push_count,
+                if job_id.is_some() {
+                    "，已加入分析佇列"
+                } else {
+                    ""
+                }
+            ))
+            .show();
+        if let Err(e) = sent {
+            tracing::warn!(error = %e, "無法發送熱門文章通知");
+        }
+        let _ = app.emit(
+            HOT_ARTICLE,
+            HotArticlePayload {
+                board: board.clone(),
+                article,
+                job_id,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// 以目前的設定將文章加入分析佇列，不中斷進行中的分析。
+fn queue_analysis(app: &tauri::AppHandle, url: &str) -> JobId {
+    let state = app.state::<AppState>();
+    let payload = AnalyzePayload {
+        url: url.to_string(),
+        options: AnalysisOptions::from_config(state.config.clone()),
+    };
+    let (job_id, control) = state.jobs.register(&payload.url);
+    tauri::async_runtime::spawn(crate::execute_job(
+        app.clone(),
+        job_id,
+        control,
+        payload,
+        ConcurrentAnalysisPolicy::Queue,
+    ));
+    job_id
+}
@@ -0,0 +1,153 @@
+//! 本機 HTTP API (`http-api` feature)：讓外部工具或 notebook 在 app 執行時驅動分析。
+//!
+//! 與桌面介面共用同一個工作佇列，以 API 加入的分析也會出現在工作清單中。
+//! 只監聽 127.0.0.1，且每個請求都必須附上設定檔中的 token。
+
+use crate::state::AppState;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use ptt_analyzer_core::analysis::AnalyzePayload;
+use ptt_analyzer_core::config::HttpApiConfig;
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::jobs::{JobDetail, JobId};
+use serde::Serialize;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tauri::Manager;
+
+struct ApiState {
+    app: tauri::AppHandle,
+    token: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyzeResponse {
+    job_id: JobId,
+}
+
+/// 依設定在背景啟動 API；未啟用或未設定 token 時不啟動。
+pub fn start(app: tauri::AppHandle, config: &HttpApiConfig) {
+    if !config.enabled {
+        return;
+    }
+    if config.token.is_empty() {
+        tracing::warn!("本機 HTTP API 已啟用但未設定 token，不啟動");
+        return;
+    }
+
+    let state = Arc::new(ApiState {
+        app,
+        token: config.token.clone(),
+    });
+    let router = Router::new()
+        .route("/analyze", post(analyze))
+        .route("/reports/:id", get(report))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            require_token,
+        ))
+        .with_state(state);
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(%addr, error = %e, "無法啟動本機 HTTP API");
+                return;
+            }
+        };
+        tracing::info!(%addr, "本機 HTTP API 已啟動");
+        if let Err(e) = axum::serve(listener, router).await {
+            tracing::error!(error = %e, "本機 HTTP API 已停止");
+        }
+    });
+}
+
+/// 檢查 `Authorization: Bearer <token>` 標頭。
+async fn require_token(
+    State(state): State<Arc<ApiState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), state.token.as_bytes()));
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// 比較所需時間與內容無關，避免以回應時間逐字猜出 token。
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 將分析加入佇列，立即回傳工作 ID。
+/// 參數與 `enqueue_analysis` 相同，省略 `config` 時使用 app 的設定檔。
+async fn analyze(
+    State(state): State<Arc<ApiState>>,
+    Json(mut body): Json<serde_json::Value>,
+) -> Result<(StatusCode, Json<AnalyzeResponse>), ApiError> {
+    if let Some(fields) = body.as_object_mut() {
+        if !fields.contains_key("config") {
+            let app_config = &state.app.state::<AppState>().inner().config;
+            fields.insert(
+                "config".to_string(),
+                serde_json::to_value(app_config).unwrap_or_default(),
+            );
+        }
+    }
+    let payload: AnalyzePayload =
+        serde_json::from_value(body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let job_id = crate::enqueue_analysis(state.app.clone(), state.app.state::<AppState>(), payload);
+    Ok((StatusCode::ACCEPTED, Json(AnalyzeResponse { job_id })))
+}
+
+/// 工作狀態；完成後附上完整的分析結果。
+async fn report(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<JobId>,
+) -> Result<Json<JobDetail>, ApiError> {
+    Ok(Json(state.app.state::<AppState>().jobs.status(id)?))
+}
+
+enum ApiError {
+    BadRequest(String),
+    App(Error),
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        ApiError::App(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::BadRequest(message) => (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "message": message })),
+            )
+                .into_response(),
+            // 與 Tauri 命令相同，錯誤以 code/context 序列化
+            ApiError::App(error) => {
+                let status = match error {
+                    Error::JobNotFound(_) => StatusCode::NOT_FOUND,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, Json(error)).into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use ptt_analyzer_core::config::LoggingConfig;
+use std::path::PathBuf;
+use tauri::Manager;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+const LOG_FILE_PREFIX: &str = "ptt-analyzer";
+/// 最多保留幾天份的日誌檔。
+const MAX_LOG_FILES: usize = 7;
+
+/// 效能追蹤檔的檔名前綴，檔案與日誌放在同一個目錄。
+#[cfg(feature = "instrumentation")]
+const TRACE_FILE_PREFIX: &str = "trace";
+
+/// 保存非同步寫檔的 guard，必須存活到程式結束，否則尾端的日誌會遺失。
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+    /// 結束時寫完 Chrome trace 檔的結尾
+    #[cfg(feature = "instrumentation")]
+    _trace: Option<tracing_chrome::FlushGuard>,
+}
+
+/// 日誌目錄 (app log 目錄)。
+pub fn log_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_log_dir().ok()
+}
+
+/// 初始化 tracing：每日輪替寫入 app log 目錄，並同時輸出到 stderr。
+/// 環境變數 `RUST_LOG` 優先於設定檔中的等級。
+/// 啟用 `instrumentation` feature 時，另將各階段的 span 寫成 Chrome trace 檔
+/// (`trace-<unix 時間>.json`)，可用 Perfetto 或 chrome://tracing 以火焰圖檢視。
+pub fn init(app: &tauri::AppHandle, config: &LoggingConfig) -> LogGuard {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.level))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let file_appender = log_dir(app).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .ok()
+    });
+
+    let (file_layer, file_guard) = match file_appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer().with_ansi(false).with_writer(writer);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer);
+
+    #[cfg(feature = "instrumentation")]
+    let (registry, trace_guard) = {
+        let (trace_layer, trace_guard) = match log_dir(app) {
+            Some(dir) => {
+                let path = dir.join(format!(
+                    "{}-{}.json",
+                    TRACE_FILE_PREFIX,
+                    ptt_analyzer_core::diagnostics::unix_now()
+                ));
+                let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                    .file(path)
+                    .trace_style(tracing_chrome::TraceStyle::Async)
+                    .include_args(true)
+                    .build();
+                (Some(layer), Some(guard))
+            }
+            None => (None, None),
+        };
+        (registry.with(trace_layer), trace_guard)
+    };
+
+    let _ = registry.try_init();
+
+    LogGuard {
+        _file: file_guard,
+        #[cfg(feature = "instrumentation")]
+        _trace: trace_guard,
+    }
+}
@@ -1,250 +1,837 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod background;
 mod config;
-mod error;
-mod scraper;
+mod deep_link;
+mod diagnostics;
+mod digest;
+mod drop;
+mod hot_articles;
+#[cfg(feature = "http-api")]
+mod http_api;
+mod logging;
+mod menu;
+mod monitoring;
+mod notifications;
+mod panic;
+mod push_watch;
+mod quick_analyze;
+mod scheduler;
+mod shortcut;
+mod state;
+mod tray;
+mod updater;
 
-// (新增) 引入 AppConfig 以在 Payload 中使用
-use config::AppConfig;
-use error::Error;
-use futures::stream::{self, StreamExt};
+use ptt_analyzer_core::alerts::{AlertEvent, AlertRule, AlertStore};
+use ptt_analyzer_core::analysis::{
+    self, AnalysisOptions, AnalyzePayload, RetryPayload, RetryResult,
+};
+use ptt_analyzer_core::backoff::RateLimiters;
+use ptt_analyzer_core::config::{BackgroundConfig, ConcurrentAnalysisPolicy, WebhookConfig};
+use ptt_analyzer_core::diagnostics::DiagnosticsState;
+use ptt_analyzer_core::digest::{Digest, DigestStore};
+use ptt_analyzer_core::disk_cache::{self, DiskCache};
+use ptt_analyzer_core::domain::{BoardName, UserId};
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::fetcher::{Fetcher, FixtureFetcher, HttpFetcher};
+use ptt_analyzer_core::groups::{UserGroup, UserGroupStore};
+use ptt_analyzer_core::history::{
+    self, AppearanceHistory, ArticleTimeline, FastResponder, SimilarAccount, UserHistory,
+};
+use ptt_analyzer_core::jobs::{self, EventSink, JobControl, JobEmitter, JobId, JobManager};
+use ptt_analyzer_core::notes::{NoteStore, UserNote};
+use ptt_analyzer_core::profile::{self, UserProfile};
+use ptt_analyzer_core::providers::UserStatsCache;
+use ptt_analyzer_core::report::{self, AnalysisResult, ResultPage, ResultSummary};
+use ptt_analyzer_core::schedules::{Schedule, ScheduleStore, ScheduleTarget};
+use ptt_analyzer_core::trends::{TrendPoint, UserTrendStore};
+use ptt_analyzer_core::watchlists::{Watchlist, WatchlistStore};
+use ptt_analyzer_core::webhooks::{self, WebhookEvent};
+use ptt_analyzer_core::{article_url, batch, health, scraper, selectors};
 use serde::{Deserialize, Serialize};
+use state::AppState;
 use std::collections::HashMap;
-use tauri::Emitter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
 
-const CONCURRENT_LIMIT: usize = 10;
+/// 隱藏的離線模式：設定為 fixture 目錄時，所有 HTTP 請求改讀預先存下的 HTML 檔，
+/// 且不使用磁碟上的快取，讓完整的分析流程 (含命令與事件) 不連網也能重現相同結果。
+const OFFLINE_FIXTURES_ENV: &str = "PTT_ANALYZER_FIXTURES";
 
-// --- 核心資料結構 (Core Data Structures) ---
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct PttWebData {
-    board_comments: HashMap<String, u32>,
-    total_comments: u32,
+/// 將分析流程的事件轉發給前端視窗。
+struct TauriEvents(tauri::AppHandle);
+
+impl EventSink for TauriEvents {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let _ = self.0.emit(event, payload);
+    }
+}
+
+fn job_emitter(app: &tauri::AppHandle, job_id: JobId) -> JobEmitter {
+    JobEmitter::new(Arc::new(TauriEvents(app.clone())), job_id)
+}
+
+// --- Tauri 命令 (Tauri Command) ---
+#[tauri::command]
+async fn analyze_ptt_article(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    // (修改) 整個 command 的參數改為接收單一的 payload
+    payload: AnalyzePayload,
+) -> Result<AnalysisResult, Error> {
+    let (job_id, control) = state.jobs.register(&payload.url);
+    let policy = payload.options.config.concurrent_analysis;
+    execute_job(app, job_id, control, payload, policy).await
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct UserReportData {
-    user: String,
-    article_comments: u32,
-    board_comments: HashMap<String, u32>,
-    total_comments: u32,
+/// 將分析加入佇列後立即回傳工作 ID，工作會在背景依序執行。
+#[tauri::command]
+fn enqueue_analysis(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    payload: AnalyzePayload,
+) -> JobId {
+    let (job_id, control) = state.jobs.register(&payload.url);
+    tauri::async_runtime::spawn(execute_job(
+        app,
+        job_id,
+        control,
+        payload,
+        ConcurrentAnalysisPolicy::Queue,
+    ));
+    job_id
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// `enqueue_url_list` 的參數：網址清單檔路徑，以及套用到每篇文章的分析選項。
+#[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AnalysisResult {
-    metadata: ReportMetadata,
-    highlighted_data: Vec<UserReportData>,
-    normal_data: Vec<UserReportData>,
+struct UrlListPayload {
+    path: String,
+    #[serde(flatten)]
+    options: AnalysisOptions,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ReportMetadata {
-    title: String,
+struct QueuedArticle {
+    line: usize,
     url: String,
-    board: String,
-    filter_types: Vec<String>,
-    keywords: Option<Vec<String>>,
-    highlight_condition: Option<String>,
+    job_id: JobId,
 }
 
-// --- Tauri 事件 Payload ---
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct ProgressPayload {
-    current: usize,
-    total: usize,
-    user_id: String,
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UrlListResult {
+    queued: Vec<QueuedArticle>,
+    duplicates: Vec<batch::DuplicateLine>,
+    errors: Vec<batch::LineError>,
 }
 
-// (新增) 定義一個結構體來接收來自前端的完整 payload
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct AnalyzePayload {
-    url: String,
-    filter_types: Vec<String>,
-    keywords: Option<Vec<String>>,
-    highlight_condition: Option<String>,
-    config: AppConfig, // 包含前端傳來的設定
+/// 讀取網址清單檔，驗證並去除重複後逐篇加入分析佇列，無效的行會連同行號回報。
+#[tauri::command]
+fn enqueue_url_list(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    payload: UrlListPayload,
+) -> Result<UrlListResult, Error> {
+    let parsed = batch::read_url_list(std::path::Path::new(&payload.path))?;
+
+    let queued = parsed
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let job_id = enqueue_analysis(
+                app.clone(),
+                state.clone(),
+                AnalyzePayload {
+                    url: entry.url.clone(),
+                    options: payload.options.clone(),
+                },
+            );
+            QueuedArticle {
+                line: entry.line,
+                url: entry.url,
+                job_id,
+            }
+        })
+        .collect();
+
+    Ok(UrlListResult {
+        queued,
+        duplicates: parsed.duplicates,
+        errors: parsed.errors,
+    })
 }
 
-// --- Tauri 命令 (Tauri Command) ---
 #[tauri::command]
-async fn analyze_ptt_article(
+fn list_jobs(state: tauri::State<'_, AppState>) -> Vec<jobs::JobSummary> {
+    state.jobs.list()
+}
+
+#[tauri::command]
+fn job_status(state: tauri::State<'_, AppState>, job_id: JobId) -> Result<jobs::JobDetail, Error> {
+    state.jobs.status(job_id)
+}
+
+#[tauri::command]
+fn cancel_job(state: tauri::State<'_, AppState>, job_id: JobId) -> Result<(), Error> {
+    state.jobs.cancel(job_id)
+}
+
+/// 取得已完成工作的結果摘要 (不含資料列)，資料列數量龐大時搭配 `get_result_page` 使用。
+#[tauri::command]
+fn get_result_summary(
+    state: tauri::State<'_, AppState>,
+    job_id: JobId,
+) -> Result<ResultSummary, Error> {
+    state.jobs.with_result(job_id, AnalysisResult::summary)
+}
+
+/// 分頁取得已完成工作的資料列，讓前端以虛擬捲動顯示大型結果。
+/// `grouped` 為 true 時以群組檢視顯示，同一帳號群組的資料列合併為一列。
+#[tauri::command]
+fn get_result_page(
+    state: tauri::State<'_, AppState>,
+    job_id: JobId,
+    offset: usize,
+    limit: usize,
+    grouped: Option<bool>,
+) -> Result<ResultPage, Error> {
+    if grouped.unwrap_or(false) {
+        let groups = state.user_groups.list();
+        return state
+            .jobs
+            .with_result(job_id, |result| result.grouped_page(&groups, offset, limit));
+    }
+    state
+        .jobs
+        .with_result(job_id, |result| result.page(offset, limit))
+}
+
+/// 匯入先前儲存的報告檔 (舊版格式會先轉換)，登記為已完成的工作，
+/// 之後即可與新的分析結果一樣以 `get_result_page` 瀏覽。
+#[tauri::command]
+fn import_report(state: tauri::State<'_, AppState>, path: String) -> Result<ResultSummary, Error> {
+    let bytes = std::fs::read(&path).map_err(|e| Error::LocalFile {
+        path: path.clone(),
+        reason: e.to_string(),
+    })?;
+    let result = report::load_report(&bytes)?;
+    Ok(register_report(&state.jobs, result))
+}
+
+/// 將讀取的報告登記為已完成的工作，之後即可以工作 ID 瀏覽。
+fn register_report(jobs: &JobManager, mut result: AnalysisResult) -> ResultSummary {
+    let (job_id, _control) = jobs.register(&result.metadata.url);
+    result.job_id = job_id;
+    let summary = result.summary();
+    jobs.finish(job_id, &Ok(result));
+    summary
+}
+
+/// 在新視窗開啟已完成工作 (或匯入的報告) 的結果，方便並排比較兩次分析。
+/// 新視窗的網址帶有 `reportId` 參數，前端以此載入結果；同一份報告的視窗已開啟時改為聚焦該視窗。
+/// 在同步命令中建立視窗會在 Windows 上 deadlock，因此為 async 命令。
+#[tauri::command]
+async fn open_report_window(
     app: tauri::AppHandle,
-    // (修改) 整個 command 的參數改為接收單一的 payload
+    state: tauri::State<'_, AppState>,
+    report_id: JobId,
+) -> Result<(), Error> {
+    let title = state
+        .jobs
+        .with_result(report_id, |result| result.metadata.title.clone())?;
+    let label = format!("report-{}", report_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        return window
+            .set_focus()
+            .map_err(|e| Error::Internal(e.to_string()));
+    }
+    let window = tauri::WebviewWindowBuilder::new(
+        &app,
+        label,
+        tauri::WebviewUrl::App(format!("index.html?reportId={}", report_id).into()),
+    )
+    .title(format!("{} - PTT 留言分析器", title))
+    .inner_size(1280.0, 720.0)
+    .build()
+    .map_err(|e| Error::Internal(e.to_string()))?;
+    // 視窗開啟期間保留報告，之後的工作結束時才不會被清除而無法分頁瀏覽
+    state.jobs.pin(report_id)?;
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            handle.state::<AppState>().jobs.unpin(report_id);
+        }
+    });
+    Ok(())
+}
+
+/// 暫停工作：尚未開始的 pttweb.cc 查詢會等待恢復，已完成的結果保留。
+#[tauri::command]
+fn pause_job(state: tauri::State<'_, AppState>, job_id: JobId) -> Result<(), Error> {
+    state.jobs.pause(job_id)
+}
+
+#[tauri::command]
+fn resume_job(state: tauri::State<'_, AppState>, job_id: JobId) -> Result<(), Error> {
+    state.jobs.resume(job_id)
+}
+
+/// 執行一個已登記的分析工作：等待執行權、執行分析並更新工作狀態與發送結束事件。
+async fn execute_job(
+    app: tauri::AppHandle,
+    job_id: JobId,
+    control: JobControl,
     payload: AnalyzePayload,
+    policy: ConcurrentAnalysisPolicy,
 ) -> Result<AnalysisResult, Error> {
-    // (修改) 從 payload 中解構出所需變數
-    let url = payload.url;
-    let filter_types = payload.filter_types;
-    let keywords = payload.keywords;
-    let highlight_condition = payload.highlight_condition;
-    let app_config = payload.config; // 直接使用從前端傳來的設定
-
-    // 步驟 1: 爬取 PTT 文章頁面
-    let article_data = scraper::scrape_ptt_article(&url, &filter_types, &keywords).await?;
-
-    if article_data.user_comment_counts.is_empty() {
-        let metadata = ReportMetadata {
-            title: article_data.title,
-            url,
-            board: article_data.board,
-            filter_types,
-            keywords,
-            highlight_condition,
-        };
-        return Ok(AnalysisResult {
-            metadata,
-            highlighted_data: vec![],
-            normal_data: vec![],
-        });
+    let emitter = job_emitter(&app, job_id);
+    let notify_watchlist_matches = payload.options.config.notifications.watchlist_matches;
+    let origin_options = payload
+        .options
+        .analyze_forwarded_origin
+        .then(|| payload.options.clone());
+    let state = app.state::<AppState>();
+    let result = analysis::execute_job(&state.analysis(), &emitter, control, payload, policy).await;
+    if let Ok(result) = &result {
+        if notify_watchlist_matches {
+            notifications::notify_watchlist_matches(&app, result);
+        }
+        let origin_url = result
+            .metadata
+            .forwarded_from
+            .as_ref()
+            .and_then(|forwarded| forwarded.url.clone());
+        if let (Some(url), Some(mut options)) = (origin_url, origin_options) {
+            // 原文若也是轉錄文章不再往上追
+            options.analyze_forwarded_origin = false;
+            let origin_job_id =
+                enqueue_analysis(app.clone(), app.state(), AnalyzePayload { url, options });
+            tracing::info!(job_id, origin_job_id, "轉錄文章的原文已加入分析佇列");
+        }
+        notifications::notify_alerts(&app, result);
+        notifications::send_webhooks(&app, WebhookEvent::analysis_completed(result));
+        menu::refresh(&app);
     }
+    result
+}
 
-    // (修改) 直接使用來自 payload 的設定，不再從檔案載入
-    // let app_config = config::load_config(&app); // <--- 移除此行
-    let mut target_boards = app_config.boards.clone(); // 使用傳入的看板列表
-    if !target_boards.contains(&article_data.board) {
-        target_boards.push(article_data.board.clone());
-    }
+/// 只重新查詢指定使用者 (通常是上次分析中失敗的使用者)，不需重新分析整篇文章。
+#[tauri::command]
+async fn retry_failed(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    payload: RetryPayload,
+) -> Result<RetryResult, Error> {
+    let label = analysis::retry_job_label(&state.jobs, &payload);
+    let (job_id, control) = state.jobs.register(&label);
+    let emitter = job_emitter(&app, job_id);
+    analysis::retry_users(&state.analysis(), &emitter, control, payload).await
+}
 
-    // 步驟 2: 併發查詢 pttweb.cc
-    let users_to_scrape: Vec<_> = article_data.user_comment_counts.keys().cloned().collect();
-    let total_users = users_to_scrape.len();
-
-    let report_futures = stream::iter(users_to_scrape.into_iter().enumerate())
-        .map(|(i, user)| {
-            let app_handle = app.clone();
-            let target_boards_clone = target_boards.clone();
-            async move {
-                let payload = ProgressPayload {
-                    current: i + 1,
-                    total: total_users,
-                    user_id: user.clone(),
-                };
-                let _ = app_handle.emit("SCRAPE_PROGRESS", payload);
-
-                match scraper::scrape_ptt_web(&user, &target_boards_clone).await {
-                    Ok(ptt_web_data) => (user, Some(ptt_web_data)),
-                    Err(Error::PttWebUserNotFound(_)) => (user, None),
-                    Err(e) => {
-                        println!("查詢 {} 時發生非預期錯誤: {:?}", user, e);
-                        (user, None)
-                    }
-                }
-            }
-        })
-        .buffer_unordered(CONCURRENT_LIMIT);
+/// 前端收到拖放的文字 (連結或網址) 時轉交，驗證後加入分析佇列。
+#[tauri::command]
+fn analyze_dropped_text(app: tauri::AppHandle, text: String) -> drop::DropResult {
+    drop::handle_text(&app, &text)
+}
 
-    let ptt_web_results: Vec<_> = report_futures.collect().await;
+/// 將網址欄的輸入 (文章網址或 `#AID (看板)` 形式的文章代碼) 轉換為標準文章網址。
+#[tauri::command]
+fn resolve_article_input(input: String) -> Result<String, Error> {
+    article_url::normalize_article_url(&input).map(|(_, url)| url)
+}
 
-    let mut report_data: Vec<UserReportData> = ptt_web_results
-        .into_iter()
-        .map(|(user, ptt_web_data_option)| {
-            let (board_comments, total_comments) = ptt_web_data_option
-                .map(|data| (data.board_comments, data.total_comments))
-                .unwrap_or_else(|| (HashMap::new(), 0));
-
-            UserReportData {
-                user: user.clone(),
-                article_comments: *article_data.user_comment_counts.get(&user).unwrap_or(&0),
-                board_comments,
-                total_comments,
-            }
-        })
-        .collect();
+/// 檢查 ptt.cc 與 pttweb.cc 的連線狀況與延遲，讓前端在分析前提醒使用者。
+#[tauri::command]
+async fn health_check() -> health::HealthReport {
+    health::check_all().await
+}
 
-    // 步驟 3: 排序資料
-    report_data.sort_by(|a, b| {
-        let val_a: u32;
-        let val_b: u32;
+/// 回傳版本、建置資訊、設定與快取位置、進行中的工作與近期錯誤統計。
+#[tauri::command]
+fn get_diagnostics(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> diagnostics::DiagnosticsReport {
+    diagnostics::collect(&app, &state.diagnostics)
+}
 
-        match app_config.sorting.sort_by.as_str() {
-            "本文留言數" => {
-                val_a = a.article_comments;
-                val_b = b.article_comments;
-            }
-            "生涯總留言數" => {
-                val_a = a.total_comments;
-                val_b = b.total_comments;
-            }
-            board_name => {
-                val_a = *a.board_comments.get(board_name).unwrap_or(&0);
-                val_b = *b.board_comments.get(board_name).unwrap_or(&0);
-            }
-        }
+/// 回傳文章快照與報告紀錄的磁碟快取用量。
+#[tauri::command]
+fn get_cache_stats(state: tauri::State<'_, AppState>) -> disk_cache::CacheStats {
+    state.disk_cache.stats()
+}
 
-        if app_config.sorting.order == "desc" {
-            val_b.cmp(&val_a)
-        } else {
-            val_a.cmp(&val_b)
-        }
+/// 重新載入 app data 目錄中的 selectors 覆寫檔，網站改版時不必重新啟動即可套用修正。
+#[tauri::command]
+fn reload_selectors(app: tauri::AppHandle) -> Result<(), Error> {
+    selectors::reload(selectors_path(&app).as_deref())
+}
+
+/// selectors 覆寫檔的完整路徑。
+fn selectors_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(selectors::SELECTORS_FILE_NAME))
+}
+
+#[tauri::command]
+fn list_watchlists(state: tauri::State<'_, AppState>) -> Vec<Watchlist> {
+    state.watchlists.list()
+}
+
+/// 建立新的觀察名單；之後的分析結果會標記出現在名單上的留言者。
+#[tauri::command]
+fn create_watchlist(state: tauri::State<'_, AppState>, name: String) -> Result<Watchlist, Error> {
+    state.watchlists.create(&name)
+}
+
+#[tauri::command]
+fn add_user(
+    state: tauri::State<'_, AppState>,
+    watchlist: String,
+    user_id: UserId,
+) -> Result<Watchlist, Error> {
+    state.watchlists.add_user(&watchlist, user_id)
+}
+
+#[tauri::command]
+fn remove_user(
+    state: tauri::State<'_, AppState>,
+    watchlist: String,
+    user_id: UserId,
+) -> Result<Watchlist, Error> {
+    state.watchlists.remove_user(&watchlist, &user_id)
+}
+
+/// 所有帳號的備註與標籤。
+#[tauri::command]
+fn get_user_notes(state: tauri::State<'_, AppState>) -> HashMap<UserId, UserNote> {
+    state.notes.all()
+}
+
+/// 設定帳號的備註與標籤，之後的分析結果會附上；兩者皆為空時移除備註。
+#[tauri::command]
+fn set_user_note(
+    state: tauri::State<'_, AppState>,
+    user_id: UserId,
+    note: String,
+    tags: Vec<String>,
+) -> Result<(), Error> {
+    state.notes.set(user_id, UserNote { note, tags })
+}
+
+/// 從 pttweb.cc 取得使用者的完整資料 (所有看板、近期留言與發文)，供詳細資料面板顯示。
+#[tauri::command]
+async fn get_user_profile(
+    state: tauri::State<'_, AppState>,
+    user_id: UserId,
+) -> Result<UserProfile, Error> {
+    profile::fetch_user_profile(
+        &user_id,
+        state.http_client.as_ref(),
+        &state.rate_limiters.pttweb,
+    )
+    .await
+}
+
+/// 使用者出現過的所有已分析文章，例如「在最近 12 次分析中出現 9 次」。
+#[tauri::command]
+fn query_user_history(state: tauri::State<'_, AppState>, user: UserId) -> UserHistory {
+    state.history.query(&user)
+}
+
+/// 在過去分析過的文章中，經常與此使用者在相近時間留言的帳號 (可能為分身或同集團)，附上同時出現的文章。
+/// `window_minutes` 未指定時為 10 分鐘。
+#[tauri::command]
+fn find_similar_accounts(
+    state: tauri::State<'_, AppState>,
+    user: UserId,
+    window_minutes: Option<u32>,
+) -> Vec<SimilarAccount> {
+    state.history.find_similar(
+        &user,
+        window_minutes.unwrap_or(history::DEFAULT_CO_OCCURRENCE_WINDOW_MINUTES),
+    )
+}
+
+/// 在過去分析過的文章中，幾乎每次都在 `author` 發文後很快留言的帳號，附上各篇的回應時間。
+/// `within_minutes` 未指定時為 2 分鐘。
+#[tauri::command]
+fn find_fast_responders(
+    state: tauri::State<'_, AppState>,
+    author: UserId,
+    within_minutes: Option<u32>,
+) -> Vec<FastResponder> {
+    state.history.fast_responders(
+        &author,
+        within_minutes.unwrap_or(history::DEFAULT_FAST_RESPONSE_MINUTES),
+    )
+}
+
+/// 同一篇文章每次分析 (包含追蹤新推文) 時的留言者數與高亮使用者的變化。
+#[tauri::command]
+fn get_article_timeline(state: tauri::State<'_, AppState>, url: String) -> ArticleTimeline {
+    // 與分析時相同，以正規化後的網址查詢
+    let url = article_url::resolve_input(&url)
+        .map(|(_, url)| url)
+        .unwrap_or(url);
+    state.history.article_timeline(&url)
+}
+
+/// 使用者每次被查詢時的生涯總留言數與在 `board` 的留言數，用於找出短時間內突然活躍的帳號。
+#[tauri::command]
+fn get_user_trend(
+    state: tauri::State<'_, AppState>,
+    user: UserId,
+    board: BoardName,
+) -> Vec<TrendPoint> {
+    state.trends.trend(&user, &board)
+}
+
+#[tauri::command]
+fn list_user_groups(state: tauri::State<'_, AppState>) -> Vec<UserGroup> {
+    state.user_groups.list()
+}
+
+/// 建立帳號群組 (「同一人/同集團」)，或取代同名群組的成員。
+#[tauri::command]
+fn save_user_group(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    members: Vec<UserId>,
+) -> Result<UserGroup, Error> {
+    state.user_groups.save(&name, members)
+}
+
+#[tauri::command]
+fn delete_user_group(state: tauri::State<'_, AppState>, name: String) -> Result<(), Error> {
+    state.user_groups.delete(&name)
+}
+
+/// 檢查是否有新版本；有新版本時同時發送 `UPDATE_AVAILABLE` 事件。
+#[tauri::command]
+async fn check_for_updates(app: tauri::AppHandle) -> Result<Option<updater::UpdateInfo>, Error> {
+    updater::check(&app).await
+}
+
+/// 下載並安裝新版本，完成後重新啟動 app。
+#[tauri::command]
+async fn install_update(app: tauri::AppHandle) -> Result<(), Error> {
+    updater::install(&app).await
+}
+
+/// 設定頁變更背景執行設定時呼叫，立即更新開機自動啟動與關閉視窗的行為。
+#[tauri::command]
+fn apply_background_config(app: tauri::AppHandle, config: BackgroundConfig) -> Result<(), Error> {
+    background::apply(&app, &config)
+}
+
+/// 允許以瀏覽器開啟的網站，避免命令被用來開啟任意網址。
+const OPENABLE_HOSTS: &[&str] = &["www.pttweb.cc"];
+
+/// 以預設瀏覽器開啟使用者的 pttweb.cc 頁面。
+#[tauri::command]
+fn open_user_page(app: tauri::AppHandle, user_id: UserId) -> Result<(), Error> {
+    if !user_id.is_valid() {
+        return Err(Error::InvalidUserId(user_id.to_string()));
+    }
+    open_external_url(&app, &format!("https://www.pttweb.cc/user/{}", user_id))
+}
+
+/// 只有 https 且主機在 [`OPENABLE_HOSTS`] 中的網址才會交給瀏覽器。
+fn open_external_url(app: &tauri::AppHandle, url: &str) -> Result<(), Error> {
+    let allowed = tauri::Url::parse(url).is_ok_and(|parsed| {
+        parsed.scheme() == "https"
+            && parsed
+                .host_str()
+                .is_some_and(|host| OPENABLE_HOSTS.contains(&host))
     });
+    if !allowed {
+        return Err(Error::UrlNotAllowed(url.to_string()));
+    }
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| Error::Internal(e.to_string()))
+}
 
-    // 步驟 4: 處理高亮邏輯
-    let (highlighted_data, normal_data) = if let Some(condition) =
-        highlight_condition.as_ref().filter(|s| !s.is_empty())
-    {
-        let parts: Vec<&str> = condition.split(',').collect();
-        if parts.len() == 3 {
-            let hl_board = parts[0].trim();
-            let operator = parts[1].trim();
-            let value_str = parts[2].trim();
-            let is_percentage = value_str.ends_with('%');
-            let threshold = value_str
-                .trim_end_matches('%')
-                .parse::<f64>()
-                .unwrap_or(-1.0);
-
-            if threshold >= 0.0 {
-                report_data.into_iter().partition(|user| {
-                    let board_comments = *user.board_comments.get(hl_board).unwrap_or(&0) as f64;
-                    let total_comments = user.total_comments as f64;
-                    let value_to_compare = if is_percentage && total_comments > 0.0 {
-                        (board_comments / total_comments) * 100.0
-                    } else {
-                        board_comments
-                    };
-
-                    match operator {
-                        "<" => value_to_compare < threshold,
-                        "<=" => value_to_compare <= threshold,
-                        ">" => value_to_compare > threshold,
-                        ">=" => value_to_compare >= threshold,
-                        "==" => (value_to_compare - threshold).abs() < 1e-9,
-                        _ => false,
-                    }
-                })
-            } else {
-                (vec![], report_data)
-            }
-        } else {
-            (vec![], report_data)
-        }
-    } else {
-        (vec![], report_data)
-    };
-
-    let metadata = ReportMetadata {
-        title: article_data.title,
-        url,
-        board: article_data.board,
-        filter_types,
-        keywords,
-        highlight_condition,
-    };
-
-    Ok(AnalysisResult {
-        metadata,
-        highlighted_data,
-        normal_data,
-    })
+/// 追蹤已完成分析的文章，每 `interval_secs` 秒 (預設 60) 檢查一次新推文並更新該份報告。
+/// `options` 應與原本分析時相同，新推文才會套用同樣的篩選條件。
+#[tauri::command]
+fn watch_article(
+    app: tauri::AppHandle,
+    report_id: JobId,
+    interval_secs: Option<u64>,
+    options: AnalysisOptions,
+) -> Result<(), Error> {
+    push_watch::start(
+        &app,
+        report_id,
+        interval_secs.unwrap_or(push_watch::DEFAULT_INTERVAL_SECS),
+        options,
+    )
+}
+
+#[tauri::command]
+fn unwatch_article(state: tauri::State<'_, AppState>, report_id: JobId) {
+    push_watch::stop(&state, report_id);
+}
+
+#[tauri::command]
+fn list_watched_articles(state: tauri::State<'_, AppState>) -> Vec<JobId> {
+    state.push_watches.list()
+}
+
+/// 監控面板的狀態總覽：排程、追蹤中的文章、熱門文章監看與每日摘要的上次與下次執行時間及結果。
+#[tauri::command]
+fn get_monitoring_status(state: tauri::State<'_, AppState>) -> monitoring::MonitoringStatus {
+    monitoring::status(&state)
+}
+
+/// 發送測試訊息到 webhook (可以是尚未儲存的設定)，失敗時回傳錯誤讓使用者修正網址。
+#[tauri::command]
+async fn test_webhook(webhook: WebhookConfig) -> Result<(), Error> {
+    webhooks::send(&webhook, &WebhookEvent::Test).await
+}
+
+#[tauri::command]
+fn list_alert_rules(state: tauri::State<'_, AppState>) -> Vec<AlertRule> {
+    state.alerts.rules()
+}
+
+/// 新增警示規則，或以 `id` 修改既有的規則；`conditions` 的格式與高亮條件相同，需全部符合才觸發。
+#[tauri::command]
+fn save_alert_rule(
+    state: tauri::State<'_, AppState>,
+    id: Option<u64>,
+    name: String,
+    conditions: Vec<String>,
+) -> Result<AlertRule, Error> {
+    state.alerts.save_rule(id, name, conditions)
+}
+
+#[tauri::command]
+fn delete_alert_rule(state: tauri::State<'_, AppState>, id: u64) -> Result<(), Error> {
+    state.alerts.delete_rule(id)
+}
+
+/// 觸發過的警示，最近的在前。
+#[tauri::command]
+fn get_alert_history(state: tauri::State<'_, AppState>) -> Vec<AlertEvent> {
+    state.alerts.history()
+}
+
+/// 最近產生的每日摘要，最新的在前。
+#[tauri::command]
+fn list_digests(state: tauri::State<'_, AppState>) -> Vec<Digest> {
+    state.digests.list()
+}
+
+/// 立即產生過去 24 小時的摘要 (不需等到設定的時間)。
+#[tauri::command]
+fn generate_digest(state: tauri::State<'_, AppState>) -> Digest {
+    state.digests.generate(&state.history, &state.alerts)
+}
+
+#[tauri::command]
+fn list_schedules(state: tauri::State<'_, AppState>) -> Vec<Schedule> {
+    state.schedules.list()
+}
+
+/// 新增每隔 `interval_minutes` 分鐘分析一次的排程；`options` 未指定時使用執行當下的設定檔。
+#[tauri::command]
+fn create_schedule(
+    state: tauri::State<'_, AppState>,
+    target: ScheduleTarget,
+    interval_minutes: u32,
+    options: Option<AnalysisOptions>,
+) -> Result<Schedule, Error> {
+    state.schedules.create(target, interval_minutes, options)
+}
+
+#[tauri::command]
+fn delete_schedule(state: tauri::State<'_, AppState>, id: u64) -> Result<(), Error> {
+    state.schedules.delete(id)
+}
+
+/// 以系統檔案總管開啟日誌目錄。
+#[tauri::command]
+fn open_log_folder(app: tauri::AppHandle) -> Result<(), Error> {
+    let dir = logging::log_dir(&app)
+        .ok_or_else(|| Error::Internal("log directory unavailable".to_string()))?;
+    std::fs::create_dir_all(&dir).map_err(|e| Error::Storage {
+        path: dir.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| Error::Internal(e.to_string()))
 }
 
 fn main() {
     tauri::Builder::default()
+        // 必須第一個註冊，才能在其他 plugin 初始化前將連結轉交給已在執行的 app
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            quick_analyze::show_main_window(app);
+            quick_analyze::analyze_args(app, argv.get(1..).unwrap_or_default(), Path::new(&cwd));
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_clipboard_manager::init())
-        .invoke_handler(tauri::generate_handler![analyze_ptt_article])
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![background::MINIMIZED_ARG]),
+        ))
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                drop::handle_paths(window.app_handle(), paths);
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                background::on_close_requested(window, api);
+            }
+            _ => {}
+        })
+        .setup(|app| {
+            panic::install_hook(app.handle().clone());
+            let app_config = config::load_config(app.handle());
+            app.manage(logging::init(app.handle(), &app_config.logging));
+            tracing::info!(version = %app.package_info().version, "應用程式啟動");
+            // 覆寫檔有誤時沿用內建的 selectors，不影響啟動
+            if let Err(e) = selectors::reload(selectors_path(app.handle()).as_deref()) {
+                tracing::warn!(error = %e, "無法載入 selectors 覆寫檔");
+            }
+
+            let fixtures_dir = std::env::var_os(OFFLINE_FIXTURES_ENV).map(PathBuf::from);
+            let cache_dir = match fixtures_dir {
+                Some(_) => None,
+                None => app.path().app_cache_dir().ok(),
+            };
+            let http_client: Box<dyn Fetcher> = match fixtures_dir {
+                Some(dir) => {
+                    tracing::warn!(dir = %dir.display(), "離線模式：從 fixture 檔回應所有請求");
+                    Box::new(FixtureFetcher::new(dir))
+                }
+                None => Box::new(HttpFetcher),
+            };
+            let data_dir = app.path().app_data_dir().ok();
+            let disk_cache = DiskCache::new(cache_dir.as_deref());
+            disk_cache.set_max_bytes(app_config.cache_max_mb.saturating_mul(1024 * 1024));
+            let close_to_tray = AtomicBool::new(app_config.background.close_to_tray);
+            let rate_limiters = RateLimiters::default();
+            rate_limiters
+                .quiet_hours
+                .set_config(app_config.quiet_hours.clone());
+            app.manage(AppState {
+                http_client,
+                config: app_config,
+                cache: UserStatsCache::load(cache_dir.as_deref()),
+                disk_cache,
+                jobs: JobManager::default(),
+                rate_limiters,
+                lookups: scraper::UserPageLookups::default(),
+                diagnostics: DiagnosticsState::default(),
+                watchlists: WatchlistStore::load(data_dir.as_deref()),
+                notes: NoteStore::load(data_dir.as_deref()),
+                user_groups: UserGroupStore::load(data_dir.as_deref()),
+                history: AppearanceHistory::load(data_dir.as_deref()),
+                trends: UserTrendStore::load(data_dir.as_deref()),
+                schedules: ScheduleStore::load(data_dir.as_deref()),
+                alerts: AlertStore::load(data_dir.as_deref()),
+                digests: DigestStore::load(data_dir.as_deref()),
+                push_watches: Default::default(),
+                hot_articles: Default::default(),
+                close_to_tray,
+            });
+            if let Err(e) = menu::setup(app.handle()) {
+                tracing::warn!(error = %e, "無法建立應用程式選單");
+            }
+            if let Err(e) = tray::setup(app) {
+                tracing::warn!(error = %e, "無法建立系統匣圖示");
+            }
+            shortcut::setup(
+                app.handle(),
+                &app.state::<AppState>().config.global_shortcut,
+            );
+            deep_link::setup(app.handle());
+            updater::check_in_background(app.handle().clone());
+            scheduler::start(app.handle().clone());
+            hot_articles::start(app.handle(), &app.state::<AppState>().config.hot_articles);
+            digest::start(app.handle(), &app.state::<AppState>().config.digest);
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            quick_analyze::analyze_args(app.handle(), &args, &cwd);
+            background::setup(app.handle(), &app.state::<AppState>().config.background);
+            #[cfg(feature = "http-api")]
+            http_api::start(
+                app.handle().clone(),
+                &app.state::<AppState>().config.http_api,
+            );
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            analyze_ptt_article,
+            enqueue_analysis,
+            enqueue_url_list,
+            analyze_dropped_text,
+            list_jobs,
+            job_status,
+            get_result_summary,
+            get_result_page,
+            import_report,
+            open_report_window,
+            cancel_job,
+            pause_job,
+            resume_job,
+            retry_failed,
+            resolve_article_input,
+            health_check,
+            get_diagnostics,
+            get_cache_stats,
+            reload_selectors,
+            list_watchlists,
+            create_watchlist,
+            add_user,
+            remove_user,
+            get_user_notes,
+            set_user_note,
+            list_user_groups,
+            save_user_group,
+            delete_user_group,
+            query_user_history,
+            find_similar_accounts,
+            find_fast_responders,
+            get_user_trend,
+            get_article_timeline,
+            get_user_profile,
+            watch_article,
+            unwatch_article,
+            list_watched_articles,
+            get_monitoring_status,
+            test_webhook,
+            list_alert_rules,
+            save_alert_rule,
+            delete_alert_rule,
+            get_alert_history,
+            list_digests,
+            generate_digest,
+            list_schedules,
+            create_schedule,
+            delete_schedule,
+            open_user_page,
+            apply_background_config,
+            check_for_updates,
+            install_update,
+            open_log_folder
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
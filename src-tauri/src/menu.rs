@@ -0,0 +1,106 @@
+//! 應用程式選單 (檔案、分析、最近的分析)。「最近的分析」取自留言者出現紀錄中的文章，
+//! 報告仍在磁碟快取中時直接開啟，否則重新分析。
+
+use crate::quick_analyze::{self, Completion};
+use crate::state::AppState;
+use ptt_analyzer_core::report;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{Emitter, Manager};
+
+/// 「最近的分析」最多列出的文章數。
+const RECENT_LIMIT: usize = 10;
+const IMPORT_REPORT_ID: &str = "menu-import-report";
+const ANALYZE_CLIPBOARD_ID: &str = "menu-analyze-clipboard";
+/// 最近的分析的選單項目 ID 為此前綴加上文章網址。
+const RECENT_ID_PREFIX: &str = "menu-recent:";
+/// 選擇「匯入報告」時發送給前端的事件，由前端開啟檔案對話框後呼叫 `import_report`。
+const MENU_IMPORT_REPORT: &str = "MENU_IMPORT_REPORT";
+/// 從最近的分析開啟報告時發送給前端的事件，payload 為登記後的工作 ID。
+const MENU_OPEN_REPORT: &str = "MENU_OPEN_REPORT";
+
+/// 建立選單並處理選單事件。
+pub fn setup(app: &tauri::AppHandle) -> tauri::Result<()> {
+    app.set_menu(build(app)?)?;
+    app.on_menu_event(|app, event| {
+        let id = event.id.as_ref();
+        if let Some(url) = id.strip_prefix(RECENT_ID_PREFIX) {
+            open_recent(app, url);
+            return;
+        }
+        match id {
+            IMPORT_REPORT_ID => {
+                quick_analyze::show_main_window(app);
+                let _ = app.emit(MENU_IMPORT_REPORT, ());
+            }
+            ANALYZE_CLIPBOARD_ID => quick_analyze::analyze_clipboard(app, Completion::ShowWindow),
+            _ => {}
+        }
+    });
+    Ok(())
+}
+
+/// 分析完成後重建選單，讓「最近的分析」包含剛完成的文章。
+pub fn refresh(app: &tauri::AppHandle) {
+    let rebuilt = build(app).and_then(|menu| app.set_menu(menu).map(|_| ()));
+    if let Err(e) = rebuilt {
+        tracing::warn!(error = %e, "無法更新應用程式選單");
+    }
+}
+
+fn build(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let file = Submenu::with_items(
+        app,
+        "檔案",
+        true,
+        &[
+            &MenuItem::with_id(app, IMPORT_REPORT_ID, "匯入報告…", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(app, Some("結束"))?,
+        ],
+    )?;
+    let analyze = Submenu::with_items(
+        app,
+        "分析",
+        true,
+        &[&MenuItem::with_id(
+            app,
+            ANALYZE_CLIPBOARD_ID,
+            "分析剪貼簿網址",
+            true,
+            None::<&str>,
+        )?],
+    )?;
+
+    let recent_articles = app.state::<AppState>().history.recent(RECENT_LIMIT);
+    let recent = Submenu::new(app, "最近的分析", !recent_articles.is_empty())?;
+    for article in recent_articles {
+        recent.append(&MenuItem::with_id(
+            app,
+            format!("{}{}", RECENT_ID_PREFIX, article.url),
+            format!("[{}] {}", article.board, article.title),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    Menu::with_items(app, &[&file, &analyze, &recent])
+}
+
+/// 開啟最近分析過的文章：報告仍在磁碟快取中時登記為已完成的工作並通知前端，否則重新分析。
+fn open_recent(app: &tauri::AppHandle, url: &str) {
+    let state = app.state::<AppState>();
+    let saved = state
+        .history
+        .get(url)
+        .and_then(|article| article.report_key)
+        .and_then(|key| state.disk_cache.get(&key))
+        .and_then(|bytes| report::load_report(&bytes).ok());
+    match saved {
+        Some(result) => {
+            let summary = crate::register_report(&state.jobs, result);
+            quick_analyze::show_main_window(app);
+            let _ = app.emit(MENU_OPEN_REPORT, summary.job_id);
+        }
+        None => quick_analyze::analyze_input(app, url, "最近的分析", Completion::ShowWindow),
+    }
+}
@@ -0,0 +1,75 @@
+//! 監控面板使用的狀態總覽：彙整排程、追蹤新推文、熱門文章監看與每日摘要的執行狀態，
+//! 前端不必自行記錄事件重建這些狀態。
+
+use crate::hot_articles::HotArticleStatus;
+use crate::push_watch::WatchStatus;
+use crate::state::AppState;
+use ptt_analyzer_core::config::QuietHoursMode;
+use ptt_analyzer_core::diagnostics::unix_now;
+use ptt_analyzer_core::digest;
+use ptt_analyzer_core::schedules::{ScheduleRun, ScheduleTarget};
+use serde::Serialize;
+
+/// `get_monitoring_status` 的回傳值，時間皆為 Unix 時間 (秒)。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringStatus {
+    /// 目前所在的安靜時段模式，不在安靜時段時為空
+    pub quiet_hours: Option<QuietHoursMode>,
+    pub schedules: Vec<ScheduleStatus>,
+    pub watches: Vec<WatchStatus>,
+    /// 沒有監看的看板時為空
+    pub hot_articles: Option<HotArticleStatus>,
+    pub digest: DigestStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    pub id: u64,
+    pub target: ScheduleTarget,
+    pub interval_minutes: u32,
+    /// 最近一次執行的時間與結果 (失敗時包含錯誤代碼與訊息)
+    pub last_run: Option<ScheduleRun>,
+    /// 尚未執行過時為空，於排程器下一次檢查時執行
+    pub next_run_at: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestStatus {
+    pub enabled: bool,
+    pub last_generated_at: Option<u64>,
+    /// 未啟用時為空
+    pub next_run_at: Option<u64>,
+}
+
+pub fn status(state: &AppState) -> MonitoringStatus {
+    let interval_factor = state.rate_limiters.quiet_hours.interval_factor();
+    let schedules = state
+        .schedules
+        .list()
+        .into_iter()
+        .map(|schedule| ScheduleStatus {
+            next_run_at: schedule.next_run_at(interval_factor),
+            last_run: schedule.history.last().cloned(),
+            id: schedule.id,
+            target: schedule.target,
+            interval_minutes: schedule.interval_minutes,
+        })
+        .collect();
+    let digest_config = &state.config.digest;
+    MonitoringStatus {
+        quiet_hours: state.rate_limiters.quiet_hours.current_mode(),
+        schedules,
+        watches: state.push_watches.statuses(),
+        hot_articles: state.hot_articles.status(),
+        digest: DigestStatus {
+            enabled: digest_config.enabled,
+            last_generated_at: state.digests.last_generated_at(),
+            next_run_at: digest_config
+                .enabled
+                .then(|| digest::next_run_at(unix_now(), digest_config.hour)),
+        },
+    }
+}
@@ -0,0 +1,88 @@
+use crate::state::AppState;
+use ptt_analyzer_core::report::AnalysisResult;
+use ptt_analyzer_core::webhooks::{self, WebhookEvent};
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 警示規則觸發時發送，payload 為 [`ptt_analyzer_core::alerts::AlertEvent`]。
+pub const ALERT_TRIGGERED: &str = "ALERT_TRIGGERED";
+
+/// 一則通知最多列出的使用者數，其餘以「等 N 人」帶過。
+const MAX_LISTED_USERS: usize = 5;
+
+/// 以使用者定義的警示規則檢查分析結果，每個觸發的規則發送一則事件與系統通知。
+pub fn notify_alerts(app: &tauri::AppHandle, result: &AnalysisResult) {
+    for event in app.state::<AppState>().alerts.evaluate(result) {
+        let mut users = event
+            .users
+            .iter()
+            .take(MAX_LISTED_USERS)
+            .map(|user| user.to_string())
+            .collect::<Vec<_>>()
+            .join("、");
+        if event.users.len() > MAX_LISTED_USERS {
+            users.push_str(&format!(" 等 {} 人", event.users.len()));
+        }
+        let sent = app
+            .notification()
+            .builder()
+            .title(format!("警示：{}", event.rule_name))
+            .body(format!("{}\n{}", event.title, users))
+            .show();
+        if let Err(e) = sent {
+            tracing::warn!(error = %e, "無法發送警示通知");
+        }
+        send_webhooks(app, WebhookEvent::AlertTriggered(event.clone()));
+        let _ = app.emit(ALERT_TRIGGERED, event);
+    }
+}
+
+/// 在背景將事件送到設定中訂閱此種事件的 webhook。
+pub fn send_webhooks(app: &tauri::AppHandle, event: WebhookEvent) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        webhooks::dispatch(&state.config.webhooks, &event).await;
+    });
+}
+
+/// 分析結果中有觀察名單上的留言者時，發送列出文章標題與這些使用者的系統通知。
+pub fn notify_watchlist_matches(app: &tauri::AppHandle, result: &AnalysisResult) {
+    let matches: Vec<String> = result
+        .highlighted_data
+        .iter()
+        .chain(&result.normal_data)
+        .filter(|row| !row.watchlists.is_empty())
+        .map(|row| format!("{} ({})", row.user, row.watchlists.join("、")))
+        .collect();
+    if matches.is_empty() {
+        return;
+    }
+
+    let mut body = format!(
+        "{}\n{}",
+        result.metadata.title,
+        matches
+            .iter()
+            .take(MAX_LISTED_USERS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    if matches.len() > MAX_LISTED_USERS {
+        body.push_str(&format!("\n等 {} 人", matches.len()));
+    }
+
+    let sent = app
+        .notification()
+        .builder()
+        .title(format!(
+            "觀察名單上的 {} 位使用者出現在文章中",
+            matches.len()
+        ))
+        .body(body)
+        .show();
+    if let Err(e) = sent {
+        tracing::warn!(error = %e, "無法發送觀察名單通知");
+    }
+}
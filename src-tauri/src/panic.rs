@@ -0,0 +1,36 @@
+use ptt_analyzer_core::error::panic_message;
+use ptt_analyzer_core::events;
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use tauri::Emitter;
+
+/// `FATAL_ERROR` 事件的 payload。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FatalErrorPayload {
+    message: String,
+    location: Option<String>,
+}
+
+/// 安裝 panic hook：記錄 backtrace 並發送 `FATAL_ERROR` 事件給前端，
+/// 避免 Windows release 版本在崩潰時視窗直接無聲關閉。
+pub fn install_hook(app: tauri::AppHandle) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info.payload());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        let backtrace = Backtrace::force_capture();
+
+        tracing::error!(
+            message = %message,
+            location = ?location,
+            backtrace = %backtrace,
+            "程式發生未預期的錯誤 (panic)"
+        );
+        let _ = app.emit(events::FATAL_ERROR, FatalErrorPayload { message, location });
+
+        default_hook(info);
+    }));
+}
@@ -0,0 +1,196 @@
+//! 追蹤已分析文章的新推文：定期重新抓取文章，只將新增的推文併入既有報告，
+//! 並以 `NEW_PUSHES` 事件 (附上報告的工作 ID) 通知前端。
+
+use crate::state::AppState;
+use ptt_analyzer_core::analysis::{self, AnalysisOptions};
+use ptt_analyzer_core::diagnostics::unix_now;
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::events;
+use ptt_analyzer_core::jobs::{JobError, JobId};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+/// 未指定時的檢查間隔 (秒)。
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+/// 最短的檢查間隔 (秒)，避免對 ptt.cc 發出過多請求。
+const MIN_INTERVAL_SECS: u64 = 15;
+
+/// 追蹤中的一份報告的狀態，時間皆為 Unix 時間 (秒)。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatus {
+    pub report_id: JobId,
+    pub url: String,
+    pub interval_secs: u64,
+    pub started_at: u64,
+    pub last_poll_at: Option<u64>,
+    pub next_poll_at: Option<u64>,
+    /// 上次檢查時新增的留言數
+    pub last_new_comments: Option<u32>,
+    /// 上次檢查失敗的原因，成功後清除
+    pub last_error: Option<JobError>,
+}
+
+struct Watch {
+    id: u64,
+    status: WatchStatus,
+}
+
+/// 追蹤中的報告。每次開始追蹤分配新的編號，追蹤迴圈發現編號不同 (已停止或重新開始) 時結束。
+#[derive(Default)]
+pub struct PushWatches {
+    next_id: AtomicU64,
+    watches: Mutex<HashMap<JobId, Watch>>,
+}
+
+impl PushWatches {
+    /// 回傳新的編號，以及是否取代了原本的追蹤。
+    fn start(&self, report_id: JobId, url: String, interval_secs: u64) -> (u64, bool) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let status = WatchStatus {
+            report_id,
+            url,
+            interval_secs,
+            started_at: unix_now(),
+            last_poll_at: None,
+            next_poll_at: None,
+            last_new_comments: None,
+            last_error: None,
+        };
+        let replaced = self
+            .watches
+            .lock()
+            .unwrap()
+            .insert(report_id, Watch { id, status })
+            .is_some();
+        (id, replaced)
+    }
+
+    /// 回傳是否原本在追蹤中。
+    fn stop(&self, report_id: JobId) -> bool {
+        self.watches.lock().unwrap().remove(&report_id).is_some()
+    }
+
+    pub fn list(&self) -> Vec<JobId> {
+        let mut ids: Vec<JobId> = self.watches.lock().unwrap().keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn statuses(&self) -> Vec<WatchStatus> {
+        let mut statuses: Vec<WatchStatus> = self
+            .watches
+            .lock()
+            .unwrap()
+            .values()
+            .map(|watch| watch.status.clone())
+            .collect();
+        statuses.sort_by_key(|status| status.report_id);
+        statuses
+    }
+
+    fn is_current(&self, report_id: JobId, id: u64) -> bool {
+        self.watches
+            .lock()
+            .unwrap()
+            .get(&report_id)
+            .is_some_and(|watch| watch.id == id)
+    }
+
+    /// 更新追蹤狀態；追蹤已停止或重新開始時不做任何事。
+    fn update_status(&self, report_id: JobId, id: u64, f: impl FnOnce(&mut WatchStatus)) {
+        if let Some(watch) = self.watches.lock().unwrap().get_mut(&report_id) {
+            if watch.id == id {
+                f(&mut watch.status);
+            }
+        }
+    }
+}
+
+/// 停止追蹤並解除報告的保留。
+pub fn stop(state: &AppState, report_id: JobId) {
+    if state.push_watches.stop(report_id) {
+        state.jobs.unpin(report_id);
+    }
+}
+
+/// 開始追蹤 `report_id` 的報告；已在追蹤時以新的選項與間隔重新開始。
+pub fn start(
+    app: &tauri::AppHandle,
+    report_id: JobId,
+    interval_secs: u64,
+    options: AnalysisOptions,
+) -> Result<(), Error> {
+    let state = app.state::<AppState>();
+    let (url, mut snapshot) = state.jobs.with_result(report_id, |result| {
+        (result.metadata.url.clone(), result.comment_counts())
+    })?;
+    let interval_secs = interval_secs.max(MIN_INTERVAL_SECS);
+    // 追蹤期間保留報告，以免之後的工作結束時被清除而無法更新
+    state.jobs.pin(report_id)?;
+    let (watch_id, replaced) = state.push_watches.start(report_id, url, interval_secs);
+    if replaced {
+        // 原本的追蹤已保留過一次
+        state.jobs.unpin(report_id);
+    }
+    let interval = Duration::from_secs(interval_secs);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let emitter = crate::job_emitter(&app, report_id);
+        loop {
+            let state = app.state::<AppState>();
+            let watches = &state.push_watches;
+            let quiet_hours = &state.rate_limiters.quiet_hours;
+            let wait = quiet_hours.background_interval(interval);
+            watches.update_status(report_id, watch_id, |status| {
+                status.next_poll_at = Some(unix_now() + wait.as_secs());
+            });
+            tokio::time::sleep(wait).await;
+            quiet_hours.wait_background().await;
+            if !watches.is_current(report_id, watch_id) {
+                return;
+            }
+            let result =
+                analysis::poll_new_pushes(&state.analysis(), &emitter, &options, &mut snapshot)
+                    .await;
+            watches.update_status(report_id, watch_id, |status| {
+                status.last_poll_at = Some(unix_now());
+                match &result {
+                    Ok(new_pushes) => {
+                        status.last_new_comments = Some(
+                            new_pushes
+                                .as_ref()
+                                .map(|new_pushes| new_pushes.counts.values().sum())
+                                .unwrap_or(0),
+                        );
+                        status.last_error = None;
+                    }
+                    Err(e) => status.last_error = Some(JobError::from(e)),
+                }
+            });
+            match result {
+                Ok(Some(new_pushes)) => {
+                    emitter.emit(events::NEW_PUSHES, new_pushes);
+                    let _ = state.jobs.with_result(report_id, |result| {
+                        crate::notifications::notify_alerts(&app, result)
+                    });
+                }
+                Ok(None) => {}
+                // 報告已從工作佇列中移除，無法再更新
+                Err(e @ (Error::JobNotFound(_) | Error::ResultNotReady(_))) => {
+                    tracing::info!(report_id, error = %e, "停止追蹤文章");
+                    if watches.is_current(report_id, watch_id) {
+                        stop(&state, report_id);
+                    }
+                    return;
+                }
+                Err(e) => tracing::warn!(report_id, error = %e, "檢查新推文失敗"),
+            }
+        }
+    });
+    Ok(())
+}
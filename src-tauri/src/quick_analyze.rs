@@ -0,0 +1,139 @@
+//! 不經過前端表單的快速分析：以設定檔的看板與預設選項在背景分析剪貼簿或連結中的文章網址。
+//! 系統匣、全域快捷鍵、`ptt-analyzer://` 連結與命令列參數共用。
+
+use crate::state::AppState;
+use ptt_analyzer_core::analysis::{AnalysisOptions, AnalyzePayload};
+use ptt_analyzer_core::article_url::{self, ArticleInput};
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::report::AnalysisResult;
+use std::path::Path;
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+
+/// 分析完成時發送給前端的事件，payload 為工作 ID，讓前端切換到該份結果。
+const QUICK_ANALYSIS_FINISHED: &str = "QUICK_ANALYSIS_FINISHED";
+
+/// 分析完成後的提示方式。
+#[derive(Clone, Copy)]
+pub enum Completion {
+    /// 顯示並聚焦主視窗
+    ShowWindow,
+    /// 只發送系統通知，不打斷使用者 (例如正在瀏覽器中看文章)
+    Notify,
+}
+
+/// 讀取剪貼簿並在背景分析其中的文章網址；剪貼簿不是 PTT 文章網址時以系統通知告知。
+pub fn analyze_clipboard(app: &tauri::AppHandle, completion: Completion) {
+    let text = app.clipboard().read_text().unwrap_or_default();
+    analyze_input(app, &text, "剪貼簿", completion);
+}
+
+/// 分析啟動 app 時的命令列參數中的文章 (網址、文章代碼或本機 HTML 檔)，`args` 不含程式本身。
+/// 已在執行時再次啟動 app，參數會轉交給原本的 app，`cwd` 為新程序的工作目錄。
+pub fn analyze_args(app: &tauri::AppHandle, args: &[String], cwd: &Path) {
+    // 選項由各自的功能處理，ptt-analyzer:// 連結由 deep link plugin 轉交
+    let inputs = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-') && !arg.starts_with("ptt-analyzer:"));
+    for input in inputs {
+        let relative = cwd.join(input);
+        let input = if Path::new(input).is_relative() && relative.is_file() {
+            relative.display().to_string()
+        } else {
+            input.clone()
+        };
+        analyze_input(app, &input, "命令列參數", Completion::ShowWindow);
+    }
+}
+
+/// 在背景分析 `input` (文章網址、`#AID (看板)` 形式的文章代碼或本機 HTML 檔)；
+/// 無法辨識時以系統通知告知，`origin` 為通知中顯示的輸入來源。
+pub fn analyze_input(app: &tauri::AppHandle, input: &str, origin: &str, completion: Completion) {
+    analyze_resolved(
+        app,
+        article_url::resolve_input(input.trim()),
+        origin,
+        completion,
+    );
+}
+
+/// 同 [`analyze_input`]，但不接受本機檔案，用於來自外部的輸入 (例如 `ptt-analyzer://` 連結)。
+pub fn analyze_remote_input(
+    app: &tauri::AppHandle,
+    input: &str,
+    origin: &str,
+    completion: Completion,
+) {
+    analyze_resolved(
+        app,
+        article_url::resolve_remote_input(input.trim()),
+        origin,
+        completion,
+    );
+}
+
+fn analyze_resolved(
+    app: &tauri::AppHandle,
+    resolved: Result<(ArticleInput, String), Error>,
+    origin: &str,
+    completion: Completion,
+) {
+    let url = match resolved {
+        Ok((_, url)) => url,
+        Err(e) => {
+            tracing::info!(origin, error = %e, "輸入不是 PTT 文章網址");
+            notify(
+                app,
+                "PTT 留言分析器",
+                &format!("{}內容不是 PTT 文章網址", origin),
+            );
+            return;
+        }
+    };
+
+    let state = app.state::<AppState>();
+    let payload = AnalyzePayload {
+        url,
+        options: AnalysisOptions::from_config(state.config.clone()),
+    };
+    let (job_id, control) = state.jobs.register(&payload.url);
+    let policy = payload.options.config.concurrent_analysis;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(result) = crate::execute_job(app.clone(), job_id, control, payload, policy).await
+        else {
+            return;
+        };
+        let _ = app.emit(QUICK_ANALYSIS_FINISHED, job_id);
+        match completion {
+            Completion::ShowWindow => show_main_window(&app),
+            Completion::Notify => notify_finished(&app, &result),
+        }
+    });
+}
+
+pub fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn notify_finished(app: &tauri::AppHandle, result: &AnalysisResult) {
+    let body = format!(
+        "{}\n{} 位留言者，{} 位符合高亮條件",
+        result.metadata.title,
+        result.highlighted_data.len() + result.normal_data.len(),
+        result.highlighted_data.len()
+    );
+    notify(app, "分析完成", &body);
+}
+
+fn notify(app: &tauri::AppHandle, title: &str, body: &str) {
+    let sent = app.notification().builder().title(title).body(body).show();
+    if let Err(e) = sent {
+        tracing::warn!(error = %e, "無法發送通知");
+    }
+}
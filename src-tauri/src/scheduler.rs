@@ -0,0 +1,148 @@
+//! 排程分析的背景工作：每分鐘檢查一次到期的排程，將文章加入分析佇列。
+//! 報告與手動分析一樣存入磁碟快取，完成後發送 `SCHEDULE_COMPLETED` 事件與系統通知。
+
+use crate::state::AppState;
+use ptt_analyzer_core::analysis::{AnalysisOptions, AnalyzePayload};
+use ptt_analyzer_core::config::ConcurrentAnalysisPolicy;
+use ptt_analyzer_core::error::Error;
+use ptt_analyzer_core::jobs::JobId;
+use ptt_analyzer_core::schedules::{Schedule, ScheduleRunOutcome, ScheduleTarget};
+use ptt_analyzer_core::scraper;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 排程的分析完成時發送給前端的事件，payload 為 [`ScheduleCompletedPayload`]。
+pub const SCHEDULE_COMPLETED: &str = "SCHEDULE_COMPLETED";
+/// 檢查到期排程的間隔。
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCompletedPayload {
+    pub schedule_id: u64,
+    pub job_id: JobId,
+    pub url: String,
+    pub title: String,
+}
+
+/// 處理 app 關閉期間錯過的排程後啟動背景工作，app 結束前持續執行。
+pub fn start(app: tauri::AppHandle) {
+    {
+        let state = app.state::<AppState>();
+        if let Err(e) = state.schedules.catch_up(state.config.missed_schedules) {
+            tracing::warn!(error = %e, "無法儲存錯過的排程紀錄");
+        }
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            // 暫停時段中不取出排程，到期的排程在時段結束後執行
+            state.rate_limiters.quiet_hours.wait_background().await;
+            let factor = state.rate_limiters.quiet_hours.interval_factor();
+            let due = state.schedules.take_due(factor);
+            for schedule in due {
+                tauri::async_runtime::spawn(run(app.clone(), schedule));
+            }
+        }
+    });
+}
+
+/// 執行一次排程：看板排程先找出最新文章，已分析過時略過。
+async fn run(app: tauri::AppHandle, schedule: Schedule) {
+    let state = app.state::<AppState>();
+    let url = match &schedule.target {
+        ScheduleTarget::Article { url } => url.clone(),
+        ScheduleTarget::Board { board } => {
+            match scraper::latest_article_url(board, state.http_client.as_ref()).await {
+                Ok(Some(url)) if schedule.last_url.as_deref() != Some(url.as_str()) => url,
+                Ok(_) => {
+                    record_run(&state, schedule.id, ScheduleRunOutcome::NoNewArticle);
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(schedule = schedule.id, board = %board, error = %e, "無法取得看板的最新文章");
+                    record_run(&state, schedule.id, failed(None, &e));
+                    return;
+                }
+            }
+        }
+    };
+    if let Err(e) = state.schedules.set_last_url(schedule.id, &url) {
+        tracing::warn!(schedule = schedule.id, error = %e, "無法儲存排程紀錄");
+    }
+
+    let payload = AnalyzePayload {
+        url: url.clone(),
+        options: schedule
+            .options
+            .unwrap_or_else(|| AnalysisOptions::from_config(state.config.clone())),
+    };
+    let (job_id, control) = state.jobs.register(&payload.url);
+    tracing::info!(schedule = schedule.id, job_id, url = %url, "執行排程分析");
+    // 排程在背景執行，與手動分析同時發生時一律排隊，不套用設定中的拒絕策略
+    let result = crate::execute_job(
+        app.clone(),
+        job_id,
+        control,
+        payload,
+        ConcurrentAnalysisPolicy::Queue,
+    )
+    .await;
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            record_run(&state, schedule.id, failed(Some(url), &e));
+            return;
+        }
+    };
+    record_run(
+        &state,
+        schedule.id,
+        ScheduleRunOutcome::Completed {
+            job_id,
+            url: url.clone(),
+        },
+    );
+
+    let _ = app.emit(
+        SCHEDULE_COMPLETED,
+        ScheduleCompletedPayload {
+            schedule_id: schedule.id,
+            job_id,
+            url,
+            title: result.metadata.title.clone(),
+        },
+    );
+    let sent = app
+        .notification()
+        .builder()
+        .title("排程分析完成")
+        .body(format!(
+            "{}\n{} 位留言者，{} 位符合高亮條件",
+            result.metadata.title,
+            result.highlighted_data.len() + result.normal_data.len(),
+            result.highlighted_data.len()
+        ))
+        .show();
+    if let Err(e) = sent {
+        tracing::warn!(error = %e, "無法發送排程通知");
+    }
+}
+
+fn failed(url: Option<String>, error: &Error) -> ScheduleRunOutcome {
+    ScheduleRunOutcome::Failed {
+        url,
+        code: error.code().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn record_run(state: &AppState, id: u64, outcome: ScheduleRunOutcome) {
+    if let Err(e) = state.schedules.record_run(id, outcome) {
+        tracing::warn!(schedule = id, error = %e, "無法儲存排程的執行紀錄");
+    }
+}
@@ -0,0 +1,25 @@
+//! 全域快捷鍵：在瀏覽器中看 PTT 時，複製文章網址後按下快捷鍵即在背景分析，完成後以系統通知告知。
+
+use crate::quick_analyze::{self, Completion};
+use ptt_analyzer_core::config::GlobalShortcutConfig;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// 依設定註冊快捷鍵；未啟用時不註冊，快捷鍵格式錯誤或已被其他程式佔用時只記錄警告。
+pub fn setup(app: &tauri::AppHandle, config: &GlobalShortcutConfig) {
+    if !config.enabled {
+        return;
+    }
+    let registered =
+        app.global_shortcut()
+            .on_shortcut(config.shortcut.as_str(), |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    quick_analyze::analyze_clipboard(app, Completion::Notify);
+                }
+            });
+    match registered {
+        Ok(()) => tracing::info!(shortcut = %config.shortcut, "已註冊全域快捷鍵"),
+        Err(e) => {
+            tracing::warn!(shortcut = %config.shortcut, error = %e, "無法註冊全域快捷鍵")
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::hot_articles::HotArticleMonitor;
+use crate::push_watch::PushWatches;
+use ptt_analyzer_core::alerts::AlertStore;
+use ptt_analyzer_core::analysis::AnalysisState;
+use ptt_analyzer_core::backoff::RateLimiters;
+use ptt_analyzer_core::config::AppConfig;
+use ptt_analyzer_core::diagnostics::DiagnosticsState;
+use ptt_analyzer_core::digest::DigestStore;
+use ptt_analyzer_core::disk_cache::DiskCache;
+use ptt_analyzer_core::fetcher::Fetcher;
+use ptt_analyzer_core::groups::UserGroupStore;
+use ptt_analyzer_core::history::AppearanceHistory;
+use ptt_analyzer_core::jobs::JobManager;
+use ptt_analyzer_core::notes::NoteStore;
+use ptt_analyzer_core::providers::UserStatsCache;
+use ptt_analyzer_core::schedules::ScheduleStore;
+use ptt_analyzer_core::scraper;
+use ptt_analyzer_core::trends::UserTrendStore;
+use ptt_analyzer_core::watchlists::WatchlistStore;
+use std::sync::atomic::AtomicBool;
+
+/// app 唯一的 managed state：所有命令共用的連線、設定、快取、工作佇列與限流狀態，
+/// 在啟動時建立一次，命令以 `tauri::State<AppState>` 取得。
+pub struct AppState {
+    /// 所有 HTTP 請求經由此 fetcher；離線模式時改讀 fixture 檔
+    pub http_client: Box<dyn Fetcher>,
+    /// 啟動時載入的設定檔
+    pub config: AppConfig,
+    pub cache: UserStatsCache,
+    pub disk_cache: DiskCache,
+    pub jobs: JobManager,
+    pub rate_limiters: RateLimiters,
+    /// 同一位使用者進行中的 pttweb.cc 查詢，由所有分析共用
+    pub lookups: scraper::UserPageLookups,
+    pub diagnostics: DiagnosticsState,
+    /// 使用者自訂的觀察名單，存放於 app data 目錄
+    pub watchlists: WatchlistStore,
+    /// 使用者對帳號的備註與標籤，存放於 app data 目錄
+    pub notes: NoteStore,
+    /// 使用者認定為同一人或同一集團的帳號群組，存放於 app data 目錄
+    pub user_groups: UserGroupStore,
+    /// 留言者出現過的文章紀錄，存放於 app data 目錄
+    pub history: AppearanceHistory,
+    /// 使用者統計的歷史紀錄，存放於 app data 目錄
+    pub trends: UserTrendStore,
+    /// 定期執行的分析排程，存放於 app data 目錄
+    pub schedules: ScheduleStore,
+    /// 使用者定義的警示規則與觸發紀錄，存放於 app data 目錄
+    pub alerts: AlertStore,
+    /// 最近產生的每日摘要，存放於 app data 目錄
+    pub digests: DigestStore,
+    /// 正在追蹤新推文的報告
+    pub push_watches: PushWatches,
+    /// 熱門文章監看的執行狀態
+    pub hot_articles: HotArticleMonitor,
+    /// 關閉視窗時是否隱藏到系統匣；啟動時取自設定檔，可由 `apply_background_config` 變更
+    pub close_to_tray: AtomicBool,
+}
+
+impl AppState {
+    /// 分析流程需要的共用狀態。
+    pub fn analysis(&self) -> AnalysisState<'_> {
+        AnalysisState {
+            jobs: &self.jobs,
+            diagnostics: &self.diagnostics,
+            rate_limiters: &self.rate_limiters,
+            cache: &self.cache,
+            lookups: &self.lookups,
+            disk_cache: &self.disk_cache,
+            fetcher: self.http_client.as_ref(),
+            watchlists: &self.watchlists,
+            notes: &self.notes,
+            history: &self.history,
+            trends: &self.trends,
+        }
+    }
+}
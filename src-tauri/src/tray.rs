@@ -0,0 +1,41 @@
+//! 系統匣圖示：不必切換到視窗即可分析剪貼簿中的文章網址，完成後再顯示視窗。
+
+use crate::quick_analyze::{self, Completion};
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+
+const ANALYZE_CLIPBOARD_ID: &str = "analyze-clipboard";
+const SHOW_WINDOW_ID: &str = "show-window";
+const QUIT_ID: &str = "quit";
+
+/// 建立系統匣圖示與選單。
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(
+                app,
+                ANALYZE_CLIPBOARD_ID,
+                "分析剪貼簿網址",
+                true,
+                None::<&str>,
+            )?,
+            &MenuItem::with_id(app, SHOW_WINDOW_ID, "顯示視窗", true, None::<&str>)?,
+            &MenuItem::with_id(app, QUIT_ID, "結束", true, None::<&str>)?,
+        ],
+    )?;
+    let mut tray = TrayIconBuilder::new()
+        .menu(&menu)
+        .tooltip("PTT 留言分析器")
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            ANALYZE_CLIPBOARD_ID => quick_analyze::analyze_clipboard(app, Completion::ShowWindow),
+            SHOW_WINDOW_ID => quick_analyze::show_main_window(app),
+            QUIT_ID => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! 自動更新：PTT 或 pttweb.cc 改版時，解析規則的修正需要盡快送到使用者手上。
+//! 啟動時在背景檢查一次，有新版本時發送 `UPDATE_AVAILABLE` 事件，由前端詢問是否安裝。
+
+use ptt_analyzer_core::error::Error;
+use serde::Serialize;
+use tauri::Emitter;
+use tauri_plugin_updater::{Updater, UpdaterExt};
+
+/// 有新版本時發送給前端的事件，payload 為 [`UpdateInfo`]。
+pub const UPDATE_AVAILABLE: &str = "UPDATE_AVAILABLE";
+/// 驗證更新檔簽章的公鑰，發行版建置時以環境變數提供，覆寫 tauri.conf.json 中的空值。
+const UPDATER_PUBKEY: Option<&str> = option_env!("PTT_ANALYZER_UPDATER_PUBKEY");
+
+/// 可安裝的新版本。
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// 發行說明
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+/// 啟動時檢查更新，失敗 (例如離線或未設定公鑰) 時只記錄日誌。
+pub fn check_in_background(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = check(&app).await {
+            tracing::info!(error = %e, "無法檢查更新");
+        }
+    });
+}
+
+/// 檢查是否有新版本，有新版本時發送 [`UPDATE_AVAILABLE`] 事件。
+pub async fn check(app: &tauri::AppHandle) -> Result<Option<UpdateInfo>, Error> {
+    let update = updater(app)?.check().await.map_err(update_error)?;
+    let info = update.map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+    });
+    if let Some(info) = &info {
+        tracing::info!(version = %info.version, "有新版本可安裝");
+        let _ = app.emit(UPDATE_AVAILABLE, info);
+    }
+    Ok(info)
+}
+
+/// 下載並安裝新版本後重新啟動；已是最新版本時不做任何事。
+pub async fn install(app: &tauri::AppHandle) -> Result<(), Error> {
+    let Some(update) = updater(app)?.check().await.map_err(update_error)? else {
+        return Ok(());
+    };
+    tracing::info!(version = %update.version, "下載並安裝新版本");
+    update
+        .download_and_install(|_, _| {}, || {})
+        .await
+        .map_err(update_error)?;
+    app.restart()
+}
+
+fn updater(app: &tauri::AppHandle) -> Result<Updater, Error> {
+    let mut builder = app.updater_builder();
+    if let Some(pubkey) = UPDATER_PUBKEY {
+        builder = builder.pubkey(pubkey);
+    }
+    builder.build().map_err(update_error)
+}
+
+fn update_error(error: tauri_plugin_updater::Error) -> Error {
+    Error::Update(error.to_string())
+}